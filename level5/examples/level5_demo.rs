@@ -47,7 +47,7 @@ fn demo_multilingual_reasoning() {
         "Task: quantum_optimization, Domain: Logistics, Language: Indonesian",
         "id",
         0.94,
-    );
+    ).unwrap();
 
     // Translation step
     meta.log_event(
@@ -56,7 +56,7 @@ fn demo_multilingual_reasoning() {
         "What are quantum computing applications for logistics optimization in Indonesia?",
         "en",
         0.92,
-    );
+    ).unwrap();
 
     // Reasoning in English
     meta.log_event(
@@ -65,7 +65,7 @@ fn demo_multilingual_reasoning() {
         "QAOA and VQE can optimize routing, scheduling, and resource allocation",
         "en",
         0.95,
-    );
+    ).unwrap();
 
     // Retrieval
     meta.log_event(
@@ -74,7 +74,7 @@ fn demo_multilingual_reasoning() {
         "Found 5 papers from ITB and UI on quantum logistics optimization",
         "en",
         0.89,
-    );
+    ).unwrap();
 
     // Validation
     meta.log_event(
@@ -83,7 +83,7 @@ fn demo_multilingual_reasoning() {
         "Confirmed pilot projects in Jakarta and Surabaya",
         "en",
         0.91,
-    );
+    ).unwrap();
 
     // Synthesis in Indonesian
     meta.log_event(
@@ -92,7 +92,7 @@ fn demo_multilingual_reasoning() {
         "Quantum computing digunakan untuk optimasi rute pengiriman dan penjadwalan di Indonesia",
         "id",
         0.96,
-    );
+    ).unwrap();
 
     println!("📊 Reasoning Trace:");
     println!("   • Total steps: {}", meta.get_trace_depth());
@@ -129,7 +129,7 @@ fn demo_contributor_leaderboard() {
             4 => AgentType::Validation,
             _ => AgentType::Synthesis,
         };
-        meta1.log_event(agent, "input", "output", if i % 2 == 0 { "id" } else { "en" }, 0.9);
+        meta1.log_event(agent, "input", "output", if i % 2 == 0 { "id" } else { "en" }, 0.9).unwrap();
     }
     let prov1 = meta1.emit_provenance();
     leaderboard.add_entry(prov1, vec!["id".to_string(), "en".to_string()]);
@@ -138,7 +138,7 @@ fn demo_contributor_leaderboard() {
     println!("Adding contributor: alice_researcher (Multilingual NLP expert)");
     let mut meta2 = MetaAgent::new("alice_researcher", "quantum_backend_v2");
     for i in 0..15 {
-        meta2.log_event(AgentType::Reasoning, "input", "output", "en", 0.88);
+        meta2.log_event(AgentType::Reasoning, "input", "output", "en", 0.88).unwrap();
     }
     let prov2 = meta2.emit_provenance();
     leaderboard.add_entry(prov2, vec!["en".to_string(), "zh".to_string()]);
@@ -153,7 +153,7 @@ fn demo_contributor_leaderboard() {
             2 => AgentType::Action,
             _ => AgentType::Synthesis,
         };
-        meta3.log_event(agent, &format!("unique_{}", i), &format!("result_{}", i), "en", 0.92);
+        meta3.log_event(agent, &format!("unique_{}", i), &format!("result_{}", i), "en", 0.92).unwrap();
     }
     let prov3 = meta3.emit_provenance();
     leaderboard.add_entry(prov3, vec!["en".to_string(), "es".to_string(), "fr".to_string()]);
@@ -163,7 +163,7 @@ fn demo_contributor_leaderboard() {
     for submission in 0..3 {
         let mut meta4 = MetaAgent::new("charlie_dev", "quantum_backend_v1");
         for i in 0..10 {
-            meta4.log_event(AgentType::Reasoning, "input", "output", "en", 0.85);
+            meta4.log_event(AgentType::Reasoning, "input", "output", "en", 0.85).unwrap();
         }
         let prov4 = meta4.emit_provenance();
         leaderboard.add_entry(prov4, vec!["en".to_string()]);
@@ -209,7 +209,7 @@ fn demo_memory_and_provenance() {
             &format!("Detailed output for step {}", i),
             lang,
             confidence,
-        );
+        ).unwrap();
     }
 
     println!("📊 Trace Statistics:");
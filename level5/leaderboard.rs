@@ -0,0 +1,2354 @@
+// leaderboard.rs - Level 5 Contributor Leaderboard
+// Ranks contributors by trace depth, uniqueness, and submission activity
+
+use std::collections::{HashMap, HashSet};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use super::meta_agent::{pair_cosine_similarity, AgentType, ContributorProfile, ProvenanceLog, ProvenanceSigner, SignatureError};
+
+/// Number of most recent submissions compared against the rest for `Improvement`
+const IMPROVEMENT_WINDOW: usize = 5;
+
+/// Size of the two back-to-back submission windows compared for `RecentImprovement`
+const RECENT_IMPROVEMENT_WINDOW: usize = 3;
+
+/// Streak length (days) that earns the full streak share of the combined score
+const STREAK_TARGET_DAYS: f64 = 30.0;
+
+/// Depth per thousand tokens that earns the full efficiency share of the combined score
+const TOKEN_EFFICIENCY_TARGET: f64 = 100.0;
+
+/// Ranking criteria for the leaderboard
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum RankingCriteria {
+    TraceDepth,
+    Uniqueness,
+    Submissions,
+    AvgDepth,
+    Combined,
+    NormalizedSubmissionRate,
+    ReasoningEntropy,
+    TokenEfficiency,
+    Improvement,
+    Streak,
+    OutputDiversity,
+    Tournament,
+    Specialization,
+    RecentImprovement,
+    NetworkCentrality,
+}
+
+impl std::fmt::Display for RankingCriteria {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RankingCriteria::TraceDepth => write!(f, "Trace Depth"),
+            RankingCriteria::Uniqueness => write!(f, "Uniqueness"),
+            RankingCriteria::Submissions => write!(f, "Submissions"),
+            RankingCriteria::AvgDepth => write!(f, "Average Depth"),
+            RankingCriteria::Combined => write!(f, "Combined Score"),
+            RankingCriteria::NormalizedSubmissionRate => write!(f, "Normalized Submission Rate"),
+            RankingCriteria::ReasoningEntropy => write!(f, "Reasoning Entropy"),
+            RankingCriteria::TokenEfficiency => write!(f, "Token Efficiency"),
+            RankingCriteria::Improvement => write!(f, "Improvement"),
+            RankingCriteria::Streak => write!(f, "Streak"),
+            RankingCriteria::OutputDiversity => write!(f, "Output Diversity"),
+            RankingCriteria::Tournament => write!(f, "Tournament Wins"),
+            RankingCriteria::Specialization => write!(f, "Specialization"),
+            RankingCriteria::RecentImprovement => write!(f, "Recent Improvement"),
+            RankingCriteria::NetworkCentrality => write!(f, "Network Centrality"),
+        }
+    }
+}
+
+/// Contributor tier derived from rank position within the leaderboard
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Tier {
+    Diamond,
+    Platinum,
+    Gold,
+    Silver,
+    Bronze,
+}
+
+impl Tier {
+    /// Tier for a 1-based rank among `total` contributors (top 10% Diamond,
+    /// top 25% Platinum, top 50% Gold, top 75% Silver, rest Bronze)
+    pub fn from_rank(rank: usize, total: usize) -> Tier {
+        let position = rank as f64 / total.max(1) as f64;
+        if position <= 0.10 {
+            Tier::Diamond
+        } else if position <= 0.25 {
+            Tier::Platinum
+        } else if position <= 0.50 {
+            Tier::Gold
+        } else if position <= 0.75 {
+            Tier::Silver
+        } else {
+            Tier::Bronze
+        }
+    }
+}
+
+impl std::fmt::Display for Tier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tier::Diamond => write!(f, "Diamond"),
+            Tier::Platinum => write!(f, "Platinum"),
+            Tier::Gold => write!(f, "Gold"),
+            Tier::Silver => write!(f, "Silver"),
+            Tier::Bronze => write!(f, "Bronze"),
+        }
+    }
+}
+
+/// Aggregated statistics for a single contributor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContributorStats {
+    pub contributor_id: String,
+    pub total_submissions: usize,
+    pub trace_depth: usize,
+    /// Deepest weighted trace depth across submissions (0.0 if never recorded);
+    /// depth rankings use it when set and fall back to `trace_depth`
+    #[serde(default)]
+    pub weighted_trace_depth: f64,
+    pub avg_trace_depth: f64,
+    pub uniqueness_score: f64,
+    pub languages_used: Vec<String>,
+    pub backend_used: String,
+    pub provenance_hash: String,
+    pub last_updated: DateTime<Utc>,
+    pub combined_score: f64,
+    pub rank: usize,
+    #[serde(default)]
+    pub normalized_submission_rate: f64,
+    #[serde(default)]
+    pub reasoning_entropy: f64,
+    #[serde(default)]
+    pub total_estimated_tokens: usize,
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub confidence_profile: HashMap<String, f64>,
+    #[serde(default)]
+    pub improvement_score: f64,
+    /// Mean depth of the last 3 submissions minus that of the 3 before them
+    #[serde(default)]
+    pub recent_improvement_rate: f64,
+    /// Consecutive UTC days with a submission, ending on the latest submission day
+    #[serde(default)]
+    pub streak: usize,
+    /// Mean output moving-average type-token ratio across submissions
+    #[serde(default)]
+    pub output_diversity_score: f64,
+    /// Normalized rank from the last rank update (1.0 = best, 0.0 = worst)
+    #[serde(default)]
+    pub percentile_rank: f64,
+    /// Share of other contributors whose sessions build on this one's, as of the last rank update
+    #[serde(default)]
+    pub network_centrality: f64,
+    /// Profile rebuilt from submission history
+    #[serde(default)]
+    pub profile: ContributorProfile,
+}
+
+impl ContributorStats {
+    /// Agent type with the lowest mean confidence
+    pub fn weakest_agent(&self) -> Option<&str> {
+        self.confidence_profile.iter()
+            .min_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(b.0)))
+            .map(|(agent, _)| agent.as_str())
+    }
+
+    /// Agent type with the highest mean confidence
+    pub fn strongest_agent(&self) -> Option<&str> {
+        self.confidence_profile.iter()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| b.0.cmp(a.0)))
+            .map(|(agent, _)| agent.as_str())
+    }
+
+    /// Summarize performance as a graded report card.
+    ///
+    /// The grade is relative to other contributors, from `percentile_rank` as of
+    /// the last rank update: the top 10% is an A, the next 20% a B, the next 30%
+    /// a C, the next 20% a D, and the bottom 20% an F.
+    pub fn to_report_card(&self) -> ReportCard {
+        let grade = match self.percentile_rank {
+            s if s >= 0.9 => 'A',
+            s if s >= 0.7 => 'B',
+            s if s >= 0.4 => 'C',
+            s if s >= 0.2 => 'D',
+            _ => 'F',
+        };
+
+        let mut strengths = Vec::new();
+        let mut areas_for_improvement = Vec::new();
+
+        if self.trace_depth > 50 {
+            strengths.push(format!("Deep reasoning traces (max depth {})", self.trace_depth));
+        } else if self.trace_depth < 10 {
+            areas_for_improvement.push("Build longer reasoning traces before submitting".to_string());
+        }
+        if self.uniqueness_score > 0.7 {
+            strengths.push(format!("Highly original traces (uniqueness {:.2})", self.uniqueness_score));
+        } else if self.uniqueness_score < 0.4 {
+            areas_for_improvement.push("Mix more agent types and languages to raise uniqueness".to_string());
+        }
+        if self.total_submissions >= 10 {
+            strengths.push(format!("Consistent contributor ({} submissions)", self.total_submissions));
+        } else if self.total_submissions < 3 {
+            areas_for_improvement.push("Submit more sessions to build a track record".to_string());
+        }
+        if self.streak >= 7 {
+            strengths.push(format!("{}-day submission streak", self.streak));
+        } else if self.streak <= 1 {
+            areas_for_improvement.push("Submit on consecutive days to build a streak".to_string());
+        }
+        if self.output_diversity_score > 0.7 {
+            strengths.push("Varied, non-repetitive outputs".to_string());
+        } else if self.output_diversity_score < 0.4 {
+            areas_for_improvement.push("Vary output wording instead of repeating boilerplate".to_string());
+        }
+        if self.languages_used.len() >= 3 {
+            strengths.push(format!("Multilingual ({} languages)", self.languages_used.len()));
+        } else if self.languages_used.len() <= 1 {
+            areas_for_improvement.push("Reason across more than one language".to_string());
+        }
+
+        let summary = format!(
+            "{} earned grade {} with a combined score of {:.3} (rank {}) across {} submissions.",
+            self.contributor_id, grade, self.combined_score, self.rank, self.total_submissions
+        );
+
+        ReportCard {
+            grade,
+            summary,
+            strengths,
+            areas_for_improvement,
+        }
+    }
+}
+
+/// Graded performance summary for a single contributor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportCard {
+    pub grade: char,
+    pub summary: String,
+    pub strengths: Vec<String>,
+    pub areas_for_improvement: Vec<String>,
+}
+
+impl ReportCard {
+    /// Render the report card as a Markdown document
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# Report Card\n\n**Grade: {}**\n\n{}\n", self.grade, self.summary);
+        for (heading, items) in [("Strengths", &self.strengths), ("Areas for Improvement", &self.areas_for_improvement)] {
+            markdown.push_str(&format!("\n## {}\n\n", heading));
+            if items.is_empty() {
+                markdown.push_str("- None noted\n");
+            }
+            for item in items {
+                markdown.push_str(&format!("- {}\n", item));
+            }
+        }
+        markdown
+    }
+}
+
+/// Reason a contributor's stats look statistically anomalous
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SuspicionFlag {
+    /// Uniqueness above 0.9 from a trace shallower than 5 events
+    HighUniquenessLowDepth,
+    /// Latest provenance hash matches another contributor's
+    DuplicateHash,
+    /// Depth per submission (carried) more than 3 standard deviations above the mean
+    UnrealisticDepthRate(f64),
+}
+
+/// Projected standing of a submission that has not been added to the leaderboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationResult {
+    pub projected_rank: usize,
+    pub projected_tier: Tier,
+    pub beat_count: usize,
+    pub lose_to_count: usize,
+    /// Combined score needed to overtake the contributor just above (0.0 at rank 1)
+    pub score_gap_to_next: f64,
+    /// Combined score lead over the contributor just below (0.0 when last)
+    pub score_gap_from_prev: f64,
+}
+
+/// Cumulative leaderboard aggregates as of the end of one time bucket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeSeriesPoint {
+    /// Start of the bucket
+    pub timestamp: DateTime<Utc>,
+    pub total_contributors: usize,
+    pub total_submissions: usize,
+    pub mean_trace_depth: f64,
+    pub mean_uniqueness_score: f64,
+}
+
+/// Contributors who made their first submission in the same time window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CohortReport {
+    /// Start date of the join window (`YYYY-MM-DD`)
+    pub cohort_id: String,
+    pub join_window_start: DateTime<Utc>,
+    pub join_window_end: DateTime<Utc>,
+    pub contributor_count: usize,
+    pub mean_trace_depth: f64,
+    pub mean_uniqueness_score: f64,
+    /// Fraction of the cohort that submitted during the most recent window
+    pub retention_rate: f64,
+    /// Highest combined score in the cohort
+    pub top_contributor_id: String,
+}
+
+/// Aggregated performance metrics for a single inference backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendStats {
+    pub backend_used: String,
+    pub contributor_count: usize,
+    pub mean_trace_depth: f64,
+    pub max_trace_depth: usize,
+    pub mean_uniqueness_score: f64,
+}
+
+/// Weighted network of contributors who submitted close together in time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContributorGraph {
+    pub edges: Vec<(String, String, usize)>,
+}
+
+impl ContributorGraph {
+    /// Render the graph in Graphviz DOT format
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph contributors {\n");
+        for (a, b, weight) in &self.edges {
+            dot.push_str(&format!(
+                "    \"{}\" -- \"{}\" [weight={}, label=\"{}\"];\n",
+                a.replace('"', "\\\""),
+                b.replace('"', "\\\""),
+                weight,
+                weight
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Contributor with the most distinct neighbours (ties go to the smallest id)
+    pub fn most_connected_contributor(&self) -> Option<&str> {
+        let mut degrees: HashMap<&str, usize> = HashMap::new();
+        for (a, b, _) in &self.edges {
+            *degrees.entry(a.as_str()).or_insert(0) += 1;
+            *degrees.entry(b.as_str()).or_insert(0) += 1;
+        }
+        degrees.into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(contributor_id, _)| contributor_id)
+    }
+}
+
+/// Errors raised while persisting the leaderboard to SQLite
+#[cfg(feature = "sqlite")]
+#[derive(Debug, thiserror::Error)]
+pub enum SqliteError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("Invalid timestamp: {0}")]
+    Timestamp(#[from] chrono::ParseError),
+}
+
+/// Errors raised while restoring a leaderboard from a JSON Lines dump
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Some lines failed to parse; `leaderboard` holds the `loaded` lines that did
+    #[error("loaded {loaded} lines but {failed} failed to parse")]
+    PartialLoad { leaderboard: Box<Leaderboard>, loaded: usize, failed: usize },
+}
+
+/// Errors raised while publishing leaderboard updates to Kafka
+#[cfg(feature = "kafka")]
+#[derive(Debug, thiserror::Error)]
+pub enum KafkaError {
+    #[error("Kafka error: {0}")]
+    Kafka(#[from] rdkafka::error::KafkaError),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("{0} leaderboard messages failed to send")]
+    UndeliveredMessages(usize),
+}
+
+/// Suffix appended to the stats topic for rank-change messages
+#[cfg(feature = "kafka")]
+pub const RANK_TOPIC_SUFFIX: &str = ".ranks";
+
+/// Destination for keyed leaderboard update messages
+#[cfg(feature = "kafka")]
+pub trait LeaderboardProducer: std::fmt::Debug + Send + Sync {
+    /// Queue a message on `topic`
+    fn send(&self, topic: &str, key: &str, payload: &str) -> Result<(), KafkaError>;
+    /// Block until queued messages are delivered
+    fn flush(&self) -> Result<(), KafkaError>;
+}
+
+/// `LeaderboardProducer` backed by an rdkafka producer that polls on a background thread
+#[cfg(feature = "kafka")]
+pub struct RdKafkaProducer {
+    producer: rdkafka::producer::ThreadedProducer<rdkafka::producer::DefaultProducerContext>,
+}
+
+#[cfg(feature = "kafka")]
+impl RdKafkaProducer {
+    /// Connect to a comma-separated list of bootstrap brokers
+    pub fn new(brokers: &str) -> Result<Self, KafkaError> {
+        let producer = rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .create()?;
+        Ok(RdKafkaProducer { producer })
+    }
+}
+
+#[cfg(feature = "kafka")]
+impl std::fmt::Debug for RdKafkaProducer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RdKafkaProducer").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "kafka")]
+impl LeaderboardProducer for RdKafkaProducer {
+    fn send(&self, topic: &str, key: &str, payload: &str) -> Result<(), KafkaError> {
+        self.producer
+            .send(rdkafka::producer::BaseRecord::to(topic).key(key).payload(payload))
+            .map_err(|(e, _)| KafkaError::Kafka(e))
+    }
+
+    fn flush(&self) -> Result<(), KafkaError> {
+        use rdkafka::producer::Producer;
+        Ok(self.producer.flush(std::time::Duration::from_secs(10))?)
+    }
+}
+
+/// Producer and topic that leaderboard updates are published to
+#[cfg(feature = "kafka")]
+#[derive(Debug, Clone)]
+struct KafkaSink {
+    producer: std::sync::Arc<dyn LeaderboardProducer>,
+    topic: String,
+    failed_sends: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+/// Contributor references implied by `parent_hash` links, updated one submission at a time
+#[derive(Debug, Clone, Default)]
+struct ReferenceGraph {
+    /// Contributors who submitted each trace hash
+    owners: HashMap<String, HashSet<String>>,
+    /// Contributors who submitted a log whose parent is each trace hash
+    citers: HashMap<String, HashSet<String>>,
+    /// Distinct `(from, to)` references between different contributors
+    edges: HashSet<(String, String)>,
+}
+
+impl ReferenceGraph {
+    /// Graph over every log in `history`
+    fn from_history(history: &HashMap<String, Vec<ProvenanceLog>>) -> Self {
+        let mut graph = ReferenceGraph::default();
+        for (contributor_id, logs) in history {
+            for log in logs {
+                graph.record(contributor_id, log);
+            }
+        }
+        graph
+    }
+
+    /// Add the edges a new log creates, whichever end of a reference arrived first
+    fn record(&mut self, contributor_id: &str, log: &ProvenanceLog) {
+        for citer in self.citers.get(&log.trace_hash).into_iter().flatten() {
+            if citer != contributor_id {
+                self.edges.insert((citer.clone(), contributor_id.to_string()));
+            }
+        }
+        if let Some(parent) = &log.parent_hash {
+            for owner in self.owners.get(parent).into_iter().flatten() {
+                if owner != contributor_id {
+                    self.edges.insert((contributor_id.to_string(), owner.clone()));
+                }
+            }
+            self.citers.entry(parent.clone()).or_default().insert(contributor_id.to_string());
+        }
+        self.owners.entry(log.trace_hash.clone()).or_default().insert(contributor_id.to_string());
+    }
+}
+
+/// Point-in-time copy of contributor ranks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardSnapshot {
+    pub timestamp: DateTime<Utc>,
+    pub ranks: HashMap<String, usize>,
+}
+
+/// Contributor leaderboard built from provenance logs
+#[derive(Debug, Clone, Default)]
+pub struct Leaderboard {
+    pub entries: HashMap<String, ContributorStats>,
+    pub contributor_history: HashMap<String, Vec<ProvenanceLog>>,
+    pub snapshots: Vec<LeaderboardSnapshot>,
+    /// When set, `add_entry` and `add_entry_batch` ignore unsigned submissions
+    pub strict_signature_mode: bool,
+    reference_graph: ReferenceGraph,
+    /// Centrality read back by a loader whose history carries no references;
+    /// stands in for the reference graph until the next submission is recorded
+    restored_centrality: HashMap<String, f64>,
+    rank_updates: usize,
+    #[cfg(feature = "kafka")]
+    kafka: Option<KafkaSink>,
+}
+
+impl Leaderboard {
+    /// Create empty leaderboard
+    pub fn new() -> Self {
+        Leaderboard {
+            entries: HashMap::new(),
+            contributor_history: HashMap::new(),
+            snapshots: Vec::new(),
+            strict_signature_mode: false,
+            reference_graph: ReferenceGraph::default(),
+            restored_centrality: HashMap::new(),
+            rank_updates: 0,
+            #[cfg(feature = "kafka")]
+            kafka: None,
+        }
+    }
+
+    /// Create empty leaderboard whose `add_entry` and `add_entry_batch` ignore
+    /// unsigned submissions
+    pub fn with_strict_signatures() -> Self {
+        Leaderboard {
+            strict_signature_mode: true,
+            ..Leaderboard::new()
+        }
+    }
+
+    /// Add provenance submission for a contributor.
+    ///
+    /// In strict signature mode an unsigned log is ignored. The signature is not
+    /// verified here; use `add_entry_signed` for that.
+    pub fn add_entry(&mut self, provenance: ProvenanceLog, languages: Vec<String>) {
+        if !self.accepts(&provenance) {
+            return;
+        }
+        #[cfg(feature = "kafka")]
+        let contributor_id = provenance.contributor_id.clone();
+        self.insert_entry(provenance, languages);
+        self.update_ranks(RankingCriteria::Combined);
+        #[cfg(feature = "kafka")]
+        self.publish_stats(&contributor_id);
+    }
+
+    /// Add a submission only if it carries a valid signature from `public_key`.
+    ///
+    /// Unsigned logs are rejected with `MissingSignature` whatever the signature mode.
+    pub fn add_entry_signed(
+        &mut self,
+        provenance: ProvenanceLog,
+        languages: Vec<String>,
+        public_key: &[u8; 32],
+    ) -> Result<(), SignatureError> {
+        ProvenanceSigner::verify(&provenance, public_key)?;
+        self.add_entry(provenance, languages);
+        Ok(())
+    }
+
+    /// Whether `add_entry` takes this log under the current signature mode
+    fn accepts(&self, provenance: &ProvenanceLog) -> bool {
+        !self.strict_signature_mode || provenance.signature.is_some()
+    }
+
+    /// Add many submissions, recomputing ranks once at the end; in strict
+    /// signature mode unsigned logs are skipped
+    pub fn add_entry_batch(&mut self, entries: Vec<(ProvenanceLog, Vec<String>)>) {
+        let entries: Vec<_> = entries.into_iter().filter(|(p, _)| self.accepts(p)).collect();
+        #[cfg(feature = "kafka")]
+        let contributor_ids: Vec<String> = entries.iter().map(|(p, _)| p.contributor_id.clone()).collect();
+        for (provenance, languages) in entries {
+            self.insert_entry(provenance, languages);
+        }
+        self.update_ranks(RankingCriteria::Combined);
+        #[cfg(feature = "kafka")]
+        for contributor_id in &contributor_ids {
+            self.publish_stats(contributor_id);
+        }
+    }
+
+    /// Record a submission in history and contributor stats without re-ranking
+    fn insert_entry(&mut self, provenance: ProvenanceLog, languages: Vec<String>) {
+        let contributor_id = provenance.contributor_id.clone();
+        self.reference_graph.record(&contributor_id, &provenance);
+        self.restored_centrality.clear();
+
+        let history = self.contributor_history
+            .entry(contributor_id.clone())
+            .or_default();
+        history.push(provenance.clone());
+
+        let stats = self.entries
+            .entry(contributor_id)
+            .or_insert_with(|| Self::empty_stats(&provenance));
+        Self::apply_submission(stats, history, provenance, languages);
+    }
+
+    /// Stats for a contributor with no submissions yet
+    fn empty_stats(provenance: &ProvenanceLog) -> ContributorStats {
+        ContributorStats {
+            contributor_id: provenance.contributor_id.clone(),
+            total_submissions: 0,
+            trace_depth: 0,
+            weighted_trace_depth: 0.0,
+            avg_trace_depth: 0.0,
+            uniqueness_score: 0.0,
+            languages_used: Vec::new(),
+            backend_used: provenance.backend_used.clone(),
+            provenance_hash: provenance.trace_hash.clone(),
+            last_updated: provenance.timestamp,
+            combined_score: 0.0,
+            rank: 0,
+            normalized_submission_rate: 0.0,
+            reasoning_entropy: 0.0,
+            total_estimated_tokens: 0,
+            deleted: false,
+            confidence_profile: HashMap::new(),
+            improvement_score: 0.0,
+            recent_improvement_rate: 0.0,
+            streak: 0,
+            output_diversity_score: 0.0,
+            percentile_rank: 0.0,
+            network_centrality: 0.0,
+            profile: ContributorProfile::default(),
+        }
+    }
+
+    /// Fold a submission into stats; `history` must already end with `provenance`
+    fn apply_submission(
+        stats: &mut ContributorStats,
+        history: &[ProvenanceLog],
+        provenance: ProvenanceLog,
+        languages: Vec<String>,
+    ) {
+        let avg_trace_depth = history.iter().map(|p| p.trace_depth as f64).sum::<f64>() / history.len() as f64;
+        let confidence_profile = Self::compute_confidence_profile(history);
+        let improvement_score = Self::compute_improvement_score(history);
+        let output_diversity_score = history.iter().map(|p| p.output_diversity).sum::<f64>() / history.len() as f64;
+
+        stats.total_submissions += 1;
+        stats.trace_depth = stats.trace_depth.max(provenance.trace_depth);
+        stats.weighted_trace_depth = stats.weighted_trace_depth.max(provenance.weighted_trace_depth);
+        stats.avg_trace_depth = avg_trace_depth;
+        stats.uniqueness_score = stats.uniqueness_score.max(provenance.uniqueness_score);
+        for lang in languages {
+            if !stats.languages_used.contains(&lang) {
+                stats.languages_used.push(lang);
+            }
+        }
+        stats.backend_used = provenance.backend_used;
+        stats.provenance_hash = provenance.trace_hash;
+        stats.last_updated = provenance.timestamp;
+        stats.reasoning_entropy = provenance.reasoning_entropy;
+        stats.total_estimated_tokens += provenance.total_tokens_estimate;
+        stats.confidence_profile = confidence_profile;
+        stats.improvement_score = improvement_score;
+        stats.recent_improvement_rate = Self::compute_recent_improvement_rate(history);
+        stats.streak = Self::compute_streak(history);
+        stats.output_diversity_score = output_diversity_score;
+        Self::refresh_profile(stats, history);
+    }
+
+    /// Rebuild every contributor's profile from their submission history, e.g.
+    /// after the reasoning style inference changes
+    pub fn bulk_update_profiles(&mut self) {
+        for (contributor_id, stats) in self.entries.iter_mut() {
+            if let Some(history) = self.contributor_history.get(contributor_id) {
+                Self::refresh_profile(stats, history);
+            }
+        }
+    }
+
+    /// Replace the derived parts of a profile, keeping the expertise domains and
+    /// fluency scores that history cannot supply
+    fn refresh_profile(stats: &mut ContributorStats, history: &[ProvenanceLog]) {
+        let mut profile = ContributorProfile::from_provenance_history(&stats.contributor_id, history);
+        profile.expertise_domains = std::mem::take(&mut stats.profile.expertise_domains);
+        profile.language_fluency_scores = std::mem::take(&mut stats.profile.language_fluency_scores);
+        stats.profile = profile;
+    }
+
+    /// Recompute every contributor's streak from their submission history
+    pub fn compute_streaks(&mut self) {
+        for (contributor_id, stats) in self.entries.iter_mut() {
+            stats.streak = self.contributor_history
+                .get(contributor_id)
+                .map_or(0, |history| Self::compute_streak(history));
+        }
+    }
+
+    /// Consecutive days with a submission, counting back from the latest submission day
+    fn compute_streak(history: &[ProvenanceLog]) -> usize {
+        let days: std::collections::BTreeSet<chrono::NaiveDate> = history.iter()
+            .map(|p| p.timestamp.date_naive())
+            .collect();
+
+        let mut streak = 0;
+        let mut expected = days.iter().next_back().copied();
+        for day in days.iter().rev() {
+            if Some(*day) != expected {
+                break;
+            }
+            streak += 1;
+            expected = day.pred_opt();
+        }
+        streak
+    }
+
+    /// Mean confidence per agent type across a contributor's submissions.
+    ///
+    /// Provenance logs carry no per-event confidence, so each transition's score
+    /// (the confidence leading into it) is credited to the agent it leaves. The
+    /// final agent of a trace therefore only counts if it also appears earlier.
+    pub(crate) fn compute_confidence_profile(history: &[ProvenanceLog]) -> HashMap<String, f64> {
+        let mut sums: HashMap<String, (f64, usize)> = HashMap::new();
+        for transition in history.iter().flat_map(|p| &p.transitions) {
+            let entry = sums.entry(transition.from_agent.to_string()).or_insert((0.0, 0));
+            entry.0 += transition.transition_score;
+            entry.1 += 1;
+        }
+        sums.into_iter()
+            .map(|(agent, (sum, count))| (agent, sum / count as f64))
+            .collect()
+    }
+
+    /// Mean depth of the last `IMPROVEMENT_WINDOW` submissions minus the mean depth
+    /// of all earlier ones (0.0 until there is at least one earlier submission)
+    fn compute_improvement_score(history: &[ProvenanceLog]) -> f64 {
+        if history.len() <= IMPROVEMENT_WINDOW {
+            return 0.0;
+        }
+        let (historical, recent) = history.split_at(history.len() - IMPROVEMENT_WINDOW);
+        let mean_depth = |logs: &[ProvenanceLog]| {
+            logs.iter().map(|p| p.trace_depth as f64).sum::<f64>() / logs.len() as f64
+        };
+        mean_depth(recent) - mean_depth(historical)
+    }
+
+    /// Mean depth of the last `RECENT_IMPROVEMENT_WINDOW` submissions minus the mean
+    /// depth of the window before them (0.0 until both windows are full)
+    fn compute_recent_improvement_rate(history: &[ProvenanceLog]) -> f64 {
+        if history.len() < 2 * RECENT_IMPROVEMENT_WINDOW {
+            return 0.0;
+        }
+        let recent = &history[history.len() - RECENT_IMPROVEMENT_WINDOW..];
+        let previous = &history[history.len() - 2 * RECENT_IMPROVEMENT_WINDOW..history.len() - RECENT_IMPROVEMENT_WINDOW];
+        let mean_depth = |logs: &[ProvenanceLog]| {
+            logs.iter().map(|p| p.trace_depth as f64).sum::<f64>() / logs.len() as f64
+        };
+        mean_depth(recent) - mean_depth(previous)
+    }
+
+    /// Recompute combined scores and assign ranks for the given criteria
+    fn update_ranks(&mut self, criteria: RankingCriteria) {
+        self.rank_updates += 1;
+        #[cfg(feature = "kafka")]
+        let previous_ranks: HashMap<String, usize> = self.entries.iter()
+            .map(|(id, stats)| (id.clone(), stats.rank))
+            .collect();
+
+        let centrality = self.compute_network_centrality();
+        for stats in self.entries.values_mut() {
+            stats.network_centrality = self.restored_centrality.get(&stats.contributor_id)
+                .or_else(|| centrality.get(&stats.contributor_id))
+                .copied()
+                .unwrap_or(0.0);
+            stats.combined_score = Self::compute_combined_score(stats);
+            if stats.deleted {
+                stats.rank = 0;
+                stats.percentile_rank = 0.0;
+            }
+        }
+
+        let order: Vec<String> = self.ranked(criteria)
+            .into_iter()
+            .map(|s| s.contributor_id.clone())
+            .collect();
+
+        for (i, contributor_id) in order.iter().enumerate() {
+            if let Some(stats) = self.entries.get_mut(contributor_id) {
+                stats.rank = i + 1;
+                stats.percentile_rank = Self::percentile_rank(i + 1, order.len());
+            }
+        }
+
+        #[cfg(feature = "kafka")]
+        self.publish_rank_changes(&previous_ranks);
+    }
+
+    /// Number of times ranks have been recomputed since the leaderboard was created
+    pub fn rank_update_count(&self) -> usize {
+        self.rank_updates
+    }
+
+    /// Normalized rank of every active contributor under `criteria`
+    /// (1.0 = best, 0.0 = worst; a lone contributor scores 1.0)
+    pub fn compute_global_percentile_ranks(&self, criteria: RankingCriteria) -> HashMap<String, f64> {
+        let ranked = self.ranked(criteria);
+        let total = ranked.len();
+        ranked.into_iter()
+            .enumerate()
+            .map(|(i, stats)| (stats.contributor_id.clone(), Self::percentile_rank(i + 1, total)))
+            .collect()
+    }
+
+    /// In-degree centrality of every active contributor in the reference graph.
+    ///
+    /// Contributor A references B when one of A's submissions has a `parent_hash`
+    /// matching a trace hash in B's history. Each referencing contributor counts
+    /// once, self-references are ignored, and the in-degree is divided by
+    /// `total_contributors - 1` (0.0 for a lone contributor). References are
+    /// recorded as submissions arrive, so this visits each edge once rather
+    /// than rescanning history; logs pushed onto `contributor_history` directly
+    /// are not seen.
+    pub fn compute_network_centrality(&self) -> HashMap<String, f64> {
+        let active: HashSet<&str> = self.active_entries().map(|s| s.contributor_id.as_str()).collect();
+
+        let mut in_degrees: HashMap<&str, usize> = HashMap::new();
+        for (from, to) in &self.reference_graph.edges {
+            if active.contains(from.as_str()) && active.contains(to.as_str()) {
+                *in_degrees.entry(to.as_str()).or_insert(0) += 1;
+            }
+        }
+
+        let others = active.len().saturating_sub(1);
+        active.iter()
+            .map(|&contributor_id| {
+                let in_degree = in_degrees.get(contributor_id).copied().unwrap_or(0);
+                let centrality = if others == 0 { 0.0 } else { in_degree as f64 / others as f64 };
+                (contributor_id.to_string(), centrality)
+            })
+            .collect()
+    }
+
+    /// Current `network_centrality` of every contributor, to keep across a load
+    fn stored_centrality(&self) -> HashMap<String, f64> {
+        self.entries.iter()
+            .map(|(id, stats)| (id.clone(), stats.network_centrality))
+            .collect()
+    }
+
+    /// `(total - rank) / (total - 1)`, or 1.0 when there is only one contributor
+    fn percentile_rank(rank: usize, total: usize) -> f64 {
+        if total <= 1 {
+            return 1.0;
+        }
+        (total - rank) as f64 / (total - 1) as f64
+    }
+
+    /// Weighted combination of depth, uniqueness, activity, streak, token
+    /// efficiency, and output diversity in [0.0, 1.0]
+    fn compute_combined_score(stats: &ContributorStats) -> f64 {
+        let depth_score = (Self::depth_value(stats) / 100.0).min(1.0);
+        let avg_depth_score = (stats.avg_trace_depth / 100.0).min(1.0);
+        let submission_score = (stats.total_submissions as f64 / 20.0).min(1.0);
+        let streak_score = (stats.streak as f64 / STREAK_TARGET_DAYS).min(1.0);
+        let efficiency_score = (Self::token_efficiency(stats) / TOKEN_EFFICIENCY_TARGET).min(1.0);
+
+        0.27 * depth_score
+            + 0.27 * stats.uniqueness_score
+            + 0.105 * avg_depth_score
+            + 0.105 * submission_score
+            + 0.10 * streak_score
+            + 0.05 * efficiency_score
+            + 0.10 * stats.output_diversity_score
+    }
+
+    /// Mean trace depth per thousand mean estimated tokens per submission
+    /// (0.0 without token data), so repeat submissions are not penalized
+    fn token_efficiency(stats: &ContributorStats) -> f64 {
+        if stats.total_estimated_tokens == 0 || stats.total_submissions == 0 {
+            return 0.0;
+        }
+        let mean_tokens = stats.total_estimated_tokens as f64 / stats.total_submissions as f64;
+        stats.avg_trace_depth / mean_tokens * 1000.0
+    }
+
+    /// Weighted trace depth when recorded, raw trace depth otherwise (e.g. logs
+    /// restored from credentials); every depth comparison and the combined score use this
+    fn depth_value(stats: &ContributorStats) -> f64 {
+        if stats.weighted_trace_depth > 0.0 {
+            stats.weighted_trace_depth
+        } else {
+            stats.trace_depth as f64
+        }
+    }
+
+    /// Value of the ranking metric for a contributor (higher ranks first)
+    fn metric_value(&self, stats: &ContributorStats, criteria: RankingCriteria) -> f64 {
+        match criteria {
+            RankingCriteria::TraceDepth => Self::depth_value(stats),
+            RankingCriteria::Uniqueness => stats.uniqueness_score,
+            RankingCriteria::Submissions => stats.total_submissions as f64,
+            RankingCriteria::AvgDepth => stats.avg_trace_depth,
+            RankingCriteria::Combined => Self::compute_combined_score(stats),
+            RankingCriteria::NormalizedSubmissionRate => stats.normalized_submission_rate,
+            RankingCriteria::ReasoningEntropy => stats.reasoning_entropy,
+            RankingCriteria::TokenEfficiency => Self::token_efficiency(stats),
+            RankingCriteria::Improvement => stats.improvement_score,
+            RankingCriteria::Streak => stats.streak as f64,
+            RankingCriteria::OutputDiversity => stats.output_diversity_score,
+            RankingCriteria::Tournament => self.tournament_wins(stats) as f64,
+            RankingCriteria::Specialization => stats.profile.specialization_index,
+            RankingCriteria::RecentImprovement => stats.recent_improvement_rate,
+            RankingCriteria::NetworkCentrality => stats.network_centrality,
+        }
+    }
+
+    /// Round-robin wins against every other active contributor
+    fn tournament_wins(&self, stats: &ContributorStats) -> usize {
+        self.active_entries()
+            .filter(|other| other.contributor_id != stats.contributor_id && Self::beats(stats, other))
+            .count()
+    }
+
+    /// Whether `a` is strictly better than `b` on at least 3 of depth, uniqueness,
+    /// submissions, and average depth
+    fn beats(a: &ContributorStats, b: &ContributorStats) -> bool {
+        let better = [
+            Self::depth_value(a) > Self::depth_value(b),
+            a.uniqueness_score > b.uniqueness_score,
+            a.total_submissions > b.total_submissions,
+            a.avg_trace_depth > b.avg_trace_depth,
+        ];
+        better.iter().filter(|won| **won).count() >= 3
+    }
+
+    /// Sort contributors by the given criteria (best first)
+    fn ranked(&self, criteria: RankingCriteria) -> Vec<&ContributorStats> {
+        let mut keyed: Vec<(f64, &ContributorStats)> = self.active_entries()
+            .map(|s| (self.metric_value(s, criteria), s))
+            .collect();
+        keyed.sort_by(|(a_value, a), (b_value, b)| {
+            let tie_break = match criteria {
+                // Equal win counts fall back to the combined score
+                RankingCriteria::Tournament => Self::compute_combined_score(b)
+                    .partial_cmp(&Self::compute_combined_score(a))
+                    .unwrap_or(std::cmp::Ordering::Equal),
+                _ => std::cmp::Ordering::Equal,
+            };
+            b_value.partial_cmp(a_value)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(tie_break)
+                .then_with(|| a.contributor_id.cmp(&b.contributor_id))
+        });
+        keyed.into_iter().map(|(_, s)| s).collect()
+    }
+
+    /// Metric value at the given percentile across active contributors
+    /// (0.0 = lowest, 1.0 = highest), nearest-rank method
+    pub fn percentile_for_metric(&self, metric: RankingCriteria, percentile: f64) -> Option<f64> {
+        if !(0.0..=1.0).contains(&percentile) {
+            return None;
+        }
+        let mut values: Vec<f64> = self.active_entries()
+            .map(|s| self.metric_value(s, metric))
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        let rank = (percentile * values.len() as f64).ceil() as usize;
+        Some(values[rank.saturating_sub(1)])
+    }
+
+    /// Contributors whose metric is strictly above the percentile cutoff, best first
+    pub fn contributors_above_percentile(&self, metric: RankingCriteria, percentile: f64) -> Vec<&ContributorStats> {
+        let cutoff = match self.percentile_for_metric(metric, percentile) {
+            Some(cutoff) => cutoff,
+            None => return Vec::new(),
+        };
+        self.ranked(metric)
+            .into_iter()
+            .filter(|s| self.metric_value(s, metric) > cutoff)
+            .collect()
+    }
+
+    /// Contributors that have not been soft-deleted
+    fn active_entries(&self) -> impl Iterator<Item = &ContributorStats> {
+        self.entries.values().filter(|s| !s.deleted)
+    }
+
+    /// Hide a contributor from all rankings while keeping their stats and history.
+    ///
+    /// Returns `false` if the contributor is unknown or already deleted.
+    pub fn soft_delete_contributor(&mut self, contributor_id: &str) -> bool {
+        match self.entries.get_mut(contributor_id) {
+            Some(stats) if !stats.deleted => stats.deleted = true,
+            _ => return false,
+        }
+        self.update_ranks(RankingCriteria::Combined);
+        true
+    }
+
+    /// Return a soft-deleted contributor to the rankings.
+    ///
+    /// Returns `false` if the contributor is unknown or not deleted.
+    pub fn restore_contributor(&mut self, contributor_id: &str) -> bool {
+        match self.entries.get_mut(contributor_id) {
+            Some(stats) if stats.deleted => stats.deleted = false,
+            _ => return false,
+        }
+        self.update_ranks(RankingCriteria::Combined);
+        true
+    }
+
+    /// Rank contributors by deepest trace
+    pub fn rank_by_depth(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::TraceDepth)
+    }
+
+    /// Rank contributors by uniqueness score
+    pub fn rank_by_uniqueness(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::Uniqueness)
+    }
+
+    /// Rank contributors by number of submissions
+    pub fn rank_by_submissions(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::Submissions)
+    }
+
+    /// Rank contributors by average trace depth
+    pub fn rank_by_avg_depth(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::AvgDepth)
+    }
+
+    /// Project the combined-score rank a submission would reach, without adding it.
+    ///
+    /// The submitter's existing stats and history are included as `add_entry` would;
+    /// their current entry is not counted as a competitor.
+    pub fn simulate_submission(&self, provenance: &ProvenanceLog, languages: &[String]) -> SimulationResult {
+        let contributor_id = &provenance.contributor_id;
+        let mut history = self.contributor_history.get(contributor_id).cloned().unwrap_or_default();
+        history.push(provenance.clone());
+        let mut stats = self.entries.get(contributor_id)
+            .cloned()
+            .unwrap_or_else(|| Self::empty_stats(provenance));
+        Self::apply_submission(&mut stats, &history, provenance.clone(), languages.to_vec());
+        let score = Self::compute_combined_score(&stats);
+
+        let others: Vec<(f64, &str)> = self.ranked(RankingCriteria::Combined)
+            .into_iter()
+            .filter(|s| &s.contributor_id != contributor_id)
+            .map(|s| (Self::compute_combined_score(s), s.contributor_id.as_str()))
+            .collect();
+        let lose_to_count = others.partition_point(|&(other, id)| {
+            other > score || (other == score && id < contributor_id.as_str())
+        });
+        let projected_rank = lose_to_count + 1;
+
+        SimulationResult {
+            projected_rank,
+            projected_tier: Tier::from_rank(projected_rank, others.len() + 1),
+            beat_count: others.len() - lose_to_count,
+            lose_to_count,
+            score_gap_to_next: lose_to_count.checked_sub(1).map_or(0.0, |i| others[i].0 - score),
+            score_gap_from_prev: others.get(lose_to_count).map_or(0.0, |next| score - next.0),
+        }
+    }
+
+    /// Rank contributors by deepest trace per thousand estimated tokens
+    pub fn rank_by_token_efficiency(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::TokenEfficiency)
+    }
+
+    /// Rank contributors by current daily submission streak
+    pub fn rank_by_streak(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::Streak)
+    }
+
+    /// Rank contributors by round-robin wins, where a contributor beats another by
+    /// being strictly better on at least 3 of depth, uniqueness, submissions, and
+    /// average depth; equal win counts fall back to the combined score
+    pub fn tournament_rank(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::Tournament)
+    }
+
+    /// Rank contributors by how concentrated their work is on few agent types
+    pub fn rank_by_specialization(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::Specialization)
+    }
+
+    /// Rank contributors by mean lexical diversity of their event outputs
+    pub fn rank_by_output_diversity(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::OutputDiversity)
+    }
+
+    /// Rank contributors by recent improvement in trace depth
+    pub fn rank_by_improvement(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::Improvement)
+    }
+
+    /// Rank contributors by the change in mean depth between their last 3
+    /// submissions and the 3 before; fewer than 6 submissions score 0.0
+    pub fn rank_by_recent_improvement_rate(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::RecentImprovement)
+    }
+
+    /// Rank contributors by how many others build on their sessions
+    pub fn rank_by_network_centrality(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::NetworkCentrality)
+    }
+
+    /// Rank contributors by combined score
+    pub fn rank_combined(&self) -> Vec<&ContributorStats> {
+        self.ranked(RankingCriteria::Combined)
+    }
+
+    /// Get top N contributors for the given criteria
+    pub fn get_top_n(&self, n: usize, criteria: RankingCriteria) -> Vec<&ContributorStats> {
+        self.ranked(criteria).into_iter().take(n).collect()
+    }
+
+    /// Contributors whose latest trace is most similar to the given contributor's.
+    ///
+    /// Similarity is the cosine over (agent type, language) pairs, as in
+    /// `MetaAgent::trace_similarity`.
+    pub fn find_similar_contributors(&self, contributor_id: &str, top_n: usize) -> Vec<&ContributorStats> {
+        let target = match self.latest_provenance(contributor_id) {
+            Some(log) => log,
+            None => return Vec::new(),
+        };
+
+        let mut scored: Vec<(&ContributorStats, f64)> = self.active_entries()
+            .filter(|s| s.contributor_id != contributor_id)
+            .filter_map(|s| {
+                let log = self.latest_provenance(&s.contributor_id)?;
+                Some((s, pair_cosine_similarity(provenance_pairs(target), provenance_pairs(log))))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.contributor_id.cmp(&b.0.contributor_id))
+        });
+        scored.into_iter().take(top_n).map(|(s, _)| s).collect()
+    }
+
+    /// Link contributors whose submissions landed within `window_seconds` of each other.
+    ///
+    /// Edge weight counts the co-occurring submission pairs. Soft-deleted
+    /// contributors are left out.
+    pub fn contributor_graph(&self, window_seconds: u64) -> ContributorGraph {
+        let window = chrono::Duration::seconds(window_seconds.min(i64::MAX as u64) as i64);
+        let mut submissions: Vec<(&str, DateTime<Utc>)> = self.active_entries()
+            .filter_map(|s| self.contributor_history.get(&s.contributor_id))
+            .flatten()
+            .map(|p| (p.contributor_id.as_str(), p.timestamp))
+            .collect();
+        submissions.sort_by_key(|(_, timestamp)| *timestamp);
+
+        let mut weights: HashMap<(&str, &str), usize> = HashMap::new();
+        for (i, (a, a_time)) in submissions.iter().enumerate() {
+            for (b, b_time) in &submissions[i + 1..] {
+                if *b_time - *a_time > window {
+                    break;
+                }
+                if a != b {
+                    *weights.entry(if a < b { (a, b) } else { (b, a) }).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut edges: Vec<(String, String, usize)> = weights.into_iter()
+            .map(|((a, b), weight)| (a.to_string(), b.to_string(), weight))
+            .collect();
+        edges.sort();
+        ContributorGraph { edges }
+    }
+
+    /// Most recently added provenance log for a contributor
+    fn latest_provenance(&self, contributor_id: &str) -> Option<&ProvenanceLog> {
+        self.contributor_history.get(contributor_id)?.last()
+    }
+
+    /// Get all provenance logs submitted by a contributor
+    pub fn get_contributor_history(&self, contributor_id: &str) -> Option<&Vec<ProvenanceLog>> {
+        self.contributor_history.get(contributor_id)
+    }
+
+    /// Group contributor stats by the backend used for their latest submission
+    fn group_by_backend(&self) -> HashMap<&str, Vec<&ContributorStats>> {
+        let mut grouped: HashMap<&str, Vec<&ContributorStats>> = HashMap::new();
+        for stats in self.active_entries() {
+            grouped.entry(stats.backend_used.as_str()).or_default().push(stats);
+        }
+        grouped
+    }
+
+    /// Normalize submission counts by cohort so early joiners are not favored.
+    ///
+    /// Contributors are grouped by first submission date, floored to a
+    /// `cohort_window_days` boundary. Each contributor's rate is their submissions
+    /// divided by the days (inclusive) from their cohort start to the latest
+    /// submission on the leaderboard.
+    pub fn cohort_normalize(&mut self, cohort_window_days: u64) {
+        let window_days = cohort_window_days.max(1) as i64;
+        let latest = match self.contributor_history.values().flatten().map(|p| p.timestamp).max() {
+            Some(latest) => latest,
+            None => return,
+        };
+
+        for (contributor_id, history) in &self.contributor_history {
+            let first = match history.iter().map(|p| p.timestamp).min() {
+                Some(first) => first,
+                None => continue,
+            };
+            let first_day = first.timestamp().div_euclid(86_400);
+            let cohort_start_day = first_day - first_day.rem_euclid(window_days);
+            let latest_day = latest.timestamp().div_euclid(86_400);
+            let active_days = (latest_day - cohort_start_day + 1).max(1) as f64;
+
+            if let Some(stats) = self.entries.get_mut(contributor_id) {
+                stats.normalized_submission_rate = stats.total_submissions as f64 / active_days;
+            }
+        }
+    }
+
+    /// Running submission aggregates bucketed by time, oldest bucket first.
+    ///
+    /// Buckets are aligned to multiples of `bucket_duration_seconds` since the
+    /// Unix epoch and only buckets containing submissions are emitted. Each point
+    /// aggregates every submission up to and including its bucket.
+    pub fn time_series_stats(&self, bucket_duration_seconds: u64) -> Vec<TimeSeriesPoint> {
+        let bucket_seconds = bucket_duration_seconds.max(1) as i64;
+        let mut submissions: Vec<&ProvenanceLog> = self.contributor_history.values().flatten().collect();
+        submissions.sort_by_key(|p| p.timestamp);
+
+        let mut series: Vec<TimeSeriesPoint> = Vec::new();
+        let mut contributors = std::collections::HashSet::new();
+        let mut depth_sum = 0.0;
+        let mut uniqueness_sum = 0.0;
+
+        for (i, provenance) in submissions.iter().enumerate() {
+            let secs = provenance.timestamp.timestamp();
+            let bucket_start = secs - secs.rem_euclid(bucket_seconds);
+            let timestamp = DateTime::from_timestamp(bucket_start, 0).unwrap_or(provenance.timestamp);
+
+            contributors.insert(provenance.contributor_id.as_str());
+            depth_sum += provenance.trace_depth as f64;
+            uniqueness_sum += provenance.uniqueness_score;
+
+            let total = i + 1;
+            let point = TimeSeriesPoint {
+                timestamp,
+                total_contributors: contributors.len(),
+                total_submissions: total,
+                mean_trace_depth: depth_sum / total as f64,
+                mean_uniqueness_score: uniqueness_sum / total as f64,
+            };
+            match series.last_mut() {
+                Some(last) if last.timestamp == timestamp => *last = point,
+                _ => series.push(point),
+            }
+        }
+
+        series
+    }
+
+    /// Active contributors grouped by the window holding their first submission,
+    /// oldest cohort first.
+    ///
+    /// Windows span `cohort_window_days` days aligned to the Unix epoch. Retention
+    /// counts cohort members who submitted in the window of the latest submission
+    /// on the leaderboard.
+    pub fn generate_cohort_report(&self, cohort_window_days: u64) -> Vec<CohortReport> {
+        let window_seconds = cohort_window_days.max(1) as i64 * 86_400;
+        let window_start = |t: DateTime<Utc>| t.timestamp() - t.timestamp().rem_euclid(window_seconds);
+
+        let Some(latest) = self.contributor_history.values().flatten().map(|p| p.timestamp).max() else {
+            return Vec::new();
+        };
+        let latest_window = window_start(latest);
+
+        let mut cohorts: std::collections::BTreeMap<i64, Vec<(&ContributorStats, &Vec<ProvenanceLog>)>> =
+            std::collections::BTreeMap::new();
+        for stats in self.active_entries() {
+            let Some(history) = self.contributor_history.get(&stats.contributor_id) else {
+                continue;
+            };
+            if let Some(first) = history.iter().map(|p| p.timestamp).min() {
+                cohorts.entry(window_start(first)).or_default().push((stats, history));
+            }
+        }
+
+        cohorts.into_iter()
+            .map(|(start, members)| {
+                let count = members.len() as f64;
+                let retained = members.iter()
+                    .filter(|(_, history)| history.iter().any(|p| window_start(p.timestamp) == latest_window))
+                    .count();
+                let top = members.iter()
+                    .max_by(|(a, _), (b, _)| a.combined_score.partial_cmp(&b.combined_score)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                        .then_with(|| b.contributor_id.cmp(&a.contributor_id)))
+                    .map(|(s, _)| s.contributor_id.clone())
+                    .unwrap_or_default();
+                let join_window_start = DateTime::from_timestamp(start, 0).unwrap_or_default();
+
+                CohortReport {
+                    cohort_id: join_window_start.format("%Y-%m-%d").to_string(),
+                    join_window_start,
+                    join_window_end: join_window_start + chrono::Duration::seconds(window_seconds),
+                    contributor_count: members.len(),
+                    mean_trace_depth: members.iter().map(|(s, _)| s.trace_depth as f64).sum::<f64>() / count,
+                    mean_uniqueness_score: members.iter().map(|(s, _)| s.uniqueness_score).sum::<f64>() / count,
+                    retention_rate: retained as f64 / count,
+                    top_contributor_id: top,
+                }
+            })
+            .collect()
+    }
+
+    /// Record the current combined-score ranks of all contributors
+    pub fn take_snapshot(&mut self) {
+        let ranks = self.active_entries()
+            .map(|s| (s.contributor_id.clone(), s.rank))
+            .collect();
+
+        self.snapshots.push(LeaderboardSnapshot {
+            timestamp: Utc::now(),
+            ranks,
+        });
+    }
+
+    /// Rank of a contributor in the latest snapshot taken at or before `at`
+    pub fn get_contributor_rank_at(&self, contributor_id: &str, at: DateTime<Utc>) -> Option<usize> {
+        self.snapshots.iter()
+            .filter(|snapshot| snapshot.timestamp <= at)
+            .max_by_key(|snapshot| snapshot.timestamp)?
+            .ranks
+            .get(contributor_id)
+            .copied()
+    }
+
+    /// Aggregate performance metrics per backend, deepest backends first
+    pub fn get_backend_stats(&self) -> Vec<BackendStats> {
+        let mut backend_stats: Vec<BackendStats> = self.group_by_backend()
+            .into_iter()
+            .map(|(backend, contributors)| {
+                let count = contributors.len() as f64;
+                BackendStats {
+                    backend_used: backend.to_string(),
+                    contributor_count: contributors.len(),
+                    mean_trace_depth: contributors.iter().map(|s| s.trace_depth as f64).sum::<f64>() / count,
+                    max_trace_depth: contributors.iter().map(|s| s.trace_depth).max().unwrap_or(0),
+                    mean_uniqueness_score: contributors.iter().map(|s| s.uniqueness_score).sum::<f64>() / count,
+                }
+            })
+            .collect();
+
+        backend_stats.sort_by(|a, b| {
+            b.mean_trace_depth.partial_cmp(&a.mean_trace_depth)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.backend_used.cmp(&b.backend_used))
+        });
+        backend_stats
+    }
+
+    /// Rank backends by composite quality (normalized mean depth and mean uniqueness)
+    pub fn rank_by_backend_quality(&self) -> Vec<(&str, f64)> {
+        let means: Vec<(&str, f64, f64)> = self.group_by_backend()
+            .into_iter()
+            .map(|(backend, contributors)| {
+                let count = contributors.len() as f64;
+                let mean_depth = contributors.iter().map(|s| s.trace_depth as f64).sum::<f64>() / count;
+                let mean_uniqueness = contributors.iter().map(|s| s.uniqueness_score).sum::<f64>() / count;
+                (backend, mean_depth, mean_uniqueness)
+            })
+            .collect();
+
+        let max_mean_depth = means.iter().map(|m| m.1).fold(0.0, f64::max);
+        let mut ranked: Vec<(&str, f64)> = means.into_iter()
+            .map(|(backend, mean_depth, mean_uniqueness)| {
+                let depth_score = if max_mean_depth > 0.0 { mean_depth / max_mean_depth } else { 0.0 };
+                (backend, 0.5 * depth_score + 0.5 * mean_uniqueness)
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(b.0))
+        });
+        ranked
+    }
+
+    /// Histogram of combined scores as `(bin_lower, bin_upper, count)` over `[0.0, max_score]`
+    pub fn score_distribution(&self, bins: usize) -> Vec<(f64, f64, usize)> {
+        let scores = self.combined_scores();
+        if bins == 0 || scores.is_empty() {
+            return Vec::new();
+        }
+
+        let max_score = scores.iter().cloned().fold(0.0, f64::max);
+        let width = max_score / bins as f64;
+        let mut counts = vec![0; bins];
+        for score in &scores {
+            // The maximum score falls into the last (closed) bin
+            let bin = if width > 0.0 { ((score / width) as usize).min(bins - 1) } else { 0 };
+            counts[bin] += 1;
+        }
+
+        counts.into_iter()
+            .enumerate()
+            .map(|(i, count)| (i as f64 * width, (i + 1) as f64 * width, count))
+            .collect()
+    }
+
+    /// Mean combined score (0.0 for an empty leaderboard)
+    pub fn score_mean(&self) -> f64 {
+        let scores = self.combined_scores();
+        if scores.is_empty() {
+            return 0.0;
+        }
+        scores.iter().sum::<f64>() / scores.len() as f64
+    }
+
+    /// Population standard deviation of combined scores
+    pub fn score_stddev(&self) -> f64 {
+        let scores = self.combined_scores();
+        if scores.is_empty() {
+            return 0.0;
+        }
+        let mean = self.score_mean();
+        let variance = scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / scores.len() as f64;
+        variance.sqrt()
+    }
+
+    /// Median combined score (mean of the middle pair for even counts)
+    pub fn score_median(&self) -> f64 {
+        let mut scores = self.combined_scores();
+        if scores.is_empty() {
+            return 0.0;
+        }
+        scores.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let mid = scores.len() / 2;
+        if scores.len().is_multiple_of(2) {
+            (scores[mid - 1] + scores[mid]) / 2.0
+        } else {
+            scores[mid]
+        }
+    }
+
+    /// Active contributors with at least one `SuspicionFlag`, sorted by contributor id
+    pub fn detect_suspicious_entries(&self) -> Vec<(String, Vec<SuspicionFlag>)> {
+        let depth_rate = |s: &ContributorStats| s.trace_depth as f64 / s.total_submissions.max(1) as f64;
+        let rates: Vec<f64> = self.active_entries().map(depth_rate).collect();
+        let mean = rates.iter().sum::<f64>() / rates.len().max(1) as f64;
+        let stddev = (rates.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / rates.len().max(1) as f64).sqrt();
+
+        let mut hash_counts: HashMap<&str, usize> = HashMap::new();
+        for stats in self.active_entries() {
+            *hash_counts.entry(stats.provenance_hash.as_str()).or_insert(0) += 1;
+        }
+
+        let mut suspicious: Vec<(String, Vec<SuspicionFlag>)> = self.active_entries()
+            .filter_map(|stats| {
+                let mut flags = Vec::new();
+                if stats.uniqueness_score > 0.9 && stats.trace_depth < 5 {
+                    flags.push(SuspicionFlag::HighUniquenessLowDepth);
+                }
+                if hash_counts[stats.provenance_hash.as_str()] > 1 {
+                    flags.push(SuspicionFlag::DuplicateHash);
+                }
+                let rate = depth_rate(stats);
+                if stddev > 0.0 && rate > mean + 3.0 * stddev {
+                    flags.push(SuspicionFlag::UnrealisticDepthRate(rate));
+                }
+                (!flags.is_empty()).then(|| (stats.contributor_id.clone(), flags))
+            })
+            .collect();
+        suspicious.sort_by(|a, b| a.0.cmp(&b.0));
+        suspicious
+    }
+
+    /// Combined scores of all active contributors
+    fn combined_scores(&self) -> Vec<f64> {
+        self.active_entries().map(|s| s.combined_score).collect()
+    }
+
+    /// Number of contributors on the leaderboard (excluding soft-deleted)
+    pub fn total_contributors(&self) -> usize {
+        self.active_entries().count()
+    }
+
+    /// Number of submissions across all contributors (excluding soft-deleted)
+    pub fn total_submissions(&self) -> usize {
+        self.active_entries().map(|s| s.total_submissions).sum()
+    }
+
+    /// Print the leaderboard table for the given criteria
+    pub fn display(&self, criteria: RankingCriteria) {
+        println!("🏆 Leaderboard - ranked by {}", criteria);
+        println!("{:<6} {:<24} {:>8} {:>12} {:>12} {:>10} {:>8} {:>7}", "Rank", "Contributor", "Depth", "Uniqueness", "Submissions", "Score", "Entropy", "Streak");
+        println!("{}", "─".repeat(94));
+        for (i, stats) in self.ranked(criteria).iter().enumerate() {
+            println!(
+                "{:<6} {:<24} {:>8} {:>12.3} {:>12} {:>10.3} {:>8.3} {:>7}",
+                i + 1,
+                stats.contributor_id,
+                stats.trace_depth,
+                stats.uniqueness_score,
+                stats.total_submissions,
+                stats.combined_score,
+                stats.reasoning_entropy,
+                stats.streak,
+            );
+        }
+        println!();
+    }
+
+    /// Print detailed statistics for a single contributor
+    pub fn display_contributor(&self, contributor_id: &str) {
+        match self.entries.get(contributor_id) {
+            Some(stats) => {
+                println!("👤 Contributor: {}", stats.contributor_id);
+                println!("   • Rank: {}", stats.rank);
+                println!("   • Max trace depth: {}", stats.trace_depth);
+                println!("   • Avg trace depth: {:.2}", stats.avg_trace_depth);
+                println!("   • Uniqueness: {:.3}", stats.uniqueness_score);
+                println!("   • Reasoning entropy: {:.3}", stats.reasoning_entropy);
+                println!("   • Estimated tokens: {}", stats.total_estimated_tokens);
+                println!("   • Submissions: {}", stats.total_submissions);
+                println!("   • Streak: {} days", stats.streak);
+                println!("   • Languages: {:?}", stats.languages_used);
+                println!("   • Backend: {}", stats.backend_used);
+                println!("   • Last updated: {}\n", stats.last_updated);
+            }
+            None => println!("Contributor {} not found\n", contributor_id),
+        }
+    }
+
+    /// Shields.io-style SVG badge showing a contributor's rank, colored by tier
+    pub fn generate_badge_svg(&self, contributor_id: &str, criteria: RankingCriteria) -> Option<String> {
+        let ranked = self.ranked(criteria);
+        let total = ranked.len();
+        let rank = ranked.iter().position(|s| s.contributor_id == contributor_id)? + 1;
+
+        let color = match Tier::from_rank(rank, total) {
+            Tier::Diamond => "#4c1",
+            Tier::Platinum => "#007ec6",
+            Tier::Gold => "#dfb317",
+            _ => "#9f9f9f",
+        };
+        let label = "qlg-rank";
+        let value = format!("#{} / {}", rank, total);
+
+        // Approximate Verdana 11px glyph width used by flat Shields badges
+        let label_width = label.len() * 7 + 10;
+        let value_width = value.len() * 7 + 10;
+        let width = label_width + value_width;
+
+        Some(format!(
+            concat!(
+                r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {value}">"##,
+                r##"<title>{label}: {value}</title>"##,
+                r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>"##,
+                r##"<clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>"##,
+                r##"<g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="#555"/><rect x="{label_width}" width="{value_width}" height="20" fill="{color}"/><rect width="{width}" height="20" fill="url(#s)"/></g>"##,
+                r##"<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">"##,
+                r##"<text x="{label_x}" y="14">{label}</text><text x="{value_x}" y="14">{value}</text></g>"##,
+                r##"</svg>"##,
+            ),
+            width = width,
+            label_width = label_width,
+            value_width = value_width,
+            color = color,
+            label = label,
+            value = value,
+            label_x = label_width / 2,
+            value_x = label_width + value_width / 2,
+        ))
+    }
+
+    /// Export ranked leaderboard as JSON
+    pub fn export_json(&self, criteria: RankingCriteria) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.ranked(criteria))
+    }
+
+    /// One `ContributorStats` JSON object per line, including soft-deleted
+    /// contributors, ordered by contributor id
+    pub fn export_jsonlines(&self) -> Result<String, serde_json::Error> {
+        let mut entries: Vec<&ContributorStats> = self.entries.values().collect();
+        entries.sort_by(|a, b| a.contributor_id.cmp(&b.contributor_id));
+
+        let mut out = String::new();
+        for stats in entries {
+            out.push_str(&serde_json::to_string(stats)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Rebuild a leaderboard from an `export_jsonlines` dump and rank it by combined score.
+    ///
+    /// Each contributor's history is reduced to a single log built from its latest
+    /// submission fields. That log carries no parent reference, so the stored
+    /// `network_centrality` is kept until the next submission is added; from then
+    /// on only references recorded after the load count. Blank lines are skipped;
+    /// lines that fail to parse are counted, and the leaderboard of the rest is
+    /// returned inside `LoadError::PartialLoad`.
+    pub fn load_from_jsonlines<R: std::io::BufRead>(reader: R) -> Result<Leaderboard, LoadError> {
+        let mut leaderboard = Leaderboard::new();
+        let mut loaded = 0;
+        let mut failed = 0;
+
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let stats: ContributorStats = match serde_json::from_str(&line) {
+                Ok(stats) => stats,
+                Err(_) => {
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            leaderboard.contributor_history.insert(stats.contributor_id.clone(), vec![ProvenanceLog {
+                trace_hash: stats.provenance_hash.clone(),
+                agent_sequence: Vec::new(),
+                contributor_id: stats.contributor_id.clone(),
+                backend_used: stats.backend_used.clone(),
+                timestamp: stats.last_updated,
+                trace_depth: stats.trace_depth,
+                weighted_trace_depth: stats.weighted_trace_depth,
+                uniqueness_score: stats.uniqueness_score,
+                transitions: Vec::new(),
+                parent_hash: None,
+                reasoning_entropy: stats.reasoning_entropy,
+                total_tokens_estimate: stats.total_estimated_tokens,
+                output_diversity: stats.output_diversity_score,
+                language_sequence: stats.languages_used.clone(),
+                session_metadata: HashMap::new(),
+                signature: None,
+            }]);
+            leaderboard.entries.insert(stats.contributor_id.clone(), stats);
+            loaded += 1;
+        }
+
+        leaderboard.reference_graph = ReferenceGraph::from_history(&leaderboard.contributor_history);
+        leaderboard.restored_centrality = leaderboard.stored_centrality();
+        leaderboard.update_ranks(RankingCriteria::Combined);
+        if failed > 0 {
+            return Err(LoadError::PartialLoad { leaderboard: Box::new(leaderboard), loaded, failed });
+        }
+        Ok(leaderboard)
+    }
+
+    /// Self-contained HTML report with a ranked table and a top-10 depth chart
+    pub fn export_html(&self, criteria: RankingCriteria, title: &str) -> String {
+        use std::fmt::Write;
+
+        let ranked = self.ranked(criteria);
+        let total = ranked.len();
+        let title = escape_html(title);
+
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            concat!(
+                "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n",
+                "<style>\n",
+                "body {{ font-family: sans-serif; margin: 2em; color: #222; }}\n",
+                "table {{ border-collapse: collapse; width: 100%; }}\n",
+                "th, td {{ padding: 6px 10px; border-bottom: 1px solid #ddd; text-align: left; }}\n",
+                "th {{ background: #f4f4f4; }}\n",
+                ".tier {{ padding: 2px 8px; border-radius: 4px; color: #fff; font-size: 0.85em; }}\n",
+                ".tier-Diamond {{ background: #4c1; }} .tier-Platinum {{ background: #007ec6; }}\n",
+                ".tier-Gold {{ background: #dfb317; }} .tier-Silver, .tier-Bronze {{ background: #9f9f9f; }}\n",
+                "</style>\n</head>\n<body>\n<h1>{title}</h1>\n<p>Ranked by {criteria}</p>\n",
+            ),
+            title = title,
+            criteria = criteria,
+        );
+
+        html.push_str("<table>\n<thead><tr><th>Rank</th><th>Contributor</th><th>Tier</th><th>Depth</th><th>Uniqueness</th><th>Submissions</th><th>Language</th><th>Last Updated</th></tr></thead>\n<tbody>\n");
+        for (i, stats) in ranked.iter().enumerate() {
+            let tier = Tier::from_rank(i + 1, total);
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td><span class=\"tier tier-{}\">{}</span></td><td>{}</td><td>{:.3}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                i + 1,
+                escape_html(&stats.contributor_id),
+                tier,
+                tier,
+                stats.trace_depth,
+                stats.uniqueness_score,
+                stats.total_submissions,
+                escape_html(stats.languages_used.first().map_or("", String::as_str)),
+                stats.last_updated.format("%Y-%m-%d"),
+            );
+        }
+        html.push_str("</tbody>\n</table>\n");
+
+        // Horizontal bar chart of the top 10 trace depths
+        let top: Vec<&&ContributorStats> = ranked.iter().take(10).collect();
+        let max_depth = top.iter().map(|s| s.trace_depth).max().unwrap_or(0).max(1);
+        let (label_width, bar_width, row_height) = (160, 400, 24);
+        let _ = writeln!(
+            html,
+            "<h2>Top {} by trace depth</h2>\n<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+            top.len(),
+            label_width + bar_width + 60,
+            top.len() * row_height,
+        );
+        for (i, stats) in top.iter().enumerate() {
+            let y = i * row_height;
+            let width = stats.trace_depth * bar_width / max_depth;
+            let _ = writeln!(
+                html,
+                "<text x=\"0\" y=\"{}\" font-size=\"12\">{}</text><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#007ec6\"/><text x=\"{}\" y=\"{}\" font-size=\"12\">{}</text>",
+                y + 16,
+                escape_html(&stats.contributor_id),
+                label_width,
+                y + 4,
+                width,
+                row_height - 8,
+                label_width + width + 6,
+                y + 16,
+                stats.trace_depth,
+            );
+        }
+        html.push_str("</svg>\n</body>\n</html>\n");
+        html
+    }
+
+    /// Export ranked leaderboard as CSV.
+    ///
+    /// List columns are `;`-separated; `confidence_profile` is written as
+    /// `agent=confidence` pairs sorted by agent.
+    pub fn export_csv(&self, criteria: RankingCriteria) -> Result<String, csv::Error> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record([
+            "rank", "contributor_id", "total_submissions", "trace_depth", "avg_trace_depth",
+            "uniqueness_score", "combined_score", "languages_used", "backend_used",
+            "last_updated", "confidence_profile",
+        ])?;
+
+        for (i, stats) in self.ranked(criteria).iter().enumerate() {
+            let mut profile: Vec<_> = stats.confidence_profile.iter().collect();
+            profile.sort_by(|a, b| a.0.cmp(b.0));
+            let profile = profile.iter()
+                .map(|(agent, confidence)| format!("{}={}", agent, confidence))
+                .collect::<Vec<_>>()
+                .join(";");
+
+            writer.write_record([
+                (i + 1).to_string(),
+                stats.contributor_id.clone(),
+                stats.total_submissions.to_string(),
+                stats.trace_depth.to_string(),
+                stats.avg_trace_depth.to_string(),
+                stats.uniqueness_score.to_string(),
+                stats.combined_score.to_string(),
+                stats.languages_used.join(";"),
+                stats.backend_used.clone(),
+                stats.last_updated.to_rfc3339(),
+                profile,
+            ])?;
+        }
+
+        let bytes = writer.into_inner().map_err(|e| csv::Error::from(e.into_error()))?;
+        String::from_utf8(bytes)
+            .map_err(|e| csv::Error::from(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+    }
+
+    /// Export the top `top_n` contributors as a GitHub-Flavored Markdown table.
+    ///
+    /// Columns are padded to a common width; `|` in cell text is escaped.
+    pub fn export_to_markdown_table(&self, criteria: RankingCriteria, top_n: usize) -> String {
+        let ranked = self.ranked(criteria);
+        let total = ranked.len();
+
+        let header = ["Rank", "Contributor", "Depth", "Uniqueness", "Submissions", "Tier", "Languages", "Backend"];
+        let rows: Vec<Vec<String>> = ranked.iter()
+            .take(top_n)
+            .enumerate()
+            .map(|(i, stats)| vec![
+                (i + 1).to_string(),
+                escape_markdown_cell(&stats.contributor_id),
+                stats.trace_depth.to_string(),
+                format!("{:.3}", stats.uniqueness_score),
+                stats.total_submissions.to_string(),
+                Tier::from_rank(i + 1, total).to_string(),
+                escape_markdown_cell(&stats.languages_used.join(", ")),
+                escape_markdown_cell(&stats.backend_used),
+            ])
+            .collect();
+
+        let widths: Vec<usize> = (0..header.len())
+            .map(|col| {
+                rows.iter()
+                    .map(|row| row[col].chars().count())
+                    .chain(std::iter::once(header[col].len()))
+                    .max()
+                    .unwrap_or(0)
+                    .max(3)
+            })
+            .collect();
+
+        let format_row = |cells: &mut dyn Iterator<Item = &str>| {
+            let padded: Vec<String> = cells.zip(&widths)
+                .map(|(cell, width)| format!(" {:<width$} ", cell, width = width))
+                .collect();
+            format!("|{}|\n", padded.join("|"))
+        };
+
+        let mut table = format_row(&mut header.iter().copied());
+        let separator: Vec<String> = widths.iter().map(|w| "-".repeat(w + 2)).collect();
+        table.push_str(&format!("|{}|\n", separator.join("|")));
+        for row in &rows {
+            table.push_str(&format_row(&mut row.iter().map(String::as_str)));
+        }
+        table
+    }
+}
+
+/// Escape text for a Markdown table cell
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Escape text for inclusion in HTML element content or attribute values
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// (agent type, language) pairs of a provenance log; logs without a language
+/// sequence pair every agent with an empty language
+fn provenance_pairs(log: &ProvenanceLog) -> impl Iterator<Item = (&AgentType, &str)> {
+    log.agent_sequence.iter()
+        .enumerate()
+        .map(|(i, agent)| (agent, log.language_sequence.get(i).map_or("", String::as_str)))
+}
+
+#[cfg(feature = "prometheus")]
+impl Leaderboard {
+    /// Render leaderboard metrics in the Prometheus text exposition format
+    pub fn export_prometheus_metrics(&self) -> String {
+        use std::fmt::Write;
+
+        let top_trace_depth = self.active_entries().map(|s| s.trace_depth).max().unwrap_or(0);
+        let active_count = self.total_contributors();
+        let mean_uniqueness = if active_count == 0 {
+            0.0
+        } else {
+            self.active_entries().map(|s| s.uniqueness_score).sum::<f64>() / active_count as f64
+        };
+
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            let _ = writeln!(out, "{} {}", name, value);
+        };
+        metric("leaderboard_total_contributors", "gauge", "Number of ranked contributors", self.total_contributors().to_string());
+        metric("leaderboard_total_submissions", "counter", "Provenance logs submitted", self.total_submissions().to_string());
+        metric("leaderboard_top_trace_depth", "gauge", "Deepest trace on the leaderboard", top_trace_depth.to_string());
+        metric("leaderboard_mean_uniqueness_score", "gauge", "Mean contributor uniqueness score", mean_uniqueness.to_string());
+
+        let _ = writeln!(out, "# HELP leaderboard_contributor_rank Combined-score rank per contributor");
+        let _ = writeln!(out, "# TYPE leaderboard_contributor_rank gauge");
+        for stats in self.rank_combined() {
+            let _ = writeln!(
+                out,
+                "leaderboard_contributor_rank{{contributor_id=\"{}\",backend=\"{}\"}} {}",
+                escape_label_value(&stats.contributor_id),
+                escape_label_value(&stats.backend_used),
+                stats.rank,
+            );
+        }
+        out
+    }
+}
+
+/// Escape a Prometheus label value (backslash, double quote, newline)
+#[cfg(feature = "prometheus")]
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(feature = "kafka")]
+impl Leaderboard {
+    /// Create an empty leaderboard that publishes updates to a Kafka topic.
+    ///
+    /// Each `add_entry` produces the contributor's updated stats as JSON keyed by
+    /// contributor id; rank changes go to `{topic}.ranks`.
+    pub fn with_kafka_producer(brokers: &str, topic: &str) -> Result<Leaderboard, KafkaError> {
+        Ok(Self::with_producer(std::sync::Arc::new(RdKafkaProducer::new(brokers)?), topic))
+    }
+
+    /// Create an empty leaderboard that publishes updates through `producer`
+    pub fn with_producer(producer: std::sync::Arc<dyn LeaderboardProducer>, topic: &str) -> Leaderboard {
+        Leaderboard {
+            kafka: Some(KafkaSink {
+                producer,
+                topic: topic.to_string(),
+                failed_sends: Default::default(),
+            }),
+            ..Leaderboard::new()
+        }
+    }
+
+    /// Flush queued messages, reporting any sends that failed since the last flush
+    pub fn flush_kafka(&self) -> Result<(), KafkaError> {
+        let Some(sink) = &self.kafka else {
+            return Ok(());
+        };
+        sink.producer.flush()?;
+        match sink.failed_sends.swap(0, std::sync::atomic::Ordering::SeqCst) {
+            0 => Ok(()),
+            failed => Err(KafkaError::UndeliveredMessages(failed)),
+        }
+    }
+
+    /// Send a message, counting failures for the next `flush_kafka`
+    fn publish(sink: &KafkaSink, topic: &str, key: &str, payload: Result<String, serde_json::Error>) {
+        let sent = payload
+            .map_err(KafkaError::from)
+            .and_then(|payload| sink.producer.send(topic, key, &payload));
+        if sent.is_err() {
+            sink.failed_sends.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    /// Publish a contributor's current stats to the stats topic
+    fn publish_stats(&self, contributor_id: &str) {
+        if let (Some(sink), Some(stats)) = (&self.kafka, self.entries.get(contributor_id)) {
+            Self::publish(sink, &sink.topic, contributor_id, serde_json::to_string(stats));
+        }
+    }
+
+    /// Publish `{contributor_id, previous_rank, rank}` for every contributor whose rank moved
+    fn publish_rank_changes(&self, previous_ranks: &HashMap<String, usize>) {
+        let Some(sink) = &self.kafka else {
+            return;
+        };
+        let rank_topic = format!("{}{}", sink.topic, RANK_TOPIC_SUFFIX);
+
+        let mut changed: Vec<&ContributorStats> = self.entries.values()
+            .filter(|s| previous_ranks.get(&s.contributor_id).copied().unwrap_or(0) != s.rank)
+            .collect();
+        changed.sort_by(|a, b| a.contributor_id.cmp(&b.contributor_id));
+
+        for stats in changed {
+            let message = serde_json::json!({
+                "contributor_id": stats.contributor_id,
+                "previous_rank": previous_ranks.get(&stats.contributor_id).copied().unwrap_or(0),
+                "rank": stats.rank,
+            });
+            Self::publish(sink, &rank_topic, &stats.contributor_id, serde_json::to_string(&message));
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl Leaderboard {
+    /// Persist contributor stats and provenance history to a SQLite database.
+    ///
+    /// Existing `contributors` and `provenance_history` tables are replaced.
+    /// `languages_used` is stored as a JSON array column.
+    pub fn export_to_sqlite(&self, path: &std::path::Path) -> Result<(), SqliteError> {
+        let mut conn = rusqlite::Connection::open(path)?;
+        let tx = conn.transaction()?;
+
+        tx.execute_batch(
+            "DROP TABLE IF EXISTS contributors;
+             DROP TABLE IF EXISTS provenance_history;
+             CREATE TABLE contributors (
+                 contributor_id TEXT PRIMARY KEY,
+                 total_submissions INTEGER NOT NULL,
+                 trace_depth INTEGER NOT NULL,
+                 avg_trace_depth REAL NOT NULL,
+                 uniqueness_score REAL NOT NULL,
+                 languages_used TEXT NOT NULL,
+                 backend_used TEXT NOT NULL,
+                 provenance_hash TEXT NOT NULL,
+                 last_updated TEXT NOT NULL,
+                 combined_score REAL NOT NULL,
+                 rank INTEGER NOT NULL,
+                 normalized_submission_rate REAL NOT NULL,
+                 reasoning_entropy REAL NOT NULL,
+                 total_estimated_tokens INTEGER NOT NULL,
+                 deleted INTEGER NOT NULL,
+                 confidence_profile TEXT NOT NULL,
+                 improvement_score REAL NOT NULL,
+                 streak INTEGER NOT NULL,
+                 output_diversity_score REAL NOT NULL,
+                 percentile_rank REAL NOT NULL,
+                 profile TEXT NOT NULL,
+                 weighted_trace_depth REAL NOT NULL,
+                 recent_improvement_rate REAL NOT NULL,
+                 network_centrality REAL NOT NULL
+             );
+             CREATE TABLE provenance_history (
+                 contributor_id TEXT NOT NULL,
+                 trace_hash TEXT NOT NULL,
+                 trace_depth INTEGER NOT NULL,
+                 uniqueness_score REAL NOT NULL,
+                 timestamp TEXT NOT NULL,
+                 backend_used TEXT NOT NULL,
+                 parent_hash TEXT,
+                 reasoning_entropy REAL NOT NULL,
+                 total_tokens_estimate INTEGER NOT NULL,
+                 output_diversity REAL NOT NULL,
+                 weighted_trace_depth REAL NOT NULL
+             );",
+        )?;
+
+        for stats in self.entries.values() {
+            tx.execute(
+                "INSERT INTO contributors VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24)",
+                rusqlite::params![
+                    stats.contributor_id,
+                    stats.total_submissions as i64,
+                    stats.trace_depth as i64,
+                    stats.avg_trace_depth,
+                    stats.uniqueness_score,
+                    serde_json::to_string(&stats.languages_used)?,
+                    stats.backend_used,
+                    stats.provenance_hash,
+                    stats.last_updated.to_rfc3339(),
+                    stats.combined_score,
+                    stats.rank as i64,
+                    stats.normalized_submission_rate,
+                    stats.reasoning_entropy,
+                    stats.total_estimated_tokens as i64,
+                    stats.deleted,
+                    serde_json::to_string(&stats.confidence_profile)?,
+                    stats.improvement_score,
+                    stats.streak as i64,
+                    stats.output_diversity_score,
+                    stats.percentile_rank,
+                    serde_json::to_string(&stats.profile)?,
+                    stats.weighted_trace_depth,
+                    stats.recent_improvement_rate,
+                    stats.network_centrality,
+                ],
+            )?;
+        }
+
+        for (contributor_id, history) in &self.contributor_history {
+            for log in history {
+                tx.execute(
+                    "INSERT INTO provenance_history VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                    rusqlite::params![
+                        contributor_id,
+                        log.trace_hash,
+                        log.trace_depth as i64,
+                        log.uniqueness_score,
+                        log.timestamp.to_rfc3339(),
+                        log.backend_used,
+                        log.parent_hash,
+                        log.reasoning_entropy,
+                        log.total_tokens_estimate as i64,
+                        log.output_diversity,
+                        log.weighted_trace_depth,
+                    ],
+                )?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Reconstruct a leaderboard previously written by `export_to_sqlite`.
+    ///
+    /// Restored history entries carry no `agent_sequence`, `language_sequence`,
+    /// `transitions`, `session_metadata`, or `signature`, since those are not persisted.
+    pub fn load_from_sqlite(path: &std::path::Path) -> Result<Leaderboard, SqliteError> {
+        let conn = rusqlite::Connection::open(path)?;
+        let mut leaderboard = Leaderboard::new();
+
+        let mut stmt = conn.prepare(
+            "SELECT contributor_id, total_submissions, trace_depth, avg_trace_depth, uniqueness_score,
+                    languages_used, backend_used, provenance_hash, last_updated, combined_score, rank,
+                    normalized_submission_rate, reasoning_entropy, total_estimated_tokens,
+                    deleted, confidence_profile, improvement_score, streak, output_diversity_score,
+                    percentile_rank, profile, weighted_trace_depth, recent_improvement_rate, network_centrality
+             FROM contributors",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, f64>(3)?,
+                row.get::<_, f64>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, String>(7)?,
+                row.get::<_, String>(8)?,
+                row.get::<_, f64>(9)?,
+                row.get::<_, i64>(10)?,
+                row.get::<_, f64>(11)?,
+                row.get::<_, f64>(12)?,
+                row.get::<_, i64>(13)?,
+                row.get::<_, bool>(14)?,
+                row.get::<_, String>(15)?,
+                row.get::<_, f64>(16)?,
+                row.get::<_, i64>(17)?,
+                row.get::<_, f64>(18)?,
+                row.get::<_, f64>(19)?,
+                row.get::<_, String>(20)?,
+                row.get::<_, f64>(21)?,
+                row.get::<_, f64>(22)?,
+                row.get::<_, f64>(23)?,
+            ))
+        })?;
+
+        for row in rows {
+            let (contributor_id, total_submissions, trace_depth, avg_trace_depth, uniqueness_score,
+                 languages_used, backend_used, provenance_hash, last_updated, combined_score, rank,
+                 normalized_submission_rate, reasoning_entropy, total_estimated_tokens, deleted,
+                 confidence_profile, improvement_score, streak, output_diversity_score, percentile_rank,
+                 profile, weighted_trace_depth, recent_improvement_rate, network_centrality) = row?;
+
+            leaderboard.entries.insert(contributor_id.clone(), ContributorStats {
+                contributor_id,
+                total_submissions: total_submissions as usize,
+                trace_depth: trace_depth as usize,
+                weighted_trace_depth,
+                avg_trace_depth,
+                uniqueness_score,
+                languages_used: serde_json::from_str(&languages_used)?,
+                backend_used,
+                provenance_hash,
+                last_updated: DateTime::parse_from_rfc3339(&last_updated)?.with_timezone(&Utc),
+                combined_score,
+                rank: rank as usize,
+                normalized_submission_rate,
+                reasoning_entropy,
+                total_estimated_tokens: total_estimated_tokens as usize,
+                deleted,
+                confidence_profile: serde_json::from_str(&confidence_profile)?,
+                improvement_score,
+                recent_improvement_rate,
+                streak: streak as usize,
+                output_diversity_score,
+                percentile_rank,
+                network_centrality,
+                profile: serde_json::from_str(&profile)?,
+            });
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT contributor_id, trace_hash, trace_depth, uniqueness_score, timestamp, backend_used, parent_hash,
+                    reasoning_entropy, total_tokens_estimate, output_diversity, weighted_trace_depth
+             FROM provenance_history ORDER BY rowid",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, f64>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, Option<String>>(6)?,
+                row.get::<_, f64>(7)?,
+                row.get::<_, i64>(8)?,
+                row.get::<_, f64>(9)?,
+                row.get::<_, f64>(10)?,
+            ))
+        })?;
+
+        for row in rows {
+            let (contributor_id, trace_hash, trace_depth, uniqueness_score, timestamp, backend_used, parent_hash,
+                 reasoning_entropy, total_tokens_estimate, output_diversity, weighted_trace_depth) = row?;
+
+            leaderboard.contributor_history
+                .entry(contributor_id.clone())
+                .or_default()
+                .push(ProvenanceLog {
+                    trace_hash,
+                    agent_sequence: Vec::new(),
+                    contributor_id,
+                    backend_used,
+                    timestamp: DateTime::parse_from_rfc3339(&timestamp)?.with_timezone(&Utc),
+                    trace_depth: trace_depth as usize,
+                    weighted_trace_depth,
+                    uniqueness_score,
+                    transitions: Vec::new(),
+                    parent_hash,
+                    reasoning_entropy,
+                    total_tokens_estimate: total_tokens_estimate as usize,
+                    output_diversity,
+                    language_sequence: Vec::new(),
+                    session_metadata: HashMap::new(),
+                    signature: None,
+                });
+        }
+        leaderboard.reference_graph = ReferenceGraph::from_history(&leaderboard.contributor_history);
+
+        Ok(leaderboard)
+    }
+}
+
+/// Schema migrations for the Postgres `contributors` table, embedded from `migrations/`
+#[cfg(feature = "postgres")]
+static POSTGRES_MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
+
+#[cfg(feature = "postgres")]
+impl Leaderboard {
+    /// Create or upgrade the `contributors` table by applying pending migrations.
+    ///
+    /// Run this once at deploy time, before `export_to_postgres` or `load_from_postgres`.
+    pub async fn migrate_postgres(pool: &sqlx::PgPool) -> Result<(), sqlx::migrate::MigrateError> {
+        POSTGRES_MIGRATOR.run(pool).await
+    }
+
+    /// Upsert every contributor's stats into the Postgres `contributors` table.
+    ///
+    /// Expects the schema from `migrate_postgres`. Rows for contributors missing from
+    /// this leaderboard are left untouched. `languages_used` is stored as `text[]`;
+    /// `confidence_profile` and `profile` as `jsonb`.
+    pub async fn export_to_postgres(&self, pool: &sqlx::PgPool) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        for stats in self.entries.values() {
+            sqlx::query(
+                "INSERT INTO contributors (
+                     contributor_id, total_submissions, trace_depth, weighted_trace_depth, avg_trace_depth,
+                     uniqueness_score, languages_used, backend_used, provenance_hash, last_updated,
+                     combined_score, rank, normalized_submission_rate, reasoning_entropy,
+                     total_estimated_tokens, deleted, confidence_profile, improvement_score,
+                     recent_improvement_rate, streak, output_diversity_score, percentile_rank, profile,
+                     network_centrality
+                 ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24)
+                 ON CONFLICT (contributor_id) DO UPDATE SET
+                     total_submissions = EXCLUDED.total_submissions,
+                     trace_depth = EXCLUDED.trace_depth,
+                     weighted_trace_depth = EXCLUDED.weighted_trace_depth,
+                     avg_trace_depth = EXCLUDED.avg_trace_depth,
+                     uniqueness_score = EXCLUDED.uniqueness_score,
+                     languages_used = EXCLUDED.languages_used,
+                     backend_used = EXCLUDED.backend_used,
+                     provenance_hash = EXCLUDED.provenance_hash,
+                     last_updated = EXCLUDED.last_updated,
+                     combined_score = EXCLUDED.combined_score,
+                     rank = EXCLUDED.rank,
+                     normalized_submission_rate = EXCLUDED.normalized_submission_rate,
+                     reasoning_entropy = EXCLUDED.reasoning_entropy,
+                     total_estimated_tokens = EXCLUDED.total_estimated_tokens,
+                     deleted = EXCLUDED.deleted,
+                     confidence_profile = EXCLUDED.confidence_profile,
+                     improvement_score = EXCLUDED.improvement_score,
+                     recent_improvement_rate = EXCLUDED.recent_improvement_rate,
+                     streak = EXCLUDED.streak,
+                     output_diversity_score = EXCLUDED.output_diversity_score,
+                     percentile_rank = EXCLUDED.percentile_rank,
+                     profile = EXCLUDED.profile,
+                     network_centrality = EXCLUDED.network_centrality",
+            )
+            .bind(&stats.contributor_id)
+            .bind(stats.total_submissions as i64)
+            .bind(stats.trace_depth as i64)
+            .bind(stats.weighted_trace_depth)
+            .bind(stats.avg_trace_depth)
+            .bind(stats.uniqueness_score)
+            .bind(&stats.languages_used)
+            .bind(&stats.backend_used)
+            .bind(&stats.provenance_hash)
+            .bind(stats.last_updated)
+            .bind(stats.combined_score)
+            .bind(stats.rank as i64)
+            .bind(stats.normalized_submission_rate)
+            .bind(stats.reasoning_entropy)
+            .bind(stats.total_estimated_tokens as i64)
+            .bind(stats.deleted)
+            .bind(sqlx::types::Json(&stats.confidence_profile))
+            .bind(stats.improvement_score)
+            .bind(stats.recent_improvement_rate)
+            .bind(stats.streak as i64)
+            .bind(stats.output_diversity_score)
+            .bind(stats.percentile_rank)
+            .bind(sqlx::types::Json(&stats.profile))
+            .bind(stats.network_centrality)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// Reconstruct a leaderboard from the `contributors` table written by `export_to_postgres`.
+    ///
+    /// Only stats are restored; provenance history is not persisted to Postgres,
+    /// so the stored `network_centrality` is kept until the next submission is
+    /// added, as with `load_from_jsonlines`. `last_updated` comes back at the
+    /// column's microsecond precision.
+    pub async fn load_from_postgres(pool: &sqlx::PgPool) -> Result<Leaderboard, sqlx::Error> {
+        use sqlx::Row;
+
+        let rows = sqlx::query("SELECT * FROM contributors").fetch_all(pool).await?;
+        let mut leaderboard = Leaderboard::new();
+
+        for row in rows {
+            let contributor_id: String = row.try_get("contributor_id")?;
+            let confidence_profile: sqlx::types::Json<HashMap<String, f64>> = row.try_get("confidence_profile")?;
+            let profile: sqlx::types::Json<ContributorProfile> = row.try_get("profile")?;
+
+            leaderboard.entries.insert(contributor_id.clone(), ContributorStats {
+                contributor_id,
+                total_submissions: row.try_get::<i64, _>("total_submissions")? as usize,
+                trace_depth: row.try_get::<i64, _>("trace_depth")? as usize,
+                weighted_trace_depth: row.try_get("weighted_trace_depth")?,
+                avg_trace_depth: row.try_get("avg_trace_depth")?,
+                uniqueness_score: row.try_get("uniqueness_score")?,
+                languages_used: row.try_get("languages_used")?,
+                backend_used: row.try_get("backend_used")?,
+                provenance_hash: row.try_get("provenance_hash")?,
+                last_updated: row.try_get("last_updated")?,
+                combined_score: row.try_get("combined_score")?,
+                rank: row.try_get::<i64, _>("rank")? as usize,
+                normalized_submission_rate: row.try_get("normalized_submission_rate")?,
+                reasoning_entropy: row.try_get("reasoning_entropy")?,
+                total_estimated_tokens: row.try_get::<i64, _>("total_estimated_tokens")? as usize,
+                deleted: row.try_get("deleted")?,
+                confidence_profile: confidence_profile.0,
+                improvement_score: row.try_get("improvement_score")?,
+                recent_improvement_rate: row.try_get("recent_improvement_rate")?,
+                streak: row.try_get::<i64, _>("streak")? as usize,
+                output_diversity_score: row.try_get("output_diversity_score")?,
+                percentile_rank: row.try_get("percentile_rank")?,
+                network_centrality: row.try_get("network_centrality")?,
+                profile: profile.0,
+            });
+        }
+        leaderboard.restored_centrality = leaderboard.stored_centrality();
+
+        Ok(leaderboard)
+    }
+}
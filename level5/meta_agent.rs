@@ -5,6 +5,9 @@ use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use sha2::{Sha256, Digest};
 use serde::{Serialize, Deserialize};
+use tokio::sync::broadcast;
+
+use super::leaderboard::{ContributorStats, Leaderboard};
 
 /// Agent types in the MetaAgent system
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -19,6 +22,38 @@ pub enum AgentType {
     Translation,
 }
 
+impl AgentType {
+    /// All named agent types in canonical order
+    pub fn all() -> impl Iterator<Item = AgentType> {
+        [
+            AgentType::Classification,
+            AgentType::Reasoning,
+            AgentType::Action,
+            AgentType::Retrieval,
+            AgentType::Meta,
+            AgentType::Synthesis,
+            AgentType::Validation,
+            AgentType::Translation,
+        ]
+        .into_iter()
+    }
+
+    /// Number of named agent types
+    pub fn count() -> usize {
+        8
+    }
+
+    /// Contribution of one event of this type to weighted trace depth
+    pub fn weight_for_depth_scoring(&self) -> f64 {
+        match self {
+            AgentType::Synthesis => 1.5,
+            AgentType::Validation => 1.2,
+            AgentType::Classification => 0.8,
+            _ => 1.0,
+        }
+    }
+}
+
 impl std::fmt::Display for AgentType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -44,6 +79,181 @@ pub struct AgentEvent {
     pub language: String,
     pub confidence: f64,
     pub metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Estimated API cost in USD under the logging agent's cost model (0.0 if unpriced)
+    #[serde(default)]
+    pub estimated_cost_usd: f64,
+    /// Metadata moved out of `metadata` by `MetaAgent::compress_metadata`; read the
+    /// event's full metadata through `metadata_value` and `full_metadata`
+    #[serde(default, skip_serializing_if = "InternedMetadata::is_empty")]
+    pub interned_metadata: InternedMetadata,
+}
+
+impl AgentEvent {
+    /// One metadata value, whether compressed or not
+    pub fn metadata_value(&self, key: &str) -> Option<&str> {
+        match self.interned_metadata.get(key) {
+            Some(value) => Some(value),
+            None => self.metadata.get(key).map(String::as_str),
+        }
+    }
+
+    /// Full string metadata, whether compressed or not
+    pub fn full_metadata(&self) -> HashMap<String, String> {
+        let mut metadata = self.metadata.clone();
+        for (key, value) in self.interned_metadata.iter() {
+            metadata.insert(key.to_string(), value.to_string());
+        }
+        metadata
+    }
+
+    /// Approximate LLM token count of input and output (words / 0.75, rounded up)
+    pub fn estimate_tokens(&self) -> usize {
+        tokens_for_words(self.input.split_whitespace().count() + self.output.split_whitespace().count())
+    }
+
+    /// Start building an event; only the agent type is required
+    pub fn builder() -> AgentEventBuilder {
+        AgentEventBuilder::default()
+    }
+}
+
+/// Event metadata stored as indices into a string table shared by the events
+/// compressed together, so each distinct value is held once.
+///
+/// Serializes as a plain key-to-value map.
+#[derive(Debug, Clone, Default)]
+pub struct InternedMetadata {
+    strings: std::sync::Arc<Vec<String>>,
+    ids: HashMap<String, u32>,
+}
+
+impl InternedMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.ids.get(key).map(|id| self.strings[*id as usize].as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.ids.iter().map(|(key, id)| (key.as_str(), self.strings[*id as usize].as_str()))
+    }
+}
+
+impl Serialize for InternedMetadata {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for InternedMetadata {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = HashMap::<String, String>::deserialize(deserializer)?;
+        let mut strings = Vec::with_capacity(map.len());
+        let ids = map.into_iter()
+            .map(|(key, value)| {
+                strings.push(value);
+                (key, (strings.len() - 1) as u32)
+            })
+            .collect();
+        Ok(InternedMetadata { strings: std::sync::Arc::new(strings), ids })
+    }
+}
+
+/// Errors returned by `AgentEventBuilder::build`
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum BuilderError {
+    #[error("agent type was not set")]
+    MissingAgent,
+    #[error("confidence must be within [0.0, 1.0]")]
+    InvalidConfidence,
+}
+
+/// Builder for `AgentEvent` with defaults for everything but the agent type.
+///
+/// Defaults: empty input and output, language `"en"`, confidence `1.0`, no
+/// metadata or tags, and the timestamp at `build` time.
+#[derive(Debug, Clone, Default)]
+pub struct AgentEventBuilder {
+    timestamp: Option<DateTime<Utc>>,
+    agent: Option<AgentType>,
+    input: String,
+    output: String,
+    language: Option<String>,
+    confidence: Option<f64>,
+    metadata: HashMap<String, String>,
+    tags: Vec<String>,
+}
+
+impl AgentEventBuilder {
+    pub fn timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    pub fn agent(mut self, agent: AgentType) -> Self {
+        self.agent = Some(agent);
+        self
+    }
+
+    pub fn input(mut self, input: &str) -> Self {
+        self.input = input.to_string();
+        self
+    }
+
+    pub fn output(mut self, output: &str) -> Self {
+        self.output = output.to_string();
+        self
+    }
+
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    pub fn confidence(mut self, confidence: f64) -> Self {
+        self.confidence = Some(confidence);
+        self
+    }
+
+    pub fn metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Validate and assemble the event
+    pub fn build(self) -> Result<AgentEvent, BuilderError> {
+        let agent = self.agent.ok_or(BuilderError::MissingAgent)?;
+        let confidence = self.confidence.unwrap_or(1.0);
+        if !(0.0..=1.0).contains(&confidence) {
+            return Err(BuilderError::InvalidConfidence);
+        }
+
+        Ok(AgentEvent {
+            timestamp: self.timestamp.unwrap_or_else(Utc::now),
+            agent,
+            input: self.input,
+            output: self.output,
+            language: self.language.unwrap_or_else(|| "en".to_string()),
+            confidence,
+            metadata: self.metadata,
+            tags: self.tags,
+            estimated_cost_usd: 0.0,
+            interned_metadata: InternedMetadata::default(),
+        })
+    }
 }
 
 /// Agent transition tracking
@@ -56,6 +266,14 @@ pub struct AgentTransition {
     pub transition_score: f64,
 }
 
+/// Timestamp ordering violation detected in a trace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderingViolation {
+    pub index: usize,
+    pub event_timestamp: DateTime<Utc>,
+    pub previous_timestamp: DateTime<Utc>,
+}
+
 /// Memory fold with hierarchical compression
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryFold {
@@ -65,6 +283,178 @@ pub struct MemoryFold {
     pub compression_ratio: f64,
     pub key_insights: Vec<String>,
     pub language_distribution: HashMap<String, usize>,
+    /// Distinct languages in order of first appearance
+    pub language_sequence: Vec<String>,
+    /// Number of consecutive event pairs whose languages differ
+    pub language_transition_count: usize,
+    pub rle_agent_sequence: Vec<(AgentType, usize)>,
+    pub language_entropy: f64,
+    pub session_score: f64,
+    pub transition_entropy: f64,
+    pub total_estimated_tokens: usize,
+    pub max_consecutive_same_agent: usize,
+    pub top_output_words: Vec<String>,
+    pub session_metadata: HashMap<String, String>,
+    pub translation_pairs: Vec<(String, String)>,
+    /// Per language, the fraction of its events handled by each agent type (keyed by name)
+    pub language_agent_affinity: HashMap<String, HashMap<String, f64>>,
+    /// Fraction of the profile's expertise domains engaged when the fold was created
+    pub domain_coverage_ratio: f64,
+    /// Moving-average type-token ratio of all event outputs; see `MetaAgent::compute_output_diversity`
+    pub output_diversity: f64,
+    /// Mean Jaccard similarity between word sets of consecutive event outputs
+    pub semantic_coherence_proxy: f64,
+    /// Sum of the folded events' `estimated_cost_usd`
+    pub total_cost_usd: f64,
+    pub inferred_task: Task,
+    /// Reasoning loops found with the default detection thresholds
+    pub detected_loops: usize,
+    /// Folded events whose input quotes an earlier event's output; see
+    /// `MetaAgent::count_back_references`
+    #[serde(default)]
+    pub back_reference_count: usize,
+    /// Sentence describing how the session moved between agents
+    pub transition_narrative: String,
+    /// Running totals that let `apply_delta` visit only the new events
+    #[serde(skip)]
+    aggregates: FoldAggregates,
+}
+
+/// Running totals over a fold's events, updated one event at a time
+#[derive(Debug, Clone, Default)]
+struct FoldAggregates {
+    /// Number of leading `folded_trace` events already counted
+    events: usize,
+    total_chars: usize,
+    confidence_sum: f64,
+    high_confidence_count: usize,
+    agent_counts: HashMap<AgentType, usize>,
+    language_agent_counts: HashMap<String, HashMap<AgentType, usize>>,
+    transition_counts: HashMap<(AgentType, AgentType), usize>,
+    transition_score_sum: f64,
+    transition_score_count: usize,
+    /// Non-stopword output terms and their counts
+    output_word_counts: HashMap<String, usize>,
+    output_diversity: MovingTypeTokenRatio,
+    coherence_sum: f64,
+    last_agent: Option<AgentType>,
+    /// Confidences of the last three events, oldest first
+    recent_confidences: std::collections::VecDeque<f64>,
+    last_output_words: Option<std::collections::HashSet<String>>,
+}
+
+impl FoldAggregates {
+    fn from_events(events: &[AgentEvent]) -> Self {
+        let mut aggregates = Self::default();
+        aggregates.extend(events);
+        aggregates
+    }
+
+    /// Count `events` as following the ones already counted
+    fn extend(&mut self, events: &[AgentEvent]) {
+        for event in events {
+            self.total_chars += event.input.len() + event.output.len();
+            self.confidence_sum += event.confidence;
+            if event.confidence > 0.8 {
+                self.high_confidence_count += 1;
+            }
+            *self.agent_counts.entry(event.agent.clone()).or_insert(0) += 1;
+            *self.language_agent_counts.entry(event.language.clone())
+                .or_default()
+                .entry(event.agent.clone())
+                .or_insert(0) += 1;
+
+            if let Some(previous) = self.last_agent.take().filter(|agent| *agent != event.agent) {
+                // Same score as `preceding_confidence` for an inferred transition
+                self.transition_score_sum += self.recent_confidences.iter().sum::<f64>()
+                    / self.recent_confidences.len() as f64;
+                self.transition_score_count += 1;
+                *self.transition_counts.entry((previous, event.agent.clone())).or_insert(0) += 1;
+            }
+            self.last_agent = Some(event.agent.clone());
+            self.recent_confidences.push_back(event.confidence);
+            if self.recent_confidences.len() > 3 {
+                self.recent_confidences.pop_front();
+            }
+
+            let words: std::collections::HashSet<String> = normalized_words(&event.output).collect();
+            for word in normalized_words(&event.output) {
+                if !STOPWORDS.contains(&word.as_str()) {
+                    *self.output_word_counts.entry(word.clone()).or_insert(0) += 1;
+                }
+                self.output_diversity.push(word);
+            }
+            if let Some(previous) = &self.last_output_words {
+                self.coherence_sum += jaccard_similarity(previous, &words);
+            }
+            self.last_output_words = Some(words);
+            self.events += 1;
+        }
+    }
+}
+
+/// Holistic quality score for a reasoning session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionScore {
+    pub confidence_score: f64,
+    pub diversity_score: f64,
+    pub depth_score: f64,
+    pub transition_quality_score: f64,
+    pub overall: f64,
+}
+
+/// Estimated share of an LLM context window taken up by a trace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextWindowUsage {
+    pub estimated_tokens: usize,
+    pub fraction_used: f64,
+    pub events_that_fit: usize,
+    pub overflow: bool,
+}
+
+/// Activity and confidence of one agent type within a trace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentStats {
+    pub agent: AgentType,
+    pub event_count: usize,
+    pub mean_confidence: f64,
+    pub min_confidence: f64,
+    pub max_confidence: f64,
+    pub estimated_tokens: usize,
+}
+
+/// Dense transition counts; `counts[i][j]` counts transitions from `agents[i]` to `agents[j]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransitionMatrix {
+    pub agents: Vec<AgentType>,
+    pub counts: Vec<Vec<usize>>,
+}
+
+/// Which declared expertise domains a session actually touched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainCoverageReport {
+    pub declared_domains: Vec<String>,
+    pub engaged_domains: Vec<String>,
+    pub coverage_ratio: f64,
+}
+
+/// All trace analytics for a session in one document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceReport {
+    pub session_id: String,
+    pub contributor_id: String,
+    pub session_score: SessionScore,
+    pub memory_fold: MemoryFold,
+    pub provenance: ProvenanceLog,
+    pub agent_stats: Vec<AgentStats>,
+    pub transition_matrix: TransitionMatrix,
+    pub top_words: Vec<(String, usize)>,
+    pub language_entropy: f64,
+    pub reasoning_entropy: f64,
+    pub confidence_drift: Vec<f64>,
+    pub reasoning_velocity: Option<f64>,
+    pub event_velocity: Option<f64>,
+    pub total_cost_usd: f64,
 }
 
 /// Provenance log with SHA-256 hash for originality detection
@@ -76,12 +466,157 @@ pub struct ProvenanceLog {
     pub backend_used: String,
     pub timestamp: DateTime<Utc>,
     pub trace_depth: usize,
+    /// Trace depth with per-agent-type weights applied
+    #[serde(default)]
+    pub weighted_trace_depth: f64,
     pub uniqueness_score: f64,
     pub transitions: Vec<AgentTransition>,
+    #[serde(default)]
+    pub parent_hash: Option<String>,
+    #[serde(default)]
+    pub reasoning_entropy: f64,
+    #[serde(default)]
+    pub total_tokens_estimate: usize,
+    /// Moving-average type-token ratio of the trace's event outputs
+    #[serde(default)]
+    pub output_diversity: f64,
+    #[serde(default)]
+    pub language_sequence: Vec<String>,
+    #[serde(default)]
+    pub session_metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub signature: Option<Vec<u8>>,
+}
+
+/// Reason a provenance chain failed verification
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ChainError {
+    #[error("log {0} is missing its parent hash")]
+    MissingParentHash(usize),
+    #[error("parent hash of log {0} does not match its predecessor")]
+    HashMismatch(usize),
+    #[error("timestamp of log {0} precedes its predecessor")]
+    TimestampRegression(usize),
+    #[error("trace depth of log {0} is lower than its predecessor")]
+    DepthRegression(usize),
+    #[error("contributor of log {0} differs from the chain root")]
+    ContributorMismatch(usize),
+    #[error("signature of log {0} does not verify")]
+    InvalidSignature(usize),
+}
+
+/// Outcome of verifying a full provenance chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainVerificationResult {
+    pub valid: bool,
+    pub broken_at: Option<usize>,
+    pub error: Option<ChainError>,
+}
+
+/// Reason a provenance signature was rejected
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SignatureError {
+    #[error("provenance log is not signed")]
+    MissingSignature,
+    #[error("provenance signature does not verify")]
+    InvalidSignature,
+    #[error("malformed Ed25519 public key")]
+    InvalidPublicKey,
+}
+
+/// Event metadata rejected by `MetaAgent::log_event_with_metadata`
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MetadataLimitError {
+    #[error("metadata would total {actual} bytes, exceeding the {limit}-byte limit")]
+    ExceedsLimit { limit: usize, actual: usize },
+}
+
+/// Event rejected by the schema set with `MetaAgent::with_schema_validation`
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SchemaValidationError {
+    #[error("invalid JSON schema: {0}")]
+    InvalidSchema(String),
+    #[error("event could not be serialized for validation: {0}")]
+    Serialization(String),
+    #[error("event violates schema: {}", .0.join("; "))]
+    Violation(Vec<String>),
+}
+
+/// Event rejected by `MetaAgent::log_event_with_metadata`
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LogEventError {
+    #[error(transparent)]
+    MetadataLimit(#[from] MetadataLimitError),
+    #[error(transparent)]
+    Schema(#[from] SchemaValidationError),
+}
+
+/// Reason a verifiable credential could not be read back as a provenance log
+#[derive(Debug, thiserror::Error)]
+pub enum VcError {
+    #[error("credential is missing field `{0}`")]
+    MissingField(&'static str),
+    #[error("credential is not a QuantumLimitGraphTrace")]
+    InvalidType,
+    #[error("invalid issuance date: {0}")]
+    InvalidIssuanceDate(#[from] chrono::ParseError),
+}
+
+/// Signs provenance logs with an Ed25519 key
+pub struct ProvenanceSigner {
+    signing_key: ed25519_dalek::SigningKey,
+}
+
+/// Errors raised while rendering a provenance QR code
+#[cfg(feature = "qrcode")]
+#[derive(Debug, thiserror::Error)]
+pub enum QrError {
+    #[error("QR encoding error: {0}")]
+    Encode(#[from] qrcode::types::QrError),
+    #[error("PNG encoding error: {0}")]
+    Image(#[from] image::ImageError),
+}
+
+/// Errors raised while writing or reading Parquet traces
+#[cfg(feature = "parquet")]
+#[derive(Debug, thiserror::Error)]
+pub enum ParquetError {
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("missing or mistyped column: {0}")]
+    InvalidColumn(&'static str),
+    #[error("unknown agent type: {0}")]
+    UnknownAgent(String),
+    #[error("invalid timestamp: {0}")]
+    InvalidTimestamp(i64),
+}
+
+/// Errors raised while streaming traces through Redis
+#[cfg(feature = "redis")]
+#[derive(Debug, thiserror::Error)]
+pub enum RedisError {
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("Invalid timestamp: {0}")]
+    Timestamp(#[from] chrono::ParseError),
+    #[error("stream entry {0} is missing field {1}")]
+    MissingField(String, &'static str),
+    #[error("unknown agent type: {0}")]
+    UnknownAgent(String),
+    #[error("invalid confidence: {0}")]
+    InvalidConfidence(String),
+    #[error("invalid estimated cost: {0}")]
+    InvalidCost(String),
 }
 
 /// Contributor personalization profile
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ContributorProfile {
     pub contributor_id: String,
     pub preferred_languages: Vec<String>,
@@ -89,6 +624,339 @@ pub struct ContributorProfile {
     pub reasoning_style: String,
     pub total_traces: usize,
     pub avg_trace_depth: f64,
+    #[serde(default)]
+    pub language_fluency_scores: HashMap<String, f64>,
+    /// Normalized HHI of agent-type usage (1.0 = one agent type, 0.0 = uniform)
+    #[serde(default)]
+    pub specialization_index: f64,
+}
+
+/// Reasoning styles accepted by `ContributorProfile::validate`
+pub const RECOGNIZED_REASONING_STYLES: &[&str] = &[
+    "analytical",
+    "exploratory",
+    "systematic",
+    "creative",
+    "empirical",
+];
+
+/// Profile validation failures
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ProfileValidationError {
+    #[error("contributor_id is empty")]
+    EmptyContributorId,
+    #[error("avg_trace_depth is negative")]
+    NegativeAvgDepth,
+    #[error("unrecognized reasoning style: {0}")]
+    UnrecognizedReasoningStyle(String),
+    #[error("preferred_languages is empty")]
+    EmptyPreferredLanguages,
+}
+
+/// Profile merge failures
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ProfileMergeError {
+    #[error("cannot merge profiles of different contributors")]
+    ContributorIdMismatch,
+}
+
+impl ContributorProfile {
+    /// Combine two diverged profiles of the same contributor.
+    ///
+    /// Languages are ordered by the number of traces behind them (ties keep first
+    /// appearance); depth, fluency, and specialization are trace-weighted averages, and the
+    /// reasoning style comes from the profile with more traces (`a` on a tie).
+    pub fn merge(a: &ContributorProfile, b: &ContributorProfile) -> Result<ContributorProfile, ProfileMergeError> {
+        if a.contributor_id != b.contributor_id {
+            return Err(ProfileMergeError::ContributorIdMismatch);
+        }
+
+        let mut language_weights: Vec<(String, usize)> = Vec::new();
+        for profile in [a, b] {
+            for language in &profile.preferred_languages {
+                match language_weights.iter_mut().find(|(l, _)| l == language) {
+                    Some((_, weight)) => *weight += profile.total_traces,
+                    None => language_weights.push((language.clone(), profile.total_traces)),
+                }
+            }
+        }
+        language_weights.sort_by_key(|(_, weight)| std::cmp::Reverse(*weight));
+
+        let mut expertise_domains = a.expertise_domains.clone();
+        for domain in &b.expertise_domains {
+            if !expertise_domains.contains(domain) {
+                expertise_domains.push(domain.clone());
+            }
+        }
+
+        let (weight_a, weight_b) = match a.total_traces + b.total_traces {
+            0 => (0.5, 0.5),
+            total => (a.total_traces as f64 / total as f64, b.total_traces as f64 / total as f64),
+        };
+
+        let mut language_fluency_scores = a.language_fluency_scores.clone();
+        for (language, score) in &b.language_fluency_scores {
+            language_fluency_scores.entry(language.clone())
+                .and_modify(|existing| *existing = weight_a * *existing + weight_b * score)
+                .or_insert(*score);
+        }
+
+        let reasoning_style = if b.total_traces > a.total_traces {
+            b.reasoning_style.clone()
+        } else {
+            a.reasoning_style.clone()
+        };
+
+        Ok(ContributorProfile {
+            contributor_id: a.contributor_id.clone(),
+            preferred_languages: language_weights.into_iter().map(|(l, _)| l).collect(),
+            expertise_domains,
+            reasoning_style,
+            total_traces: a.total_traces + b.total_traces,
+            avg_trace_depth: weight_a * a.avg_trace_depth + weight_b * b.avg_trace_depth,
+            language_fluency_scores,
+            specialization_index: weight_a * a.specialization_index + weight_b * b.specialization_index,
+        })
+    }
+
+    /// Collect every validation failure in the profile
+    pub fn validate(&self) -> Result<(), Vec<ProfileValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.contributor_id.is_empty() {
+            errors.push(ProfileValidationError::EmptyContributorId);
+        }
+        if self.avg_trace_depth < 0.0 {
+            errors.push(ProfileValidationError::NegativeAvgDepth);
+        }
+        if !RECOGNIZED_REASONING_STYLES.contains(&self.reasoning_style.as_str()) {
+            errors.push(ProfileValidationError::UnrecognizedReasoningStyle(self.reasoning_style.clone()));
+        }
+        if self.preferred_languages.is_empty() {
+            errors.push(ProfileValidationError::EmptyPreferredLanguages);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Language with the highest fluency score
+    pub fn top_fluency_language(&self) -> Option<&str> {
+        self.language_fluency_scores.iter()
+            .max_by(|a, b| a.1.partial_cmp(b.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| b.0.cmp(a.0)))
+            .map(|(lang, _)| lang.as_str())
+    }
+
+    /// Normalized Herfindahl-Hirschman index over agent-type event fractions:
+    /// `(sum(p_i^2) - 1/N) / (1 - 1/N)` with `N` the number of agent types.
+    ///
+    /// A single-agent trace scores 1.0, a uniform spread over every agent type
+    /// 0.0, and an empty one 0.0.
+    pub fn compute_specialization_index(&self, agent_stats: &[AgentStats]) -> f64 {
+        specialization_index(agent_stats.iter().map(|s| s.event_count))
+    }
+
+    /// Reasoning style suggested by the agent type with the highest mean confidence.
+    ///
+    /// Falls back to `"analytical"` when the confidence profile is empty.
+    pub fn infer_reasoning_style(confidence_profile: &HashMap<String, f64>) -> String {
+        let strongest = confidence_profile.iter()
+            .max_by(|a, b| a.1.partial_cmp(b.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| b.0.cmp(a.0)))
+            .map(|(agent, _)| agent.as_str());
+
+        let style = match strongest {
+            Some("Retrieval") => "empirical",
+            Some("Validation") | Some("Meta") => "systematic",
+            Some("Synthesis") | Some("Translation") => "creative",
+            Some("Action") => "exploratory",
+            _ => "analytical",
+        };
+        style.to_string()
+    }
+
+    /// Approximate profile for a contributor known only through leaderboard stats.
+    ///
+    /// Expertise domains cannot be recovered and are left empty.
+    pub fn from_leaderboard_stats(stats: &ContributorStats) -> ContributorProfile {
+        ContributorProfile {
+            contributor_id: stats.contributor_id.clone(),
+            preferred_languages: stats.languages_used.clone(),
+            expertise_domains: vec![],
+            reasoning_style: Self::infer_reasoning_style(&stats.confidence_profile),
+            total_traces: stats.total_submissions,
+            avg_trace_depth: stats.avg_trace_depth,
+            language_fluency_scores: HashMap::new(),
+            specialization_index: stats.profile.specialization_index,
+        }
+    }
+
+    /// Derive a profile from a contributor's provenance logs.
+    ///
+    /// The three most used languages become `preferred_languages` and the style
+    /// is inferred from per-agent transition confidence; expertise domains and
+    /// fluency scores are not recorded in provenance and start empty.
+    pub fn from_provenance_history(contributor_id: &str, history: &[ProvenanceLog]) -> ContributorProfile {
+        let mut language_counts: Vec<(String, usize)> = Vec::new();
+        for language in history.iter().flat_map(|p| &p.language_sequence) {
+            match language_counts.iter_mut().find(|(l, _)| l == language) {
+                Some((_, count)) => *count += 1,
+                None => language_counts.push((language.clone(), 1)),
+            }
+        }
+        language_counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        let avg_trace_depth = match history.len() {
+            0 => 0.0,
+            n => history.iter().map(|p| p.trace_depth as f64).sum::<f64>() / n as f64,
+        };
+
+        let mut agent_counts: HashMap<&AgentType, usize> = HashMap::new();
+        for agent in history.iter().flat_map(|p| &p.agent_sequence) {
+            *agent_counts.entry(agent).or_insert(0) += 1;
+        }
+
+        ContributorProfile {
+            contributor_id: contributor_id.to_string(),
+            preferred_languages: language_counts.into_iter().take(3).map(|(l, _)| l).collect(),
+            expertise_domains: vec![],
+            reasoning_style: Self::infer_reasoning_style(&Leaderboard::compute_confidence_profile(history)),
+            total_traces: history.len(),
+            avg_trace_depth,
+            language_fluency_scores: HashMap::new(),
+            specialization_index: specialization_index(agent_counts.into_values()),
+        }
+    }
+}
+
+/// Source of semantic vectors for event text
+pub trait EmbeddingProvider {
+    fn embed(&self, text: &str) -> Vec<f32>;
+}
+
+/// Embedding provider and the vector dimension it produces
+pub struct EmbeddingConfig {
+    pub dimension: usize,
+    pub provider: Box<dyn EmbeddingProvider + Send + Sync>,
+}
+
+/// Per-token pricing used to estimate the API cost of logged events
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CostModel {
+    pub input_cost_per_1k_tokens: f64,
+    pub output_cost_per_1k_tokens: f64,
+}
+
+impl CostModel {
+    /// GPT-4 (8K context) list pricing: $0.03 per 1K input and $0.06 per 1K output tokens
+    pub fn openai_gpt4() -> Self {
+        CostModel::custom(0.03, 0.06)
+    }
+
+    /// Pricing in USD per 1K input and output tokens
+    pub fn custom(input_cost: f64, output_cost: f64) -> Self {
+        CostModel {
+            input_cost_per_1k_tokens: input_cost,
+            output_cost_per_1k_tokens: output_cost,
+        }
+    }
+
+    /// Cost of an event, pricing its input and output token estimates separately
+    pub fn estimate_cost(&self, event: &AgentEvent) -> f64 {
+        let input_tokens = estimate_text_tokens(&event.input) as f64;
+        let output_tokens = estimate_text_tokens(&event.output) as f64;
+        (input_tokens * self.input_cost_per_1k_tokens + output_tokens * self.output_cost_per_1k_tokens) / 1000.0
+    }
+}
+
+/// Distribution summary of event confidence values
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceStats {
+    pub mean: f64,
+    /// Population standard deviation
+    pub stddev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub p50: f64,
+    pub p95: f64,
+}
+
+/// Rescales raw confidences against a reference distribution so that values
+/// from different backends are comparable
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceCalibrator {
+    pub mean: f64,
+    /// Population standard deviation
+    pub stddev: f64,
+}
+
+impl ConfidenceCalibrator {
+    /// Reference distribution taken from the confidences of `trace`
+    pub fn from_trace(trace: &[AgentEvent]) -> Self {
+        if trace.is_empty() {
+            return ConfidenceCalibrator { mean: 0.0, stddev: 0.0 };
+        }
+        let n = trace.len() as f64;
+        let mean = trace.iter().map(|e| e.confidence).sum::<f64>() / n;
+        let variance = trace.iter().map(|e| (e.confidence - mean).powi(2)).sum::<f64>() / n;
+        ConfidenceCalibrator { mean, stddev: variance.sqrt() }
+    }
+
+    /// `sigmoid((raw - mean) / stddev)`, in (0, 1); a zero-spread reference maps
+    /// everything to 0.5
+    pub fn calibrate(&self, raw: f64) -> f64 {
+        let z = if self.stddev > 0.0 { (raw - self.mean) / self.stddev } else { 0.0 };
+        1.0 / (1.0 + (-z).exp())
+    }
+}
+
+/// Agent cycle repeated back-to-back along the transition path
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReasoningLoop {
+    pub cycle: Vec<AgentType>,
+    pub repetitions: usize,
+    /// First transition inside the loop
+    pub start_index: usize,
+    /// Last transition inside the loop (inclusive)
+    pub end_index: usize,
+}
+
+/// Global alignment of a trace's agent sequence against a reference sequence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlignmentResult {
+    /// Needleman-Wunsch score divided by the longer sequence length (1.0 = identical)
+    pub alignment_score: f64,
+    /// Levenshtein distance between the two sequences
+    pub edit_distance: usize,
+    /// Trace agents with `None` marking gaps
+    pub aligned_actual: Vec<Option<AgentType>>,
+    /// Reference agents with `None` marking gaps
+    pub aligned_reference: Vec<Option<AgentType>>,
+}
+
+/// Main task category of a session, inferred from its agent mix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Task {
+    Translation,
+    Classification,
+    Research,
+    Validation,
+    MultiStep,
+    Unknown,
+}
+
+/// Lifecycle state of a MetaAgent session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionState {
+    Active,
+    Completed,
 }
 
 /// Level 5 MetaAgent with advanced capabilities
@@ -100,8 +968,32 @@ pub struct MetaAgent {
     pub profile: ContributorProfile,
     pub session_id: String,
     pub current_agent: Option<AgentType>,
+    pub session_metadata: HashMap<String, String>,
+    pub state: SessionState,
+    /// Bytes of event metadata logged this session, measured as compact JSON and
+    /// including automatic entries; see `log_event_with_metadata`
+    pub metadata_bytes_total: usize,
+    session_counter: u32,
+    max_metadata_bytes: Option<usize>,
+    auto_transitions: bool,
+    event_sender: Option<broadcast::Sender<AgentEvent>>,
+    pre_log_hook: Option<PreLogHook>,
+    post_log_hook: Option<PostLogHook>,
+    embedding_config: Option<EmbeddingConfig>,
+    cost_model: Option<CostModel>,
+    #[cfg(feature = "jsonschema")]
+    schema_validator: Option<Result<std::sync::Arc<jsonschema::Validator>, SchemaValidationError>>,
 }
 
+/// Buffered events per subscriber before slow receivers start lagging
+const EVENT_STREAM_CAPACITY: usize = 1024;
+
+/// Called with (agent, input, output, language, confidence) before logging; `false` cancels
+type PreLogHook = Box<dyn Fn(&AgentType, &str, &str, &str, f64) -> bool>;
+
+/// Called with each event after it has been logged
+type PostLogHook = Box<dyn Fn(&AgentEvent)>;
+
 impl MetaAgent {
     /// Create new MetaAgent with contributor profile
     pub fn new(contributor_id: &str, backend_used: &str) -> Self {
@@ -112,6 +1004,8 @@ impl MetaAgent {
             reasoning_style: "analytical".to_string(),
             total_traces: 0,
             avg_trace_depth: 0.0,
+            language_fluency_scores: HashMap::new(),
+            specialization_index: 0.0,
         };
 
         MetaAgent {
@@ -122,11 +1016,45 @@ impl MetaAgent {
             profile,
             session_id: format!("session_{}", Utc::now().timestamp()),
             current_agent: None,
+            session_metadata: HashMap::new(),
+            state: SessionState::Active,
+            metadata_bytes_total: 0,
+            session_counter: 0,
+            max_metadata_bytes: None,
+            auto_transitions: true,
+            event_sender: None,
+            pre_log_hook: None,
+            post_log_hook: None,
+            embedding_config: None,
+            cost_model: None,
+            #[cfg(feature = "jsonschema")]
+            schema_validator: None,
         }
     }
 
-    /// Create MetaAgent with custom profile
-    pub fn with_profile(contributor_id: &str, backend_used: &str, profile: ContributorProfile) -> Self {
+    /// Create MetaAgent with custom profile.
+    ///
+    /// The profile is validated first. Debug builds panic on an invalid profile;
+    /// release builds clamp invalid values instead: an empty `contributor_id` takes
+    /// the given `contributor_id`, a negative `avg_trace_depth` becomes `0.0`, an
+    /// unrecognized `reasoning_style` becomes `"analytical"`, and empty
+    /// `preferred_languages` becomes `["en"]`.
+    pub fn with_profile(contributor_id: &str, backend_used: &str, mut profile: ContributorProfile) -> Self {
+        if let Err(errors) = profile.validate() {
+            if cfg!(debug_assertions) {
+                panic!("invalid contributor profile: {:?}", errors);
+            }
+
+            for error in errors {
+                match error {
+                    ProfileValidationError::EmptyContributorId => profile.contributor_id = contributor_id.to_string(),
+                    ProfileValidationError::NegativeAvgDepth => profile.avg_trace_depth = 0.0,
+                    ProfileValidationError::UnrecognizedReasoningStyle(_) => profile.reasoning_style = "analytical".to_string(),
+                    ProfileValidationError::EmptyPreferredLanguages => profile.preferred_languages = vec!["en".to_string()],
+                }
+            }
+        }
+
         MetaAgent {
             trace: Vec::new(),
             transitions: Vec::new(),
@@ -135,49 +1063,343 @@ impl MetaAgent {
             profile,
             session_id: format!("session_{}", Utc::now().timestamp()),
             current_agent: None,
+            session_metadata: HashMap::new(),
+            state: SessionState::Active,
+            metadata_bytes_total: 0,
+            session_counter: 0,
+            max_metadata_bytes: None,
+            auto_transitions: true,
+            event_sender: None,
+            pre_log_hook: None,
+            post_log_hook: None,
+            embedding_config: None,
+            cost_model: None,
+            #[cfg(feature = "jsonschema")]
+            schema_validator: None,
         }
     }
 
-    /// Log agent event with full context
-    pub fn log_event(&mut self, agent: AgentType, input: &str, output: &str, language: &str, confidence: f64) {
-        // Track agent transition
-        if let Some(ref prev_agent) = self.current_agent {
-            if prev_agent != &agent {
-                self.track_transition(prev_agent.clone(), agent.clone(), "natural_flow");
-            }
+    /// Clone the session into an independent branch with a new session id.
+    ///
+    /// The fork does not inherit the event stream, log hooks, or embedding config.
+    pub fn fork(&self) -> MetaAgent {
+        MetaAgent {
+            trace: self.trace.clone(),
+            transitions: self.transitions.clone(),
+            contributor_id: self.contributor_id.clone(),
+            backend_used: self.backend_used.clone(),
+            profile: self.profile.clone(),
+            session_id: format!("fork_{}_{}", self.session_id, Utc::now().timestamp()),
+            current_agent: self.current_agent.clone(),
+            session_metadata: self.session_metadata.clone(),
+            state: self.state,
+            metadata_bytes_total: self.metadata_bytes_total,
+            session_counter: 0,
+            max_metadata_bytes: self.max_metadata_bytes,
+            auto_transitions: self.auto_transitions,
+            event_sender: None,
+            pre_log_hook: None,
+            post_log_hook: None,
+            embedding_config: None,
+            cost_model: self.cost_model,
+            #[cfg(feature = "jsonschema")]
+            schema_validator: self.schema_validator.clone(),
         }
-
-        let event = AgentEvent {
-            timestamp: Utc::now(),
-            agent: agent.clone(),
-            input: input.to_string(),
-            output: output.to_string(),
-            language: language.to_string(),
-            confidence,
-            metadata: HashMap::new(),
-        };
-        
-        self.trace.push(event);
-        self.current_agent = Some(agent);
     }
 
-    /// Log event with metadata
-    pub fn log_event_with_metadata(
-        &mut self,
-        agent: AgentType,
-        input: &str,
-        output: &str,
-        language: &str,
-        confidence: f64,
-        metadata: HashMap<String, String>,
-    ) {
-        if let Some(ref prev_agent) = self.current_agent {
-            if prev_agent != &agent {
-                self.track_transition(prev_agent.clone(), agent.clone(), "natural_flow");
+    /// Replay the trace through `modifier`, which receives each event's index and
+    /// a copy of it and returns the event to record in its place.
+    ///
+    /// The replay is a fork with session id `replay_{session_id}`. Its transitions
+    /// are re-derived from agent changes in the modified trace (none when automatic
+    /// transitions are disabled), so manually tracked transitions are dropped.
+    /// Events whose input or output the modifier changed are repriced with the
+    /// cost model (0.0 without one); the rest keep their original cost.
+    pub fn replay_with_modification<F>(&self, modifier: F) -> MetaAgent
+    where
+        F: Fn(usize, AgentEvent) -> AgentEvent,
+    {
+        let original = self.expanded_trace();
+        let mut replay = self.fork();
+        replay.session_id = format!("replay_{}", self.session_id);
+        replay.trace = original.iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, event)| modifier(i, event))
+            .collect();
+        for (event, before) in replay.trace.iter_mut().zip(&original) {
+            if event.input != before.input || event.output != before.output {
+                event.estimated_cost_usd = self.cost_model.map_or(0.0, |model| model.estimate_cost(event));
             }
         }
+        replay.metadata_bytes_total = replay.trace.iter()
+            .map(|e| serialized_metadata_bytes(&e.metadata))
+            .sum();
+        replay.transitions.clear();
+        replay.rebuild_transitions();
+        replay
+    }
+
+    /// Mark the current session as completed
+    pub fn complete(&mut self) {
+        self.state = SessionState::Completed;
+    }
+
+    /// Start a fresh session, keeping the contributor, backend, and profile.
+    ///
+    /// Clears the trace, transitions, current agent, and session metadata, and
+    /// counts the finished session in `profile.total_traces`.
+    pub fn reset_session(&mut self) {
+        self.trace.clear();
+        self.transitions.clear();
+        self.current_agent = None;
+        self.session_metadata.clear();
+        self.metadata_bytes_total = 0;
+        self.state = SessionState::Active;
+        self.session_counter += 1;
+        self.session_id = format!("session_{}_{}", Utc::now().timestamp(), self.session_counter);
+        self.profile.total_traces += 1;
+    }
+
+    /// Subscribe to newly logged events; creates the broadcast channel on first use
+    pub fn enable_event_stream(&mut self) -> broadcast::Receiver<AgentEvent> {
+        match &self.event_sender {
+            Some(sender) => sender.subscribe(),
+            None => {
+                let (sender, receiver) = broadcast::channel(EVENT_STREAM_CAPACITY);
+                self.event_sender = Some(sender);
+                receiver
+            }
+        }
+    }
+
+    /// Stop streaming events; open receivers observe the channel closing
+    pub fn disable_event_stream(&mut self) {
+        self.event_sender = None;
+    }
+
+    /// Attach a session-level key-value pair, replacing any previous value
+    pub fn set_session_metadata(&mut self, key: &str, value: &str) {
+        self.session_metadata.insert(key.to_string(), value.to_string());
+    }
+
+    /// Session-level metadata value for `key`
+    pub fn get_session_metadata(&self, key: &str) -> Option<&str> {
+        self.session_metadata.get(key).map(String::as_str)
+    }
+
+    /// Run `hook` before every log; returning `false` drops the event
+    pub fn set_pre_log_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&AgentType, &str, &str, &str, f64) -> bool + 'static,
+    {
+        self.pre_log_hook = Some(Box::new(hook));
+    }
+
+    /// Run `hook` after every successfully logged event
+    pub fn set_post_log_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&AgentEvent) + 'static,
+    {
+        self.post_log_hook = Some(Box::new(hook));
+    }
+
+    /// Log agent event with full context.
+    ///
+    /// Returns the violation, and logs nothing, when the schema set with
+    /// `with_schema_validation` rejects the event.
+    pub fn log_event(
+        &mut self,
+        agent: AgentType,
+        input: &str,
+        output: &str,
+        language: &str,
+        confidence: f64,
+    ) -> Result<(), SchemaValidationError> {
+        self.append_event(agent, input, output, language, confidence, HashMap::new())
+    }
+
+    /// Log event only if `gate(confidence, &agent)` passes; returns whether it was logged.
+    ///
+    /// An event the gate lets through can still be dropped by a pre-log hook or
+    /// rejected by a schema.
+    pub fn log_event_conditional<F>(
+        &mut self,
+        agent: AgentType,
+        input: &str,
+        output: &str,
+        language: &str,
+        confidence: f64,
+        gate: F,
+    ) -> bool
+    where
+        F: Fn(f64, &AgentType) -> bool,
+    {
+        if !gate(confidence, &agent) {
+            return false;
+        }
+        let depth = self.trace.len();
+        self.log_event(agent, input, output, language, confidence).is_ok() && self.trace.len() > depth
+    }
+
+    /// Enable or disable recording a transition whenever the logged agent type changes.
+    ///
+    /// With auto transitions off, only explicit `track_transition` calls are recorded.
+    pub fn with_auto_transitions(mut self, enabled: bool) -> Self {
+        self.auto_transitions = enabled;
+        self
+    }
+
+    /// Cap the session's total metadata bytes, measured as in `log_event_with_metadata`;
+    /// that method rejects events whose metadata would exceed it
+    pub fn with_max_metadata_bytes(mut self, limit: usize) -> Self {
+        self.max_metadata_bytes = Some(limit);
+        self
+    }
+
+    /// Validate each event, serialized as JSON, against `schema` before logging it.
+    ///
+    /// Rejected events are not recorded and trigger no transition or hooks. If
+    /// `schema` does not compile, every event is rejected with
+    /// `SchemaValidationError::InvalidSchema`.
+    #[cfg(feature = "jsonschema")]
+    pub fn with_schema_validation(mut self, schema: serde_json::Value) -> Self {
+        let validator = jsonschema::validator_for(&schema)
+            .map(std::sync::Arc::new)
+            .map_err(|e| SchemaValidationError::InvalidSchema(e.to_string()));
+        self.schema_validator = Some(validator);
+        self
+    }
+
+    /// Check an event against the configured schema (always passes without one)
+    fn validate_event(&self, event: &AgentEvent) -> Result<(), SchemaValidationError> {
+        #[cfg(feature = "jsonschema")]
+        if let Some(validator) = &self.schema_validator {
+            let validator = validator.as_ref().map_err(Clone::clone)?;
+            let instance = serde_json::to_value(event)
+                .map_err(|e| SchemaValidationError::Serialization(e.to_string()))?;
+            let violations: Vec<String> = validator.iter_errors(&instance).map(|e| e.to_string()).collect();
+            if !violations.is_empty() {
+                return Err(SchemaValidationError::Violation(violations));
+            }
+        }
+        #[cfg(not(feature = "jsonschema"))]
+        let _ = event;
+        Ok(())
+    }
+
+    /// Price each subsequently logged event with `model`
+    pub fn with_cost_model(mut self, model: CostModel) -> Self {
+        self.cost_model = Some(model);
+        self
+    }
+
+    /// Sum of `estimated_cost_usd` across all events
+    pub fn total_estimated_cost_usd(&self) -> f64 {
+        self.trace.iter().map(|e| e.estimated_cost_usd).sum()
+    }
+
+    /// Use `config` to embed events for semantic comparison
+    pub fn with_embedding_config(mut self, config: EmbeddingConfig) -> Self {
+        self.embedding_config = Some(config);
+        self
+    }
+
+    /// Embedding of an event's input and output text.
+    ///
+    /// Returns `None` without an embedding config, for an out-of-range index, or
+    /// if the provider's vector does not match the configured dimension.
+    pub fn get_event_embedding(&self, index: usize) -> Option<Vec<f32>> {
+        let config = self.embedding_config.as_ref()?;
+        let event = self.trace.get(index)?;
+        let embedding = config.provider.embed(&format!("{} {}", event.input, event.output));
+        (embedding.len() == config.dimension).then_some(embedding)
+    }
+
+    /// Cosine similarity between the embeddings of two events (0.0 for zero vectors)
+    pub fn compute_embedding_similarity(&self, i: usize, j: usize) -> Option<f64> {
+        let a = self.get_event_embedding(i)?;
+        let b = self.get_event_embedding(j)?;
+
+        let dot: f64 = a.iter().zip(&b).map(|(x, y)| *x as f64 * *y as f64).sum();
+        let norm_a = a.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+        let norm_b = b.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+        if norm_a == 0.0 || norm_b == 0.0 {
+            return Some(0.0);
+        }
+        Some(dot / (norm_a * norm_b))
+    }
+
+    /// Wrap this agent so every logged event must meet a minimum confidence
+    pub fn with_confidence_gate(self, min_confidence: f64) -> ConditionalMetaAgent {
+        ConditionalMetaAgent {
+            inner: self,
+            min_confidence,
+        }
+    }
+
+    /// Log event with metadata.
+    ///
+    /// Metadata size is the length in bytes of the event's metadata serialized as
+    /// compact JSON, or 0 when it has none, and is added to `metadata_bytes_total`.
+    /// The automatic `source_language` entry of a translation is included. With a
+    /// limit set by `with_max_metadata_bytes`, an event whose own metadata would push
+    /// the session total past it is rejected and nothing is logged; an automatic
+    /// entry that would not fit is left out instead. An event the schema set with
+    /// `with_schema_validation` rejects is not logged either.
+    pub fn log_event_with_metadata(
+        &mut self,
+        agent: AgentType,
+        input: &str,
+        output: &str,
+        language: &str,
+        confidence: f64,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), LogEventError> {
+        let metadata_bytes = serialized_metadata_bytes(&metadata);
+        // An event without metadata adds no bytes, so it never pushes the total past the limit
+        if let Some(limit) = self.max_metadata_bytes.filter(|_| metadata_bytes > 0) {
+            let actual = self.metadata_bytes_total + metadata_bytes;
+            if actual > limit {
+                return Err(MetadataLimitError::ExceedsLimit { limit, actual }.into());
+            }
+        }
+        self.append_event(agent, input, output, language, confidence, metadata)?;
+        Ok(())
+    }
+
+    /// Build, validate, and append an event whose caller metadata is within the
+    /// limit; shared by the public logging methods
+    fn append_event(
+        &mut self,
+        agent: AgentType,
+        input: &str,
+        output: &str,
+        language: &str,
+        confidence: f64,
+        mut metadata: HashMap<String, String>,
+    ) -> Result<(), SchemaValidationError> {
+
+        if let Some(hook) = &self.pre_log_hook {
+            if !hook(&agent, input, output, language, confidence) {
+                return Ok(());
+            }
+        }
+
+        // A translation's language is its target; record the source from the previous event
+        if agent == AgentType::Translation && !metadata.contains_key(SOURCE_LANGUAGE_KEY) {
+            if let Some(previous) = self.trace.last() {
+                metadata.insert(SOURCE_LANGUAGE_KEY.to_string(), previous.language.clone());
+                let fits = self.max_metadata_bytes
+                    .is_none_or(|limit| self.metadata_bytes_total + serialized_metadata_bytes(&metadata) <= limit);
+                if !fits {
+                    metadata.remove(SOURCE_LANGUAGE_KEY);
+                }
+            }
+        }
+        let metadata_bytes = serialized_metadata_bytes(&metadata);
 
-        let event = AgentEvent {
+        let mut event = AgentEvent {
             timestamp: Utc::now(),
             agent: agent.clone(),
             input: input.to_string(),
@@ -185,10 +1407,144 @@ impl MetaAgent {
             language: language.to_string(),
             confidence,
             metadata,
+            tags: Vec::new(),
+            estimated_cost_usd: 0.0,
+            interned_metadata: InternedMetadata::default(),
         };
+        event.estimated_cost_usd = self.cost_model.map_or(0.0, |model| model.estimate_cost(&event));
+        self.validate_event(&event)?;
+
+        if let Some(ref prev_agent) = self.current_agent {
+            if self.auto_transitions && prev_agent != &agent {
+                self.track_transition(prev_agent.clone(), agent.clone(), "natural_flow");
+            }
+        }
         
+        if let Some(sender) = &self.event_sender {
+            // No active receivers is not an error for the logger
+            let _ = sender.send(event.clone());
+        }
+
         self.trace.push(event);
         self.current_agent = Some(agent);
+        self.metadata_bytes_total += metadata_bytes;
+
+        if let (Some(hook), Some(event)) = (&self.post_log_hook, self.trace.last()) {
+            hook(event);
+        }
+        Ok(())
+    }
+
+    /// Replace every match of `pattern` in event inputs, outputs, and metadata values.
+    ///
+    /// Returns the number of substitutions made. Provenance emitted afterwards
+    /// hashes the redacted content.
+    pub fn redact_by_pattern(&mut self, pattern: &regex::Regex, replacement: &str) -> usize {
+        let mut substitutions = 0;
+        let mut redact = |text: &mut String| {
+            let matches = pattern.find_iter(text).count();
+            if matches > 0 {
+                *text = pattern.replace_all(text, regex::NoExpand(replacement)).into_owned();
+                substitutions += matches;
+            }
+        };
+
+        for event in &mut self.trace {
+            redact(&mut event.input);
+            redact(&mut event.output);
+            for value in event.metadata.values_mut() {
+                redact(value);
+            }
+        }
+
+        // Interned values are shared, so count a match once per referencing event
+        // and redact each string table once
+        let mut redacted_tables: HashMap<*const Vec<String>, std::sync::Arc<Vec<String>>> = HashMap::new();
+        for event in &mut self.trace {
+            let interned = &mut event.interned_metadata;
+            if interned.is_empty() {
+                continue;
+            }
+            substitutions += interned.iter()
+                .map(|(_, value)| pattern.find_iter(value).count())
+                .sum::<usize>();
+            let table = redacted_tables.entry(std::sync::Arc::as_ptr(&interned.strings))
+                .or_insert_with(|| std::sync::Arc::new(interned.strings.iter()
+                    .map(|value| pattern.replace_all(value, regex::NoExpand(replacement)).into_owned())
+                    .collect()));
+            interned.strings = table.clone();
+        }
+        substitutions
+    }
+
+    /// Intern event metadata values into a string table shared by the whole trace.
+    ///
+    /// Each distinct value is stored once and every event keeps only key-to-index
+    /// maps in `interned_metadata`, leaving its `metadata` empty. The interned data
+    /// travels with the event, and `AgentEvent::metadata_value` and
+    /// `AgentEvent::full_metadata` read it back wherever the event ends up; every
+    /// MetaAgent query, export, and fold reads metadata through them. Events logged
+    /// afterwards stay uncompressed until the next call.
+    pub fn compress_metadata(&mut self) {
+        let mut strings: Vec<String> = Vec::new();
+        let mut ids: HashMap<String, u32> = HashMap::new();
+        let mut per_event: Vec<HashMap<String, u32>> = Vec::with_capacity(self.trace.len());
+        for event in &mut self.trace {
+            let mut metadata = event.full_metadata();
+            event.metadata = HashMap::new();
+            per_event.push(metadata.drain()
+                .map(|(key, value)| {
+                    let id = *ids.entry(value).or_insert_with_key(|value| {
+                        strings.push(value.clone());
+                        (strings.len() - 1) as u32
+                    });
+                    (key, id)
+                })
+                .collect());
+        }
+
+        let strings = std::sync::Arc::new(strings);
+        for (event, ids) in self.trace.iter_mut().zip(per_event) {
+            event.interned_metadata = InternedMetadata { strings: strings.clone(), ids };
+        }
+    }
+
+    /// Full string metadata of the event at `index`, whether compressed or not
+    pub fn event_metadata(&self, index: usize) -> Option<HashMap<String, String>> {
+        self.trace.get(index).map(AgentEvent::full_metadata)
+    }
+
+    /// Approximate bytes of metadata text held by the trace and its string tables
+    pub fn metadata_memory_estimate(&self) -> usize {
+        let strings: usize = self.trace.iter()
+            .flat_map(|e| &e.metadata)
+            .map(|(k, v)| k.len() + v.len())
+            .sum();
+        let interned: usize = self.trace.iter()
+            .flat_map(|e| e.interned_metadata.ids.keys())
+            .map(|k| k.len() + std::mem::size_of::<u32>())
+            .sum();
+        let mut tables: HashMap<*const Vec<String>, usize> = HashMap::new();
+        for event in self.trace.iter().filter(|e| !e.interned_metadata.is_empty()) {
+            let table = &event.interned_metadata.strings;
+            tables.entry(std::sync::Arc::as_ptr(table))
+                .or_insert_with(|| table.iter().map(String::len).sum());
+        }
+        strings + interned + tables.values().sum::<usize>()
+    }
+
+    /// Trace with interned metadata restored to string form
+    fn expanded_trace(&self) -> Vec<AgentEvent> {
+        self.trace.iter()
+            .map(|e| {
+                let mut event = e.clone();
+                if !event.interned_metadata.is_empty() {
+                    event.metadata = event.full_metadata();
+                    event.interned_metadata = InternedMetadata::default();
+                }
+                event
+            })
+            .collect()
     }
 
     /// Track agent transition
@@ -203,6 +1559,57 @@ impl MetaAgent {
         self.transitions.push(transition);
     }
 
+    /// Report every event whose timestamp is earlier than its predecessor
+    pub fn validate_trace_ordering(&self) -> Vec<OrderingViolation> {
+        self.trace.windows(2)
+            .enumerate()
+            .filter(|(_, pair)| pair[1].timestamp < pair[0].timestamp)
+            .map(|(i, pair)| OrderingViolation {
+                index: i + 1,
+                event_timestamp: pair[1].timestamp,
+                previous_timestamp: pair[0].timestamp,
+            })
+            .collect()
+    }
+
+    /// Sort the trace by timestamp and rebuild transitions from the new order.
+    ///
+    /// Warning: this rewrites the trace, so any `ProvenanceLog` emitted before the
+    /// call no longer matches this session. With auto transitions on (the default),
+    /// all transitions, including manually tracked ones, are replaced by those
+    /// implied by the sorted trace. With auto transitions off, the existing
+    /// transitions are kept unchanged.
+    pub fn fix_trace_ordering(&mut self) {
+        self.trace.sort_by_key(|e| e.timestamp);
+        self.rebuild_transitions();
+    }
+
+    /// Recompute transitions from consecutive agent changes in the trace.
+    ///
+    /// Manually tracked transitions are kept as-is when auto transitions are off.
+    fn rebuild_transitions(&mut self) {
+        self.current_agent = self.trace.last().map(|e| e.agent.clone());
+        if !self.auto_transitions {
+            return;
+        }
+
+        self.transitions.clear();
+        for i in 1..self.trace.len() {
+            let (prev, next) = (&self.trace[i - 1], &self.trace[i]);
+            if prev.agent != next.agent {
+                let transition_score = preceding_confidence(&self.trace, i);
+
+                self.transitions.push(AgentTransition {
+                    from_agent: prev.agent.clone(),
+                    to_agent: next.agent.clone(),
+                    timestamp: next.timestamp,
+                    reason: "natural_flow".to_string(),
+                    transition_score,
+                });
+            }
+        }
+    }
+
     /// Compute transition quality score
     fn compute_transition_score(&self) -> f64 {
         if self.trace.is_empty() {
@@ -221,77 +1628,500 @@ impl MetaAgent {
 
     /// Fold memory with hierarchical compression
     pub fn fold_memory(&self) -> MemoryFold {
-        let total_chars: usize = self.trace.iter()
-            .map(|e| e.input.len() + e.output.len())
-            .sum();
-        
+        self.fold_memory_with_score(self.score_trace().overall)
+    }
+
+    /// `fold_memory` for a caller that has already scored the trace
+    fn fold_memory_with_score(&self, session_score: f64) -> MemoryFold {
+        let aggregates = FoldAggregates::from_events(&self.trace);
         let summary = self.generate_summary();
-        let compression_ratio = if total_chars > 0 {
-            summary.len() as f64 / total_chars as f64
+        let compression_ratio = if aggregates.total_chars > 0 {
+            summary.len() as f64 / aggregates.total_chars as f64
         } else {
             1.0
         };
 
         let key_insights = self.extract_key_insights();
         let language_distribution = self.compute_language_distribution();
+        let rle_agent_sequence = self.build_rle_agent_sequence();
+        let language_entropy = self.compute_language_entropy();
+        let transition_entropy = self.compute_transition_entropy();
+        let total_estimated_tokens = self.total_estimated_tokens();
+        let max_consecutive_same_agent = rle_agent_sequence.iter().map(|(_, count)| *count).max().unwrap_or(0);
 
-        MemoryFold {
+        let mut fold = MemoryFold {
             session_id: self.session_id.clone(),
-            folded_trace: self.trace.clone(),
+            folded_trace: self.expanded_trace(),
             summary,
             compression_ratio,
             key_insights,
             language_distribution,
+            language_sequence: distinct_language_sequence(&self.trace),
+            language_transition_count: language_change_count(&self.trace),
+            rle_agent_sequence,
+            language_entropy,
+            session_score,
+            transition_entropy,
+            total_estimated_tokens,
+            max_consecutive_same_agent,
+            top_output_words: Vec::new(),
+            session_metadata: self.session_metadata.clone(),
+            translation_pairs: self.get_translation_pairs(),
+            language_agent_affinity: affinity_by_name(language_agent_affinity(&self.trace)),
+            domain_coverage_ratio: self.get_domain_coverage().coverage_ratio,
+            output_diversity: self.compute_output_diversity(),
+            semantic_coherence_proxy: self.compute_semantic_coherence_proxy(),
+            total_cost_usd: self.total_estimated_cost_usd(),
+            inferred_task: self.infer_primary_task(),
+            detected_loops: self.detect_reasoning_loops(DEFAULT_LOOP_CYCLE_LENGTH, DEFAULT_LOOP_REPETITIONS).len(),
+            back_reference_count: self.count_back_references(),
+            transition_narrative: String::new(),
+            aggregates,
+        };
+        fold.top_output_words = fold.top_output_word_list();
+        fold.transition_narrative = fold.summarize_transitions();
+        fold
+    }
+
+    /// Cosine similarity of two traces as bags of (agent type, language) pairs
+    pub fn trace_similarity(a: &MetaAgent, b: &MetaAgent) -> f64 {
+        pair_cosine_similarity(
+            a.trace.iter().map(|e| (&e.agent, e.language.as_str())),
+            b.trace.iter().map(|e| (&e.agent, e.language.as_str())),
+        )
+    }
+
+    /// Needleman-Wunsch alignment of the trace's agent types against `reference`
+    /// (match +1, mismatch -1, gap -0.5).
+    ///
+    /// Tied tracebacks prefer a match or mismatch, then a gap in the reference.
+    /// Two empty sequences score 1.0.
+    pub fn align_against_reference(&self, reference: &[AgentType]) -> AlignmentResult {
+        let actual: Vec<AgentType> = self.trace.iter().map(|e| e.agent.clone()).collect();
+        let (score, aligned_actual, aligned_reference) = needleman_wunsch(&actual, reference);
+
+        AlignmentResult {
+            alignment_score: match actual.len().max(reference.len()) {
+                0 => 1.0,
+                n => score / n as f64,
+            },
+            edit_distance: levenshtein(&actual, reference),
+            aligned_actual,
+            aligned_reference,
+        }
+    }
+
+    /// Approximate LLM token count across all events
+    pub fn total_estimated_tokens(&self) -> usize {
+        self.trace.iter().map(|e| e.estimate_tokens()).sum()
+    }
+
+    /// Estimate how much of a context window of the given size the trace occupies
+    pub fn compute_context_window_usage(&self, context_window_tokens: usize) -> ContextWindowUsage {
+        let estimated_tokens = self.total_estimated_tokens();
+        let fraction_used = if context_window_tokens > 0 {
+            estimated_tokens as f64 / context_window_tokens as f64
+        } else {
+            f64::INFINITY
+        };
+
+        ContextWindowUsage {
+            estimated_tokens,
+            fraction_used,
+            events_that_fit: self.count_recent_events_within(context_window_tokens),
+            overflow: estimated_tokens > context_window_tokens,
+        }
+    }
+
+    /// Drop the oldest events until the trace fits within the context window.
+    ///
+    /// Transitions are rebuilt from the remaining trace, as in `fix_trace_ordering`.
+    pub fn trim_to_context_window(&mut self, context_window_tokens: usize) {
+        let keep = self.count_recent_events_within(context_window_tokens);
+        let removed = self.trace.len() - keep;
+        if removed == 0 {
+            return;
+        }
+        self.trace.drain(..removed);
+        self.rebuild_transitions();
+    }
+
+    /// Keep the best-scoring events that fit the context window, dropping the rest.
+    ///
+    /// Each event scores `recency * (1 - quality_weight) + confidence * quality_weight`,
+    /// where recency runs from 0.0 (oldest) to 1.0 (newest) and `quality_weight` is
+    /// clamped to [0.0, 1.0]. Events are taken greedily by score, ties going to the
+    /// more recent event, skipping any that would exceed the remaining budget.
+    /// Kept events stay in trace order and transitions are rebuilt.
+    pub fn truncate_to_context_window_smart(&mut self, context_window_tokens: usize, quality_weight: f64) {
+        if self.total_estimated_tokens() <= context_window_tokens {
+            return;
+        }
+
+        let quality_weight = quality_weight.clamp(0.0, 1.0);
+        let last = self.trace.len().saturating_sub(1).max(1) as f64;
+        let scores: Vec<f64> = self.trace.iter()
+            .enumerate()
+            .map(|(i, e)| (i as f64 / last) * (1.0 - quality_weight) + e.confidence * quality_weight)
+            .collect();
+
+        let mut order: Vec<usize> = (0..self.trace.len()).collect();
+        order.sort_by(|&a, &b| {
+            scores[b].partial_cmp(&scores[a])
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| b.cmp(&a))
+        });
+
+        let mut keep = vec![false; self.trace.len()];
+        let mut used = 0;
+        for i in order {
+            let tokens = self.trace[i].estimate_tokens();
+            if used + tokens <= context_window_tokens {
+                used += tokens;
+                keep[i] = true;
+            }
         }
+
+        let mut keep = keep.into_iter();
+        self.trace.retain(|_| keep.next().unwrap_or(false));
+        self.rebuild_transitions();
+    }
+
+    /// Number of most recent events whose combined token estimate fits the budget
+    fn count_recent_events_within(&self, token_budget: usize) -> usize {
+        let mut used = 0;
+        self.trace.iter()
+            .rev()
+            .take_while(|e| {
+                used += e.estimate_tokens();
+                used <= token_budget
+            })
+            .count()
     }
 
     /// Generate intelligent summary
     fn generate_summary(&self) -> String {
-        let agent_counts = self.count_agent_types();
         let languages: Vec<String> = self.trace.iter()
             .map(|e| e.language.clone())
             .collect::<std::collections::HashSet<_>>()
             .into_iter()
             .collect();
 
-        format!(
-            "Session {} with {} reasoning steps across {} languages. Agent distribution: {:?}. Transitions: {}",
-            self.session_id,
-            self.trace.len(),
-            languages.len(),
-            agent_counts,
-            self.transitions.len()
-        )
+        format_summary(&self.build_rle_agent_sequence(), languages.len(), self.transitions.len())
     }
 
-    /// Extract key insights from trace
-    fn extract_key_insights(&self) -> Vec<String> {
-        let mut insights = Vec::new();
+    /// Run-length encode the agent sequence (e.g. Reasoning×20 → Synthesis×3)
+    pub fn build_rle_agent_sequence(&self) -> Vec<(AgentType, usize)> {
+        let mut runs: Vec<(AgentType, usize)> = Vec::new();
+        extend_rle(&mut runs, &self.trace);
+        runs
+    }
+
+    /// Longest consecutive run of one agent type as `(agent, start_index, length)`.
+    ///
+    /// Ties go to the earliest run.
+    pub fn get_longest_same_agent_run(&self) -> Option<(AgentType, usize, usize)> {
+        self.agent_runs()
+            .into_iter()
+            .reduce(|best, run| if run.2 > best.2 { run } else { best })
+    }
 
-        // High confidence outputs
-        let high_conf_events: Vec<_> = self.trace.iter()
-            .filter(|e| e.confidence > 0.8)
+    /// All consecutive runs of at least two events of the same agent type
+    pub fn get_all_same_agent_runs(&self) -> Vec<(AgentType, usize, usize)> {
+        self.agent_runs()
+            .into_iter()
+            .filter(|(_, _, length)| *length >= 2)
+            .collect()
+    }
+
+    /// Report which of the profile's expertise domains appear in the trace.
+    ///
+    /// A domain is engaged when an event's `domain` metadata value or one of its
+    /// tags matches it, ignoring case.
+    pub fn get_domain_coverage(&self) -> DomainCoverageReport {
+        let declared_domains = self.profile.expertise_domains.clone();
+        let engaged_domains: Vec<String> = declared_domains.iter()
+            .filter(|domain| {
+                let domain = domain.to_lowercase();
+                self.trace.iter().any(|e| {
+                    e.metadata_value(DOMAIN_METADATA_KEY).is_some_and(|d| d.to_lowercase() == domain)
+                        || e.tags.iter().any(|t| t.to_lowercase() == domain)
+                })
+            })
+            .cloned()
             .collect();
-        
-        if !high_conf_events.is_empty() {
-            insights.push(format!("{} high-confidence reasoning steps", high_conf_events.len()));
+        let coverage_ratio = match declared_domains.len() {
+            0 => 0.0,
+            n => engaged_domains.len() as f64 / n as f64,
+        };
+
+        DomainCoverageReport {
+            declared_domains,
+            engaged_domains,
+            coverage_ratio,
         }
+    }
+
+    /// `(source_language, target_language)` for each Translation event with a known source
+    pub fn get_translation_pairs(&self) -> Vec<(String, String)> {
+        self.trace.iter()
+            .filter(|e| e.agent == AgentType::Translation)
+            .filter_map(|e| Some((e.metadata_value(SOURCE_LANGUAGE_KEY)?.to_string(), e.language.clone())))
+            .collect()
+    }
+
+    /// Per language, the fraction of its events handled by each agent type
+    pub fn compute_per_language_agent_affinity(&self) -> HashMap<String, HashMap<AgentType, f64>> {
+        language_agent_affinity(&self.trace)
+    }
+
+    /// Number of events whose input quotes an earlier event's output
+    pub fn count_back_references(&self) -> usize {
+        count_later_events(&self.get_back_reference_events())
+    }
+
+    /// `(later_index, earlier_index)` pairs where the later event's input shares at
+    /// least `MIN_BACK_REFERENCE_CHARS` consecutive characters with the earlier output
+    pub fn get_back_reference_events(&self) -> Vec<(usize, usize)> {
+        back_reference_pairs(&self.trace)
+    }
+
+    /// Every agent run in trace order as `(agent, start_index, length)`
+    fn agent_runs(&self) -> Vec<(AgentType, usize, usize)> {
+        let mut start = 0;
+        self.build_rle_agent_sequence()
+            .into_iter()
+            .map(|(agent, length)| {
+                let run = (agent, start, length);
+                start += length;
+                run
+            })
+            .collect()
+    }
 
-        // Multilingual reasoning
+    /// Extract key insights from trace
+    fn extract_key_insights(&self) -> Vec<String> {
         let languages: std::collections::HashSet<_> = self.trace.iter()
             .map(|e| e.language.as_str())
             .collect();
-        
-        if languages.len() > 1 {
-            insights.push(format!("Multilingual reasoning across {} languages", languages.len()));
+
+        build_key_insights(&InsightSignals {
+            high_confidence_count: self.trace.iter().filter(|e| e.confidence > 0.8).count(),
+            language_count: languages.len(),
+            transition_count: self.transitions.len(),
+            transition_entropy: self.compute_transition_entropy(),
+            max_consecutive_same_agent: self.get_longest_same_agent_run().map_or(0, |run| run.2),
+            detected_loops: self.detect_reasoning_loops(DEFAULT_LOOP_CYCLE_LENGTH, DEFAULT_LOOP_REPETITIONS).len(),
+        })
+    }
+
+    /// Find agent cycles repeated at least `min_repetitions` times in a row.
+    ///
+    /// The transition path (first `from_agent`, then every `to_agent`) is scanned
+    /// with sliding windows from `min_cycle_length` upward. Cycles that are
+    /// themselves repetitions of a shorter pattern are skipped, and loops never
+    /// overlap, so shorter cycles win.
+    pub fn detect_reasoning_loops(&self, min_cycle_length: usize, min_repetitions: usize) -> Vec<ReasoningLoop> {
+        let agent_path: Vec<AgentType> = self.transitions.first()
+            .map(|t| t.from_agent.clone())
+            .into_iter()
+            .chain(self.transitions.iter().map(|t| t.to_agent.clone()))
+            .collect();
+        reasoning_loops(&agent_path, min_cycle_length, min_repetitions)
+    }
+
+    /// Sparse frequency table of (from, to) agent transitions
+    pub fn get_transition_matrix(&self) -> HashMap<(AgentType, AgentType), usize> {
+        let mut matrix = HashMap::new();
+        for transition in &self.transitions {
+            *matrix.entry((transition.from_agent.clone(), transition.to_agent.clone())).or_insert(0) += 1;
+        }
+        matrix
+    }
+
+    /// Event count, confidence range, and token estimate per agent type, in canonical order
+    pub fn compute_agent_stats(&self) -> Vec<AgentStats> {
+        let mut stats: Vec<AgentStats> = AgentType::all()
+            .map(|agent| AgentStats {
+                agent,
+                event_count: 0,
+                mean_confidence: 0.0,
+                min_confidence: f64::INFINITY,
+                max_confidence: f64::NEG_INFINITY,
+                estimated_tokens: 0,
+            })
+            .collect();
+
+        for event in &self.trace {
+            if let Some(entry) = stats.iter_mut().find(|s| s.agent == event.agent) {
+                entry.event_count += 1;
+                entry.mean_confidence += event.confidence;
+                entry.min_confidence = entry.min_confidence.min(event.confidence);
+                entry.max_confidence = entry.max_confidence.max(event.confidence);
+                entry.estimated_tokens += event.estimate_tokens();
+            }
+        }
+
+        stats.retain(|s| s.event_count > 0);
+        for entry in &mut stats {
+            entry.mean_confidence /= entry.event_count as f64;
+        }
+        stats
+    }
+
+    /// Moving-average type-token ratio (MATTR) over all event outputs: the mean
+    /// share of distinct words in every window of 50 consecutive words, so long
+    /// traces are not penalized for reusing common words.
+    ///
+    /// Words are lowercased with surrounding punctuation removed. Outputs shorter
+    /// than one window are scored as a window padded with repeats (distinct words
+    /// / 50), so a handful of words cannot outscore a long varied trace; an empty
+    /// trace scores 0.0.
+    pub fn compute_output_diversity(&self) -> f64 {
+        moving_type_token_ratio(self.trace.iter().map(|e| e.output.as_str()))
+    }
+
+    /// Lexical continuity proxy: mean Jaccard similarity between the word sets of
+    /// consecutive event outputs, normalized as in `compute_output_diversity`.
+    ///
+    /// Traces with fewer than 2 events score 0.0, as does a pair of wordless outputs.
+    pub fn compute_semantic_coherence_proxy(&self) -> f64 {
+        output_coherence(&self.trace)
+    }
+
+    /// Output diversity, as in `compute_output_diversity`, for each agent type present in the trace
+    pub fn compute_output_diversity_by_agent(&self) -> HashMap<AgentType, f64> {
+        let mut outputs: HashMap<AgentType, Vec<&str>> = HashMap::new();
+        for event in &self.trace {
+            outputs.entry(event.agent.clone()).or_default().push(event.output.as_str());
+        }
+        outputs.into_iter()
+            .map(|(agent, texts)| (agent, moving_type_token_ratio(texts.into_iter())))
+            .collect()
+    }
+
+    /// Summary of event confidences; percentiles use the nearest-rank method
+    /// and an empty trace reports all zeros
+    pub fn confidence_stats(&self) -> ConfidenceStats {
+        let mut values: Vec<f64> = self.trace.iter().map(|e| e.confidence).collect();
+        if values.is_empty() {
+            return ConfidenceStats { mean: 0.0, stddev: 0.0, min: 0.0, max: 0.0, p50: 0.0, p95: 0.0 };
         }
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
 
-        // Complex agent transitions
-        if self.transitions.len() > 5 {
-            insights.push(format!("Complex reasoning with {} agent transitions", self.transitions.len()));
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        let percentile = |p: f64| values[((p * n).ceil() as usize).saturating_sub(1)];
+
+        ConfidenceStats {
+            mean,
+            stddev: variance.sqrt(),
+            min: values[0],
+            max: values[values.len() - 1],
+            p50: percentile(0.50),
+            p95: percentile(0.95),
+        }
+    }
+
+    /// Replace every event confidence with its calibrated value
+    pub fn apply_confidence_calibration(&mut self, calibrator: &ConfidenceCalibrator) {
+        for event in &mut self.trace {
+            event.confidence = calibrator.calibrate(event.confidence);
+        }
+    }
+
+    /// Events whose confidence lies more than `z_threshold` standard deviations
+    /// from the mean (needs at least three events and non-zero spread)
+    pub fn get_confidence_outliers(&self, z_threshold: f64) -> Vec<(usize, &AgentEvent)> {
+        if self.trace.len() < 3 {
+            return Vec::new();
+        }
+        let stats = self.confidence_stats();
+        if stats.stddev == 0.0 {
+            return Vec::new();
+        }
+        self.trace.iter()
+            .enumerate()
+            .filter(|(_, e)| (e.confidence - stats.mean).abs() / stats.stddev > z_threshold)
+            .collect()
+    }
+
+    /// Change in confidence between each pair of consecutive events
+    pub fn compute_confidence_drift(&self) -> Vec<f64> {
+        self.trace.windows(2)
+            .map(|pair| pair[1].confidence - pair[0].confidence)
+            .collect()
+    }
+
+    /// Collect session score, fold, provenance, and per-agent analytics in one report
+    pub fn generate_trace_report(&self) -> TraceReport {
+        // Score and fold once; the provenance log reuses the fold's totals
+        let session_score = self.score_trace();
+        let memory_fold = self.fold_memory_with_score(session_score.overall);
+        let agent_stats = self.compute_agent_stats();
+        let reasoning_entropy = shannon_entropy(agent_stats.iter().map(|s| s.event_count));
+        let provenance = self.provenance_with_totals(
+            reasoning_entropy,
+            memory_fold.total_estimated_tokens,
+            memory_fold.output_diversity,
+        );
+
+        let agents: Vec<AgentType> = AgentType::all().collect();
+        let mut counts = vec![vec![0; agents.len()]; agents.len()];
+        for transition in &self.transitions {
+            let from = agents.iter().position(|a| *a == transition.from_agent);
+            let to = agents.iter().position(|a| *a == transition.to_agent);
+            if let (Some(from), Some(to)) = (from, to) {
+                counts[from][to] += 1;
+            }
+        }
+
+        TraceReport {
+            session_id: self.session_id.clone(),
+            contributor_id: self.contributor_id.clone(),
+            session_score,
+            provenance,
+            reasoning_entropy,
+            language_entropy: memory_fold.language_entropy,
+            top_words: memory_fold.word_frequency_analysis(TRACE_REPORT_TOP_WORDS),
+            transition_matrix: TransitionMatrix { agents, counts },
+            confidence_drift: self.compute_confidence_drift(),
+            reasoning_velocity: self.compute_reasoning_velocity(),
+            event_velocity: self.compute_event_velocity(),
+            total_cost_usd: memory_fold.total_cost_usd,
+            agent_stats,
+            memory_fold,
+        }
+    }
+
+    /// Transition pairs with their counts, most frequent first (ties in order of first occurrence)
+    pub fn get_transition_frequency_map(&self) -> Vec<((AgentType, AgentType), usize)> {
+        let mut frequencies: Vec<((AgentType, AgentType), usize)> = Vec::new();
+        for transition in &self.transitions {
+            let pair = (transition.from_agent.clone(), transition.to_agent.clone());
+            match frequencies.iter_mut().find(|(p, _)| *p == pair) {
+                Some((_, count)) => *count += 1,
+                None => frequencies.push((pair, 1)),
+            }
         }
+        frequencies.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        frequencies
+    }
 
-        insights
+    /// Most common transition pair
+    pub fn most_frequent_transition(&self) -> Option<(AgentType, AgentType)> {
+        self.get_transition_frequency_map().into_iter().next().map(|(pair, _)| pair)
+    }
+
+    /// Least common transition pair
+    pub fn least_frequent_transition(&self) -> Option<(AgentType, AgentType)> {
+        self.get_transition_frequency_map().pop().map(|(pair, _)| pair)
+    }
+
+    /// Shannon entropy (bits) over transition pair frequencies
+    pub fn compute_transition_entropy(&self) -> f64 {
+        shannon_entropy(self.get_transition_matrix().into_values())
     }
 
     /// Compute language distribution
@@ -312,19 +2142,50 @@ impl MetaAgent {
         counts
     }
 
-    /// Emit provenance log with SHA-256 hash
-    pub fn emit_provenance(&self) -> ProvenanceLog {
-        let mut hasher = Sha256::new();
-        
-        // Hash the entire reasoning trace for uniqueness
-        for event in &self.trace {
-            hasher.update(event.input.as_bytes());
-            hasher.update(event.output.as_bytes());
-            hasher.update(event.language.as_bytes());
-            hasher.update(format!("{}", event.agent).as_bytes());
-        }
-        
-        let trace_hash = format!("{:x}", hasher.finalize());
+    /// Infer the session's main task from agent type shares.
+    ///
+    /// Rules apply in order: Translation > 40%, Classification > 40%,
+    /// Synthesis > 30% with Retrieval > 20% (Research), Validation > 40%, then
+    /// four or more agent types whose counts are within a factor of two (MultiStep).
+    pub fn infer_primary_task(&self) -> Task {
+        infer_task(&self.trace)
+    }
+
+    /// Shannon entropy (bits) of the agent type distribution
+    pub fn compute_reasoning_entropy(&self) -> f64 {
+        shannon_entropy(self.count_agent_types().into_values())
+    }
+
+    /// Shannon entropy (bits) of the language distribution
+    pub fn compute_language_entropy(&self) -> f64 {
+        shannon_entropy(self.compute_language_distribution().into_values())
+    }
+
+    /// Mean of reasoning and language entropy, normalized by log2(8) into [0.0, 1.0]
+    pub fn combined_diversity_score(&self) -> f64 {
+        normalized_diversity(self.compute_reasoning_entropy(), self.compute_language_entropy())
+    }
+
+    /// Score the session on confidence, diversity, depth, and transition quality
+    pub fn score_trace(&self) -> SessionScore {
+        let transition_scores: Vec<f64> = self.transitions.iter()
+            .map(|t| t.transition_score)
+            .collect();
+        score_session(&self.trace, &transition_scores)
+    }
+
+    /// Emit provenance log with SHA-256 hash
+    pub fn emit_provenance(&self) -> ProvenanceLog {
+        self.provenance_with_totals(
+            self.compute_reasoning_entropy(),
+            self.total_estimated_tokens(),
+            self.compute_output_diversity(),
+        )
+    }
+
+    /// Provenance log given trace-wide totals the caller has already computed
+    fn provenance_with_totals(&self, reasoning_entropy: f64, total_tokens_estimate: usize, output_diversity: f64) -> ProvenanceLog {
+        let trace_hash = self.compute_trace_hash();
         let uniqueness_score = self.compute_uniqueness_score(&trace_hash);
 
         ProvenanceLog {
@@ -334,19 +2195,59 @@ impl MetaAgent {
             backend_used: self.backend_used.clone(),
             timestamp: Utc::now(),
             trace_depth: self.trace.len(),
+            weighted_trace_depth: self.compute_weighted_trace_depth(),
             uniqueness_score,
             transitions: self.transitions.clone(),
+            parent_hash: None,
+            reasoning_entropy,
+            total_tokens_estimate,
+            output_diversity,
+            language_sequence: self.trace.iter().map(|e| e.language.clone()).collect(),
+            session_metadata: self.session_metadata.clone(),
+            signature: None,
+        }
+    }
+
+    /// Short content-derived session identifier: the first 12 hex characters
+    /// of the provenance trace hash. Identical sessions share a fingerprint.
+    pub fn get_session_fingerprint(&self) -> String {
+        let mut fingerprint = self.compute_trace_hash();
+        fingerprint.truncate(SESSION_FINGERPRINT_LEN);
+        fingerprint
+    }
+
+    /// Hex SHA-256 over event content and session metadata
+    fn compute_trace_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        
+        // Hash the entire reasoning trace for uniqueness
+        for event in &self.trace {
+            hasher.update(event.input.as_bytes());
+            hasher.update(event.output.as_bytes());
+            hasher.update(event.language.as_bytes());
+            hasher.update(format!("{}", event.agent).as_bytes());
         }
+
+        // Session metadata in key order so the hash is deterministic
+        let mut session_metadata: Vec<_> = self.session_metadata.iter().collect();
+        session_metadata.sort();
+        for (key, value) in session_metadata {
+            hash_field(&mut hasher, key);
+            hash_field(&mut hasher, value);
+        }
+        
+        format!("{:x}", hasher.finalize())
     }
 
     /// Compute uniqueness score (simplified - in production would check against database)
     fn compute_uniqueness_score(&self, hash: &str) -> f64 {
         // Score based on trace complexity and diversity
-        let agent_diversity = self.count_agent_types().len() as f64 / 8.0; // 8 agent types
+        let agent_diversity = self.count_agent_types().len() as f64 / AgentType::count() as f64;
         let language_diversity = self.compute_language_distribution().len() as f64 / 5.0; // normalize
         let transition_complexity = (self.transitions.len() as f64 / self.trace.len() as f64).min(1.0);
+        let structural_score = (agent_diversity + language_diversity + transition_complexity) / 3.0;
         
-        (agent_diversity + language_diversity + transition_complexity) / 3.0
+        0.9 * structural_score + 0.1 * self.combined_diversity_score()
     }
 
     /// Update contributor profile based on current trace
@@ -366,6 +2267,19 @@ impl MetaAgent {
             .take(3)
             .map(|(lang, _)| lang)
             .collect();
+
+        // Fluency = mean confidence of events in each language
+        let mut confidence_sums: HashMap<String, (f64, usize)> = HashMap::new();
+        for event in &self.trace {
+            let entry = confidence_sums.entry(event.language.clone()).or_insert((0.0, 0));
+            entry.0 += event.confidence;
+            entry.1 += 1;
+        }
+        self.profile.language_fluency_scores = confidence_sums.into_iter()
+            .map(|(lang, (sum, count))| (lang, sum / count as f64))
+            .collect();
+
+        self.profile.specialization_index = self.profile.compute_specialization_index(&self.compute_agent_stats());
     }
 
     /// Get trace depth (number of reasoning steps)
@@ -373,14 +2287,374 @@ impl MetaAgent {
         self.trace.len()
     }
 
+    /// Trace depth with each event weighted by `AgentType::weight_for_depth_scoring`
+    pub fn compute_weighted_trace_depth(&self) -> f64 {
+        self.trace.iter().map(|e| e.agent.weight_for_depth_scoring()).sum()
+    }
+
     /// Get transition count
     pub fn get_transition_count(&self) -> usize {
         self.transitions.len()
     }
 
+    /// Events with timestamps in [start, end], in timestamp order
+    pub fn get_events_in_time_range(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<&AgentEvent> {
+        let mut events: Vec<&AgentEvent> = self.trace.iter()
+            .filter(|e| e.timestamp >= start && e.timestamp <= end)
+            .collect();
+        events.sort_by_key(|e| e.timestamp);
+        events
+    }
+
+    /// Transitions with timestamps in [start, end], in timestamp order
+    pub fn get_transitions_in_time_range(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<&AgentTransition> {
+        let mut transitions: Vec<&AgentTransition> = self.transitions.iter()
+            .filter(|t| t.timestamp >= start && t.timestamp <= end)
+            .collect();
+        transitions.sort_by_key(|t| t.timestamp);
+        transitions
+    }
+
+    /// Time between the first and last logged event
+    pub fn get_session_duration(&self) -> Option<std::time::Duration> {
+        let first = self.trace.iter().map(|e| e.timestamp).min()?;
+        let last = self.trace.iter().map(|e| e.timestamp).max()?;
+        (last - first).to_std().ok()
+    }
+
+    /// Transitions per minute of session time (`None` for sessions under one second)
+    pub fn compute_reasoning_velocity(&self) -> Option<f64> {
+        self.per_minute(self.transitions.len())
+    }
+
+    /// Events per minute of session time (`None` for sessions under one second)
+    pub fn compute_event_velocity(&self) -> Option<f64> {
+        self.per_minute(self.trace.len())
+    }
+
+    /// `count` divided by the session duration in minutes
+    fn per_minute(&self, count: usize) -> Option<f64> {
+        let duration = self.get_session_duration()?;
+        if duration < std::time::Duration::from_secs(1) {
+            return None;
+        }
+        Some(count as f64 / (duration.as_secs_f64() / 60.0))
+    }
+
+    /// Event at the given confidence percentile (0.0 = lowest, 1.0 = highest), nearest-rank method
+    pub fn event_at_percentile(&self, percentile: f64) -> Option<&AgentEvent> {
+        if !(0.0..=1.0).contains(&percentile) || self.trace.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<&AgentEvent> = self.trace.iter().collect();
+        sorted.sort_by(|a, b| a.confidence.partial_cmp(&b.confidence).unwrap_or(std::cmp::Ordering::Equal));
+
+        let rank = (percentile * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1)])
+    }
+
+    /// Confidence value at the given percentile (see `event_at_percentile`)
+    pub fn percentile_confidence(&self, percentile: f64) -> Option<f64> {
+        self.event_at_percentile(percentile).map(|e| e.confidence)
+    }
+
+    /// Up to `max_per_agent` evenly spaced events per agent type, in trace order
+    pub fn sample_representative_events(&self, max_per_agent: usize) -> Vec<&AgentEvent> {
+        let mut indices_by_agent: HashMap<&AgentType, Vec<usize>> = HashMap::new();
+        for (i, event) in self.trace.iter().enumerate() {
+            indices_by_agent.entry(&event.agent).or_default().push(i);
+        }
+
+        let mut selected: Vec<usize> = Vec::new();
+        for indices in indices_by_agent.values() {
+            let take = max_per_agent.min(indices.len());
+            if take <= 1 {
+                selected.extend(indices.iter().take(take));
+            } else {
+                // Spread picks across the agent's events, including first and last
+                let last = (indices.len() - 1) as f64;
+                selected.extend((0..take).map(|k| indices[(k as f64 * last / (take - 1) as f64).round() as usize]));
+            }
+        }
+        selected.sort_unstable();
+
+        selected.into_iter().map(|i| &self.trace[i]).collect()
+    }
+
+    /// The `n` highest-confidence events, most confident first
+    pub fn sample_highest_confidence_events(&self, n: usize) -> Vec<&AgentEvent> {
+        let mut sorted: Vec<&AgentEvent> = self.trace.iter().collect();
+        sorted.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+        sorted.truncate(n);
+        sorted
+    }
+
     /// Export trace for analysis
     pub fn export_trace_json(&self) -> Result<String, serde_json::Error> {
-        serde_json::to_string_pretty(&self.trace)
+        serde_json::to_string_pretty(&self.expanded_trace())
+    }
+
+    /// Export trace as JSON grouped by the profile's language preference order.
+    ///
+    /// Events in `preferred_languages[0]` come first, then `[1]`, and so on, with
+    /// unlisted languages last; order within a language is unchanged. Each event
+    /// carries its original trace position as `index`.
+    pub fn export_trace_json_with_language_priority(&self) -> Result<String, serde_json::Error> {
+        #[derive(Serialize)]
+        struct IndexedEvent {
+            index: usize,
+            #[serde(flatten)]
+            event: AgentEvent,
+        }
+
+        let preferred = &self.profile.preferred_languages;
+        let mut events: Vec<IndexedEvent> = self.expanded_trace()
+            .into_iter()
+            .enumerate()
+            .map(|(index, event)| IndexedEvent { index, event })
+            .collect();
+        events.sort_by_key(|e| {
+            preferred.iter()
+                .position(|lang| *lang == e.event.language)
+                .unwrap_or(preferred.len())
+        });
+        serde_json::to_string_pretty(&events)
+    }
+
+    /// Export trace as OpenAI-style chat messages.
+    ///
+    /// Retrieval, Classification, and Translation events become `user` turns
+    /// carrying the event input; all other agents become `assistant` turns
+    /// carrying the event output.
+    pub fn export_openai_messages(&self) -> Vec<serde_json::Value> {
+        self.trace.iter()
+            .map(|event| match event.agent {
+                AgentType::Retrieval | AgentType::Classification | AgentType::Translation => {
+                    serde_json::json!({ "role": "user", "content": event.input })
+                }
+                _ => serde_json::json!({ "role": "assistant", "content": event.output }),
+            })
+            .collect()
+    }
+
+    /// Export the session as a single fine-tuning JSONL record (`{"messages": [...]}`)
+    pub fn export_openai_jsonl(&self) -> Result<String, serde_json::Error> {
+        let record = serde_json::json!({ "messages": self.export_openai_messages() });
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+        Ok(line)
+    }
+
+    /// Export trace as LangChain-style run objects, one `tool` run per event.
+    ///
+    /// Each run ends when the next event starts; the last run is assumed to take
+    /// as long as the gap before it (zero for a single-event trace).
+    pub fn export_langchain_compatible_json(&self) -> Result<String, serde_json::Error> {
+        let runs: Vec<serde_json::Value> = self.trace.iter()
+            .enumerate()
+            .map(|(i, event)| {
+                let end_time = match self.trace.get(i + 1) {
+                    Some(next) => next.timestamp,
+                    None => {
+                        let elapsed = match i {
+                            0 => chrono::Duration::zero(),
+                            _ => (event.timestamp - self.trace[i - 1].timestamp).max(chrono::Duration::zero()),
+                        };
+                        event.timestamp + elapsed
+                    }
+                };
+                serde_json::json!({
+                    "run_id": format!("{}_{}", self.session_id, i),
+                    "name": event.agent.to_string(),
+                    "inputs": { "text": event.input },
+                    "outputs": { "text": event.output },
+                    "run_type": "tool",
+                    "start_time": event.timestamp.to_rfc3339(),
+                    "end_time": end_time.to_rfc3339(),
+                })
+            })
+            .collect();
+        serde_json::to_string_pretty(&runs)
+    }
+
+    /// Export the session as a W3C PROV-N document.
+    ///
+    /// The session is a `bundle` and the contributor a software `agent`. Each event
+    /// is an activity associated with that agent, whose output entity is tied to it
+    /// by `wasGeneratedBy`. Each transition becomes `wasInfluencedBy` between the
+    /// activities on either side of the agent change.
+    pub fn export_w3c_prov_n(&self) -> String {
+        use std::fmt::Write;
+
+        let agent_id = format!("{}:contributor_{}", PROV_PREFIX, prov_local_name(&self.contributor_id));
+        let mut doc = String::new();
+        let _ = writeln!(doc, "document");
+        let _ = writeln!(doc, "  prefix {} <{}>", PROV_PREFIX, PROV_NAMESPACE);
+        let _ = writeln!(doc, "  bundle {}:{}", PROV_PREFIX, prov_local_name(&self.session_id));
+        let _ = writeln!(
+            doc,
+            "    agent({}, [prov:type='prov:SoftwareAgent', prov:label=\"{}\", {}:backend=\"{}\"])",
+            agent_id,
+            prov_escape(&self.contributor_id),
+            PROV_PREFIX,
+            prov_escape(&self.backend_used),
+        );
+
+        for (i, event) in self.trace.iter().enumerate() {
+            let time = event.timestamp.to_rfc3339();
+            let _ = writeln!(
+                doc,
+                "    activity({p}:activity_{i}, {time}, -, [prov:type=\"{agent}\", {p}:language=\"{lang}\", {p}:confidence=\"{conf}\" %% xsd:double])",
+                p = PROV_PREFIX,
+                agent = event.agent,
+                lang = prov_escape(&event.language),
+                conf = event.confidence,
+            );
+            let _ = writeln!(doc, "    wasAssociatedWith({}:activity_{}, {}, -)", PROV_PREFIX, i, agent_id);
+            let _ = writeln!(doc, "    entity({}:output_{}, [prov:value=\"{}\"])", PROV_PREFIX, i, prov_escape(&event.output));
+            let _ = writeln!(doc, "    wasGeneratedBy({p}:output_{i}, {p}:activity_{i}, {time})", p = PROV_PREFIX);
+        }
+
+        for (from, to, transition) in self.transition_event_pairs() {
+            let _ = writeln!(
+                doc,
+                "    wasInfluencedBy({p}:activity_{to}, {p}:activity_{from}, [prov:label=\"{reason}\"])",
+                p = PROV_PREFIX,
+                reason = prov_escape(&transition.reason),
+            );
+        }
+
+        let _ = writeln!(doc, "  endBundle");
+        let _ = writeln!(doc, "endDocument");
+        doc
+    }
+
+    /// Export the same provenance graph as `export_w3c_prov_n` in PROV-JSON
+    pub fn export_w3c_prov_json(&self) -> Result<String, serde_json::Error> {
+        let agent_id = format!("{}:contributor_{}", PROV_PREFIX, prov_local_name(&self.contributor_id));
+        let mut activities = serde_json::Map::new();
+        let mut entities = serde_json::Map::new();
+        let mut generations = serde_json::Map::new();
+        let mut associations = serde_json::Map::new();
+        let mut influences = serde_json::Map::new();
+
+        for (i, event) in self.trace.iter().enumerate() {
+            let activity = format!("{}:activity_{}", PROV_PREFIX, i);
+            let output = format!("{}:output_{}", PROV_PREFIX, i);
+            activities.insert(activity.clone(), serde_json::json!({
+                "prov:startTime": event.timestamp.to_rfc3339(),
+                "prov:type": event.agent.to_string(),
+                format!("{}:language", PROV_PREFIX): event.language,
+                format!("{}:confidence", PROV_PREFIX): event.confidence,
+            }));
+            entities.insert(output.clone(), serde_json::json!({ "prov:value": event.output }));
+            generations.insert(format!("_:wGB{}", i), serde_json::json!({
+                "prov:entity": output,
+                "prov:activity": activity,
+                "prov:time": event.timestamp.to_rfc3339(),
+            }));
+            associations.insert(format!("_:wAW{}", i), serde_json::json!({
+                "prov:activity": activity,
+                "prov:agent": agent_id,
+            }));
+        }
+
+        for (n, (from, to, transition)) in self.transition_event_pairs().into_iter().enumerate() {
+            influences.insert(format!("_:wINF{}", n), serde_json::json!({
+                "prov:influencee": format!("{}:activity_{}", PROV_PREFIX, to),
+                "prov:influencer": format!("{}:activity_{}", PROV_PREFIX, from),
+                "prov:label": transition.reason,
+            }));
+        }
+
+        let bundle = serde_json::json!({
+            "agent": {
+                agent_id.clone(): {
+                    "prov:type": { "$": "prov:SoftwareAgent", "type": "prov:QUALIFIED_NAME" },
+                    "prov:label": self.contributor_id,
+                    format!("{}:backend", PROV_PREFIX): self.backend_used,
+                },
+            },
+            "activity": activities,
+            "entity": entities,
+            "wasGeneratedBy": generations,
+            "wasAssociatedWith": associations,
+            "wasInfluencedBy": influences,
+        });
+
+        serde_json::to_string_pretty(&serde_json::json!({
+            "prefix": { PROV_PREFIX: PROV_NAMESPACE },
+            "bundle": { format!("{}:{}", PROV_PREFIX, prov_local_name(&self.session_id)): bundle },
+        }))
+    }
+
+    /// Event indices (before, after) for each transition, matched in order to the
+    /// next adjacent event pair with the same agents; unmatched transitions are skipped
+    fn transition_event_pairs(&self) -> Vec<(usize, usize, &AgentTransition)> {
+        let mut pairs = Vec::new();
+        let mut next = 1;
+        for transition in &self.transitions {
+            let found = (next..self.trace.len()).find(|&i| {
+                self.trace[i - 1].agent == transition.from_agent && self.trace[i].agent == transition.to_agent
+            });
+            if let Some(i) = found {
+                pairs.push((i - 1, i, transition));
+                next = i + 1;
+            }
+        }
+        pairs
+    }
+
+    /// Export trace as CSV without raw input/output text
+    pub fn export_trace_csv(&self) -> Result<String, csv::Error> {
+        self.write_trace_csv(false)
+    }
+
+    /// Export trace as CSV including raw input/output text
+    pub fn export_trace_csv_full(&self) -> Result<String, csv::Error> {
+        self.write_trace_csv(true)
+    }
+
+    fn write_trace_csv(&self, include_text: bool) -> Result<String, csv::Error> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        let mut header = vec![
+            "index", "timestamp", "agent", "input_word_count", "output_word_count",
+            "language", "confidence", "elapsed_ms", "tags",
+        ];
+        if include_text {
+            header.extend(["input", "output"]);
+        }
+        writer.write_record(&header)?;
+
+        for (i, event) in self.trace.iter().enumerate() {
+            // Milliseconds since the previous event (0 for the first event)
+            let elapsed_ms = match i {
+                0 => 0,
+                _ => (event.timestamp - self.trace[i - 1].timestamp).num_milliseconds().max(0),
+            };
+
+            let mut record = vec![
+                i.to_string(),
+                event.timestamp.to_rfc3339(),
+                event.agent.to_string(),
+                event.input.split_whitespace().count().to_string(),
+                event.output.split_whitespace().count().to_string(),
+                event.language.clone(),
+                event.confidence.to_string(),
+                elapsed_ms.to_string(),
+                event.tags.join(";"),
+            ];
+            if include_text {
+                record.push(event.input.clone());
+                record.push(event.output.clone());
+            }
+            writer.write_record(&record)?;
+        }
+
+        let bytes = writer.into_inner().map_err(|e| csv::Error::from(e.into_error()))?;
+        String::from_utf8(bytes)
+            .map_err(|e| csv::Error::from(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
     }
 
     /// Export provenance for verification
@@ -389,3 +2663,1350 @@ impl MetaAgent {
         serde_json::to_string_pretty(&provenance)
     }
 }
+
+impl ProvenanceLog {
+    /// Link this log to the session it builds on.
+    ///
+    /// The trace hash stays the session's content hash; the link is committed to
+    /// by the signature payload, so link before signing.
+    pub fn with_parent(mut self, parent: &ProvenanceLog) -> Self {
+        self.parent_hash = Some(parent.trace_hash.clone());
+        self
+    }
+
+    /// Copy with the contributor replaced by a salted hash and the backend hidden.
+    ///
+    /// Trace hash, depth, uniqueness, and agent sequence are unchanged. The signature
+    /// is dropped, since it covers the original contributor and would identify them.
+    pub fn anonymize(&self, salt: &str) -> ProvenanceLog {
+        let mut hasher = Sha256::new();
+        hasher.update(self.contributor_id.as_bytes());
+        hasher.update(salt.as_bytes());
+        let digest = format!("{:x}", hasher.finalize());
+
+        let mut anonymized = self.clone();
+        anonymized.contributor_id = format!("{}{}", ANONYMIZED_PREFIX, &digest[..8]);
+        anonymized.backend_used = "anonymized".to_string();
+        anonymized.signature = None;
+        anonymized
+    }
+
+    /// Whether the contributor id was produced by `anonymize`
+    pub fn is_anonymized(&self) -> bool {
+        self.contributor_id.starts_with(ANONYMIZED_PREFIX)
+    }
+
+    /// Check that this log directly extends `parent` (errors index the child as 1)
+    pub fn verify_chain(&self, parent: &ProvenanceLog) -> Result<(), ChainError> {
+        match self.check_link(parent, 1) {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Verify an ordered chain of logs from root to tip.
+    ///
+    /// Besides parent linkage and timestamps, trace depth must be non-decreasing
+    /// and every log must belong to the root's contributor. Logs carry no event
+    /// content, so a rewritten `trace_hash` can only surface as a mismatch at its
+    /// child; `verify_full_chain_signed` pins it to the log itself.
+    pub fn verify_full_chain(logs: &[ProvenanceLog]) -> ChainVerificationResult {
+        for i in 1..logs.len() {
+            let error = logs[i].check_link(&logs[i - 1], i).or_else(|| {
+                (logs[i].contributor_id != logs[0].contributor_id).then_some(ChainError::ContributorMismatch(i))
+            });
+
+            if error.is_some() {
+                return ChainVerificationResult {
+                    valid: false,
+                    broken_at: Some(i),
+                    error,
+                };
+            }
+        }
+
+        ChainVerificationResult {
+            valid: true,
+            broken_at: None,
+            error: None,
+        }
+    }
+
+    /// `verify_full_chain`, also requiring every log to carry a valid signature
+    /// from `public_key`.
+    ///
+    /// A log whose signature fails, including one whose hash or parent link was
+    /// changed after signing, breaks the chain at its own index. The earliest
+    /// problem is reported.
+    pub fn verify_full_chain_signed(logs: &[ProvenanceLog], public_key: &[u8; 32]) -> ChainVerificationResult {
+        let linkage = Self::verify_full_chain(logs);
+        let unsigned = logs.iter().position(|log| ProvenanceSigner::verify(log, public_key).is_err());
+        match unsigned {
+            Some(i) if linkage.broken_at.is_none_or(|broken_at| i < broken_at) => ChainVerificationResult {
+                valid: false,
+                broken_at: Some(i),
+                error: Some(ChainError::InvalidSignature(i)),
+            },
+            _ => linkage,
+        }
+    }
+
+    /// Express the log as a W3C Verifiable Credential (unsigned, no proof section)
+    pub fn to_verifiable_credential(&self) -> serde_json::Value {
+        serde_json::json!({
+            "@context": ["https://www.w3.org/2018/credentials/v1"],
+            "type": ["VerifiableCredential", VC_TRACE_TYPE],
+            "issuer": self.contributor_id,
+            "issuanceDate": self.timestamp.to_rfc3339(),
+            "credentialSubject": {
+                "traceHash": self.trace_hash,
+                "traceDepth": self.trace_depth,
+                "weightedTraceDepth": self.weighted_trace_depth,
+                "uniquenessScore": self.uniqueness_score,
+                "backendUsed": self.backend_used,
+                "agentCount": self.agent_sequence.len(),
+            },
+        })
+    }
+
+    /// Rebuild a log from `to_verifiable_credential` output.
+    ///
+    /// The credential carries no agent sequence or transitions, so those come back empty.
+    /// Credentials issued before weighted depth was recorded restore it as 0.0.
+    pub fn from_verifiable_credential(vc: &serde_json::Value) -> Result<ProvenanceLog, VcError> {
+        let is_trace = vc["type"].as_array()
+            .is_some_and(|types| types.iter().any(|t| t == VC_TRACE_TYPE));
+        if !is_trace {
+            return Err(VcError::InvalidType);
+        }
+
+        let subject = &vc["credentialSubject"];
+        let field = |value: &serde_json::Value, name: &'static str| {
+            value.as_str().map(str::to_string).ok_or(VcError::MissingField(name))
+        };
+
+        Ok(ProvenanceLog {
+            trace_hash: field(&subject["traceHash"], "traceHash")?,
+            agent_sequence: Vec::new(),
+            contributor_id: field(&vc["issuer"], "issuer")?,
+            backend_used: field(&subject["backendUsed"], "backendUsed")?,
+            timestamp: DateTime::parse_from_rfc3339(&field(&vc["issuanceDate"], "issuanceDate")?)?.with_timezone(&Utc),
+            trace_depth: subject["traceDepth"].as_u64().ok_or(VcError::MissingField("traceDepth"))? as usize,
+            weighted_trace_depth: subject["weightedTraceDepth"].as_f64().unwrap_or(0.0),
+            uniqueness_score: subject["uniquenessScore"].as_f64().ok_or(VcError::MissingField("uniquenessScore"))?,
+            transitions: Vec::new(),
+            parent_hash: None,
+            reasoning_entropy: 0.0,
+            total_tokens_estimate: 0,
+            output_diversity: 0.0,
+            language_sequence: Vec::new(),
+            session_metadata: HashMap::new(),
+            signature: None,
+        })
+    }
+
+    /// Bytes covered by a `ProvenanceSigner` signature
+    fn signing_payload(&self) -> Vec<u8> {
+        format!(
+            "{}|{}|{}|{}|{}|{}|{}",
+            self.trace_hash,
+            self.contributor_id,
+            self.backend_used,
+            self.timestamp.to_rfc3339(),
+            self.trace_depth,
+            self.weighted_trace_depth,
+            self.parent_hash.as_deref().unwrap_or("")
+        )
+        .into_bytes()
+    }
+
+    /// First linkage problem between this log (at `index`) and its parent
+    fn check_link(&self, parent: &ProvenanceLog, index: usize) -> Option<ChainError> {
+        match &self.parent_hash {
+            None => Some(ChainError::MissingParentHash(index)),
+            Some(hash) if *hash != parent.trace_hash => Some(ChainError::HashMismatch(index)),
+            Some(_) if self.timestamp < parent.timestamp => Some(ChainError::TimestampRegression(index)),
+            Some(_) if self.trace_depth < parent.trace_depth => Some(ChainError::DepthRegression(index)),
+            Some(_) => None,
+        }
+    }
+}
+
+impl ProvenanceSigner {
+    /// Create a signer from a 32-byte Ed25519 secret key
+    pub fn new(secret_key: &[u8; 32]) -> Self {
+        ProvenanceSigner {
+            signing_key: ed25519_dalek::SigningKey::from_bytes(secret_key),
+        }
+    }
+
+    /// Public key that verifies this signer's signatures
+    pub fn public_key(&self) -> [u8; 32] {
+        self.signing_key.verifying_key().to_bytes()
+    }
+
+    /// Sign the log's hash, contributor, backend, timestamp, raw and weighted depth,
+    /// and parent link in place
+    pub fn sign(&self, provenance: &mut ProvenanceLog) {
+        use ed25519_dalek::Signer;
+
+        let signature = self.signing_key.sign(&provenance.signing_payload());
+        provenance.signature = Some(signature.to_bytes().to_vec());
+    }
+
+    /// Check a log's signature against `public_key`
+    pub fn verify(provenance: &ProvenanceLog, public_key: &[u8; 32]) -> Result<(), SignatureError> {
+        let bytes = provenance.signature.as_ref().ok_or(SignatureError::MissingSignature)?;
+        let bytes: [u8; 64] = bytes.as_slice().try_into().map_err(|_| SignatureError::InvalidSignature)?;
+        let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(public_key)
+            .map_err(|_| SignatureError::InvalidPublicKey)?;
+
+        verifying_key
+            .verify_strict(&provenance.signing_payload(), &ed25519_dalek::Signature::from_bytes(&bytes))
+            .map_err(|_| SignatureError::InvalidSignature)
+    }
+}
+
+#[cfg(feature = "qrcode")]
+impl ProvenanceLog {
+    /// Hex characters of `trace_hash` encoded into QR codes
+    const QR_HASH_PREFIX_LEN: usize = 32;
+
+    /// Render the truncated trace hash as an SVG QR code
+    pub fn to_qr_code_svg(&self) -> Result<String, QrError> {
+        let code = self.qr_code()?;
+        Ok(code.render::<qrcode::render::svg::Color>().min_dimensions(200, 200).build())
+    }
+
+    /// Render the truncated trace hash as a PNG QR code
+    pub fn to_qr_code_bytes(&self) -> Result<Vec<u8>, QrError> {
+        let image = self.qr_code()?.render::<image::Luma<u8>>().build();
+        let mut bytes = Vec::new();
+        image.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+        Ok(bytes)
+    }
+
+    /// QR code of the first 32 hex characters of the trace hash
+    fn qr_code(&self) -> Result<qrcode::QrCode, QrError> {
+        let end = self.trace_hash.len().min(Self::QR_HASH_PREFIX_LEN);
+        let code = qrcode::QrCode::with_error_correction_level(&self.trace_hash[..end], qrcode::EcLevel::M)?;
+        Ok(code)
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl MetaAgent {
+    /// Maximum events per Parquet row group
+    const PARQUET_ROW_GROUP_SIZE: usize = 1000;
+
+    /// Export the trace as an uncompressed Parquet file.
+    ///
+    /// Columns are `timestamp` (UTC microseconds), `agent`, `input`, `output`,
+    /// `language`, `confidence`, `metadata` (JSON object), `tags` (`LIST<UTF8>`)
+    /// and `estimated_cost_usd`. Contributor, backend, and session ids are stored as
+    /// file key-value metadata.
+    pub fn export_trace_parquet(&self) -> Result<Vec<u8>, ParquetError> {
+        use arrow_array::builder::{ListBuilder, StringBuilder};
+        use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMicrosecondArray};
+        use parquet::file::metadata::KeyValue;
+        use std::sync::Arc;
+
+        let events = self.expanded_trace();
+        let strings = |f: fn(&AgentEvent) -> String| -> ArrayRef {
+            Arc::new(StringArray::from(events.iter().map(f).collect::<Vec<_>>()))
+        };
+
+        let mut tags = ListBuilder::new(StringBuilder::new());
+        for event in &events {
+            for tag in &event.tags {
+                tags.values().append_value(tag);
+            }
+            tags.append(true);
+        }
+        let metadata = events.iter()
+            .map(|e| serde_json::to_string(&e.metadata))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let batch = RecordBatch::try_from_iter(vec![
+            ("timestamp", Arc::new(
+                TimestampMicrosecondArray::from(events.iter().map(|e| e.timestamp.timestamp_micros()).collect::<Vec<_>>())
+                    .with_timezone("UTC"),
+            ) as ArrayRef),
+            ("agent", strings(|e| e.agent.to_string())),
+            ("input", strings(|e| e.input.clone())),
+            ("output", strings(|e| e.output.clone())),
+            ("language", strings(|e| e.language.clone())),
+            ("confidence", Arc::new(Float64Array::from(events.iter().map(|e| e.confidence).collect::<Vec<_>>()))),
+            ("metadata", Arc::new(StringArray::from(metadata))),
+            ("tags", Arc::new(tags.finish())),
+            ("estimated_cost_usd", Arc::new(Float64Array::from(
+                events.iter().map(|e| e.estimated_cost_usd).collect::<Vec<_>>(),
+            ))),
+        ])?;
+
+        let properties = parquet::file::properties::WriterProperties::builder()
+            .set_max_row_group_size(Self::PARQUET_ROW_GROUP_SIZE)
+            .set_key_value_metadata(Some(vec![
+                KeyValue::new("contributor_id".to_string(), self.contributor_id.clone()),
+                KeyValue::new("backend_used".to_string(), self.backend_used.clone()),
+                KeyValue::new("session_id".to_string(), self.session_id.clone()),
+            ]))
+            .build();
+
+        let mut bytes = Vec::new();
+        let mut writer = parquet::arrow::ArrowWriter::try_new(&mut bytes, batch.schema(), Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(bytes)
+    }
+
+    /// Rebuild a MetaAgent from `export_trace_parquet` output.
+    ///
+    /// Transitions are re-derived from agent changes; profile and session
+    /// metadata are not stored in the file and start empty.
+    pub fn import_trace_parquet(bytes: &[u8]) -> Result<MetaAgent, ParquetError> {
+        use arrow_array::{Array, Float64Array, ListArray, RecordBatch, StringArray, TimestampMicrosecondArray};
+
+        let builder = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(
+            bytes::Bytes::copy_from_slice(bytes),
+        )?;
+        let file_metadata: HashMap<String, String> = builder.metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .into_iter()
+            .flatten()
+            .filter_map(|kv| Some((kv.key.clone(), kv.value.clone()?)))
+            .collect();
+        let kv = |key: &str| file_metadata.get(key).cloned().unwrap_or_default();
+
+        let mut meta = MetaAgent::new(&kv("contributor_id"), &kv("backend_used"));
+        if let Some(session_id) = file_metadata.get("session_id") {
+            meta.session_id = session_id.clone();
+        }
+
+        fn column<'a, T: 'static>(batch: &'a RecordBatch, name: &'static str) -> Result<&'a T, ParquetError> {
+            batch.column_by_name(name)
+                .and_then(|c| c.as_any().downcast_ref::<T>())
+                .ok_or(ParquetError::InvalidColumn(name))
+        }
+
+        for batch in builder.build()? {
+            let batch = batch?;
+            let timestamps = column::<TimestampMicrosecondArray>(&batch, "timestamp")?;
+            let agents = column::<StringArray>(&batch, "agent")?;
+            let inputs = column::<StringArray>(&batch, "input")?;
+            let outputs = column::<StringArray>(&batch, "output")?;
+            let languages = column::<StringArray>(&batch, "language")?;
+            let confidences = column::<Float64Array>(&batch, "confidence")?;
+            let metadata = column::<StringArray>(&batch, "metadata")?;
+            let tags = column::<ListArray>(&batch, "tags")?;
+            let costs = column::<Float64Array>(&batch, "estimated_cost_usd")?;
+
+            for row in 0..batch.num_rows() {
+                let agent = AgentType::all()
+                    .find(|a| a.to_string() == agents.value(row))
+                    .ok_or_else(|| ParquetError::UnknownAgent(agents.value(row).to_string()))?;
+                let micros = timestamps.value(row);
+                let row_tags = tags.value(row);
+                let row_tags = row_tags.as_any()
+                    .downcast_ref::<StringArray>()
+                    .ok_or(ParquetError::InvalidColumn("tags"))?;
+
+                meta.trace.push(AgentEvent {
+                    timestamp: DateTime::from_timestamp_micros(micros).ok_or(ParquetError::InvalidTimestamp(micros))?,
+                    agent,
+                    input: inputs.value(row).to_string(),
+                    output: outputs.value(row).to_string(),
+                    language: languages.value(row).to_string(),
+                    confidence: confidences.value(row),
+                    metadata: serde_json::from_str(metadata.value(row))?,
+                    tags: row_tags.iter().flatten().map(str::to_string).collect(),
+                    estimated_cost_usd: costs.value(row),
+                    interned_metadata: InternedMetadata::default(),
+                });
+            }
+        }
+
+        meta.rebuild_transitions();
+        Ok(meta)
+    }
+}
+
+#[cfg(feature = "redis")]
+impl MetaAgent {
+    /// Append an event to a Redis stream with `XADD`, returning the entry id.
+    ///
+    /// Fields are `timestamp` (RFC 3339), `agent`, `input`, `output`, `language`,
+    /// `confidence`, `metadata` / `tags` as JSON, and `estimated_cost_usd`. Metadata
+    /// includes interned values.
+    pub fn push_event_to_redis_stream<C: redis::ConnectionLike>(
+        &self,
+        event: &AgentEvent,
+        client: &mut C,
+        stream_key: &str,
+    ) -> Result<String, RedisError> {
+        let metadata = event.full_metadata();
+
+        let fields = [
+            ("timestamp", event.timestamp.to_rfc3339()),
+            ("agent", event.agent.to_string()),
+            ("input", event.input.clone()),
+            ("output", event.output.clone()),
+            ("language", event.language.clone()),
+            ("confidence", event.confidence.to_string()),
+            ("metadata", serde_json::to_string(&metadata)?),
+            ("tags", serde_json::to_string(&event.tags)?),
+            ("estimated_cost_usd", event.estimated_cost_usd.to_string()),
+        ];
+        Ok(redis::Commands::xadd(client, stream_key, "*", &fields)?)
+    }
+
+    /// Rebuild a MetaAgent from every entry of a Redis stream written by
+    /// `push_event_to_redis_stream`; transitions are re-derived from agent changes.
+    pub fn read_trace_from_redis_stream<C: redis::ConnectionLike>(
+        client: &mut C,
+        stream_key: &str,
+        contributor_id: &str,
+        backend_used: &str,
+    ) -> Result<MetaAgent, RedisError> {
+        let reply: redis::streams::StreamRangeReply = redis::Commands::xrange_all(client, stream_key)?;
+        let mut meta = MetaAgent::new(contributor_id, backend_used);
+
+        for entry in reply.ids {
+            let field = |name: &'static str| {
+                entry.get::<String>(name).ok_or_else(|| RedisError::MissingField(entry.id.clone(), name))
+            };
+            let agent_name = field("agent")?;
+            let confidence = field("confidence")?;
+            let cost = field("estimated_cost_usd")?;
+
+            meta.trace.push(AgentEvent {
+                timestamp: DateTime::parse_from_rfc3339(&field("timestamp")?)?.with_timezone(&Utc),
+                agent: AgentType::all()
+                    .find(|a| a.to_string() == agent_name)
+                    .ok_or(RedisError::UnknownAgent(agent_name))?,
+                input: field("input")?,
+                output: field("output")?,
+                language: field("language")?,
+                confidence: confidence.parse().map_err(|_| RedisError::InvalidConfidence(confidence))?,
+                metadata: serde_json::from_str(&field("metadata")?)?,
+                tags: serde_json::from_str(&field("tags")?)?,
+                estimated_cost_usd: cost.parse().map_err(|_| RedisError::InvalidCost(cost))?,
+                interned_metadata: InternedMetadata::default(),
+            });
+        }
+
+        meta.rebuild_transitions();
+        Ok(meta)
+    }
+}
+
+impl TransitionMatrix {
+    /// Number of transitions from `from` to `to`
+    pub fn get(&self, from: &AgentType, to: &AgentType) -> usize {
+        let from = self.agents.iter().position(|a| a == from);
+        let to = self.agents.iter().position(|a| a == to);
+        match (from, to) {
+            (Some(from), Some(to)) => self.counts[from][to],
+            _ => 0,
+        }
+    }
+}
+
+impl TraceReport {
+    /// Serialize the report as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Serialize the report as YAML
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
+/// MetaAgent wrapper that drops events below a confidence threshold
+pub struct ConditionalMetaAgent {
+    inner: MetaAgent,
+    pub min_confidence: f64,
+}
+
+impl ConditionalMetaAgent {
+    /// Log event if it meets the confidence threshold; returns whether it was logged
+    pub fn log_event(&mut self, agent: AgentType, input: &str, output: &str, language: &str, confidence: f64) -> bool {
+        let min_confidence = self.min_confidence;
+        self.inner.log_event_conditional(agent, input, output, language, confidence, |c, _| c >= min_confidence)
+    }
+
+    /// Unwrap the underlying MetaAgent
+    pub fn into_inner(self) -> MetaAgent {
+        self.inner
+    }
+}
+
+impl std::ops::Deref for ConditionalMetaAgent {
+    type Target = MetaAgent;
+
+    fn deref(&self) -> &MetaAgent {
+        &self.inner
+    }
+}
+
+impl MemoryFold {
+    /// Combine folds from parallel sub-tasks into a single chronological fold.
+    ///
+    /// Language counts and costs are summed and key insights are unioned
+    /// (deduplicated); all other metrics are recomputed over the merged trace.
+    pub fn merge(folds: &[MemoryFold]) -> MemoryFold {
+        let session_id = match folds.first() {
+            Some(first) => format!("{}_merged", first.session_id),
+            None => "merged".to_string(),
+        };
+
+        let mut events: Vec<AgentEvent> = folds.iter()
+            .flat_map(|f| f.folded_trace.iter().cloned())
+            .collect();
+        events.sort_by_key(|e| e.timestamp);
+
+        let mut merged = MemoryFold {
+            session_id,
+            folded_trace: Vec::new(),
+            summary: String::new(),
+            compression_ratio: 1.0,
+            key_insights: Vec::new(),
+            language_distribution: HashMap::new(),
+            language_sequence: Vec::new(),
+            language_transition_count: 0,
+            rle_agent_sequence: Vec::new(),
+            language_entropy: 0.0,
+            session_score: 0.0,
+            transition_entropy: 0.0,
+            total_estimated_tokens: 0,
+            max_consecutive_same_agent: 0,
+            top_output_words: Vec::new(),
+            session_metadata: HashMap::new(),
+            translation_pairs: Vec::new(),
+            language_agent_affinity: HashMap::new(),
+            // Declared domains are not stored on the fold, so the best session stands in
+            domain_coverage_ratio: folds.iter().map(|f| f.domain_coverage_ratio).fold(0.0, f64::max),
+            output_diversity: 0.0,
+            semantic_coherence_proxy: 0.0,
+            total_cost_usd: 0.0,
+            inferred_task: Task::Unknown,
+            detected_loops: 0,
+            back_reference_count: 0,
+            transition_narrative: String::new(),
+            aggregates: FoldAggregates::default(),
+        };
+        merged.apply_delta(&events);
+
+        // Earlier folds win when sessions disagree on a key
+        for fold in folds {
+            for (key, value) in &fold.session_metadata {
+                merged.session_metadata.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+
+        let mut language_distribution: HashMap<String, usize> = HashMap::new();
+        for fold in folds {
+            for (lang, count) in &fold.language_distribution {
+                *language_distribution.entry(lang.clone()).or_insert(0) += count;
+            }
+        }
+        merged.language_entropy = shannon_entropy(language_distribution.values().copied());
+        merged.language_distribution = language_distribution;
+
+        let mut key_insights: Vec<String> = Vec::new();
+        for insight in folds.iter().flat_map(|f| &f.key_insights) {
+            if !key_insights.contains(insight) {
+                key_insights.push(insight.clone());
+            }
+        }
+        merged.key_insights = key_insights;
+
+        merged
+    }
+
+    /// Extend the fold with events logged after it was created.
+    ///
+    /// Only the new events are visited: counts, sums, and the last event's state
+    /// are kept as running totals, and every metric is refreshed from them. The
+    /// summary, narrative, and loop count are rebuilt from the RLE sequence, so
+    /// they cost one pass over agent runs rather than events. A deserialized fold
+    /// has no running totals and rebuilds them from `folded_trace` on first use.
+    /// Transitions are inferred from agent changes, so manually tracked
+    /// transitions are not reflected. Domain coverage depends on the contributor
+    /// profile and is left unchanged. New events add their `estimated_cost_usd`
+    /// to `total_cost_usd`.
+    pub fn apply_delta(&mut self, new_events: &[AgentEvent]) {
+        if self.aggregates.events != self.folded_trace.len() {
+            // Deserialized folds carry no running totals; rebuild them once
+            self.aggregates = FoldAggregates::from_events(&self.folded_trace);
+        }
+
+        let mut previous_language = self.folded_trace.last().map(|e| e.language.as_str());
+        for event in new_events {
+            *self.language_distribution.entry(event.language.clone()).or_insert(0) += 1;
+            if !self.language_sequence.contains(&event.language) {
+                self.language_sequence.push(event.language.clone());
+            }
+            if previous_language.is_some_and(|language| language != event.language) {
+                self.language_transition_count += 1;
+            }
+            previous_language = Some(&event.language);
+        }
+        let first_touched_run = self.rle_agent_sequence.len().saturating_sub(1);
+        extend_rle(&mut self.rle_agent_sequence, new_events);
+        self.total_estimated_tokens += new_events.iter().map(|e| e.estimate_tokens()).sum::<usize>();
+        self.total_cost_usd += new_events.iter().map(|e| e.estimated_cost_usd).sum::<f64>();
+        self.max_consecutive_same_agent = self.rle_agent_sequence[first_touched_run..].iter()
+            .map(|(_, count)| *count)
+            .fold(self.max_consecutive_same_agent, usize::max);
+        self.translation_pairs.extend(translation_pairs(new_events));
+        self.aggregates.extend(new_events);
+        let first_new = self.folded_trace.len();
+        self.folded_trace.extend_from_slice(new_events);
+        self.back_reference_count += (first_new..self.folded_trace.len())
+            .filter(|&later| !quoted_events(&self.folded_trace, later).is_empty())
+            .count();
+
+        let aggregates = &self.aggregates;
+        let transition_count = self.rle_agent_sequence.len().saturating_sub(1);
+        self.language_entropy = shannon_entropy(self.language_distribution.values().copied());
+        self.transition_entropy = shannon_entropy(aggregates.transition_counts.values().copied());
+        self.session_score = score_session_totals(
+            aggregates.events,
+            aggregates.confidence_sum,
+            aggregates.agent_counts.values().copied(),
+            self.language_distribution.values().copied(),
+            (aggregates.transition_score_sum, aggregates.transition_score_count),
+        ).overall;
+        self.summary = format_summary(&self.rle_agent_sequence, self.language_distribution.len(), transition_count);
+        let agent_path: Vec<AgentType> = self.rle_agent_sequence.iter().map(|(agent, _)| agent.clone()).collect();
+        self.detected_loops = reasoning_loops(&agent_path, DEFAULT_LOOP_CYCLE_LENGTH, DEFAULT_LOOP_REPETITIONS).len();
+        self.key_insights = build_key_insights(&InsightSignals {
+            high_confidence_count: aggregates.high_confidence_count,
+            language_count: self.language_distribution.len(),
+            transition_count,
+            transition_entropy: self.transition_entropy,
+            max_consecutive_same_agent: self.max_consecutive_same_agent,
+            detected_loops: self.detected_loops,
+        });
+
+        self.language_agent_affinity = affinity_by_name(affinity_fractions(&aggregates.language_agent_counts));
+        self.output_diversity = aggregates.output_diversity.value();
+        self.semantic_coherence_proxy = match aggregates.events {
+            0 | 1 => 0.0,
+            n => aggregates.coherence_sum / (n - 1) as f64,
+        };
+        self.inferred_task = infer_task_from_counts(&aggregates.agent_counts, aggregates.events);
+        self.compression_ratio = if aggregates.total_chars > 0 {
+            self.summary.len() as f64 / aggregates.total_chars as f64
+        } else {
+            1.0
+        };
+        self.top_output_words = ranked_words(&aggregates.output_word_counts, 5).into_iter()
+            .map(|(word, _)| word)
+            .collect();
+        self.transition_narrative = self.summarize_transitions();
+    }
+
+    /// Compact context block for re-injecting the fold into an LLM prompt.
+    ///
+    /// Truncated to `max_tokens * 0.75` words (at least one word) with a trailing
+    /// ellipsis when cut.
+    pub fn to_prompt_string(&self, max_tokens: usize) -> String {
+        let mut languages: Vec<(&String, &usize)> = self.language_distribution.iter().collect();
+        languages.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let dominant_language = languages.first().map_or("none", |(lang, _)| lang.as_str());
+
+        let agent_mix = self.rle_agent_sequence.iter()
+            .map(|(agent, count)| format!("{}×{}", agent, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let prompt = format!(
+            "[Session {}] {} Key insights: {}. Dominant language: {}. Agent mix: {}.",
+            self.session_id,
+            self.summary,
+            self.key_insights.join("; "),
+            dominant_language,
+            agent_mix
+        );
+
+        let max_words = ((max_tokens as f64 * 0.75) as usize).max(1);
+        let words: Vec<&str> = prompt.split_whitespace().collect();
+        if words.len() <= max_words {
+            return prompt;
+        }
+        format!("{}…", words[..max_words].join(" "))
+    }
+
+    /// Most frequent non-stopword terms across event outputs, most frequent first
+    pub fn word_frequency_analysis(&self, top_n: usize) -> Vec<(String, usize)> {
+        ranked_words(&self.output_word_counts(), top_n)
+    }
+
+    /// Number of distinct non-stopword terms across event outputs
+    pub fn unique_word_count(&self) -> usize {
+        self.output_word_counts().len()
+    }
+
+    /// Describe the agent flow in one sentence, e.g. "The session began with
+    /// Classification, transitioned 3 times through Reasoning, and concluded
+    /// with Synthesis after a Validation pass."
+    pub fn summarize_transitions(&self) -> String {
+        let rle = &self.rle_agent_sequence;
+        let transitions = rle.len().saturating_sub(1);
+        match rle.len() {
+            0 => "The session recorded no events.".to_string(),
+            1 => format!("The session consisted solely of {} ({} events).", rle[0].0, rle[0].1),
+            2 => format!("The session began with {} and concluded with {} after 1 transition.", rle[0].0, rle[1].0),
+            n => {
+                let mut middle: Vec<String> = Vec::new();
+                for (agent, _) in &rle[1..n - 2] {
+                    let name = agent.to_string();
+                    if !middle.contains(&name) {
+                        middle.push(name);
+                    }
+                }
+                let through = match middle.len() {
+                    0 => String::new(),
+                    _ => format!(" through {}", join_with_and(&middle)),
+                };
+                format!(
+                    "The session began with {}, transitioned {} times{}, and concluded with {} after a {} pass.",
+                    rle[0].0, transitions, through, rle[n - 1].0, rle[n - 2].0
+                )
+            }
+        }
+    }
+
+    /// Terms stored in `top_output_words`
+    fn top_output_word_list(&self) -> Vec<String> {
+        self.word_frequency_analysis(5).into_iter().map(|(word, _)| word).collect()
+    }
+
+    /// Lowercased output terms with surrounding punctuation and stopwords removed
+    fn output_word_counts(&self) -> HashMap<String, usize> {
+        if self.aggregates.events == self.folded_trace.len() {
+            return self.aggregates.output_word_counts.clone();
+        }
+        let mut counts = HashMap::new();
+        for word in self.folded_trace.iter().flat_map(|e| normalized_words(&e.output)) {
+            if !STOPWORDS.contains(&word.as_str()) {
+                *counts.entry(word).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+}
+
+/// Shortest cycle counted towards `MemoryFold::detected_loops`
+const DEFAULT_LOOP_CYCLE_LENGTH: usize = 2;
+
+/// Back-to-back repetitions needed for `MemoryFold::detected_loops`
+const DEFAULT_LOOP_REPETITIONS: usize = 3;
+
+/// Words per window in `MetaAgent::compute_output_diversity`
+const OUTPUT_DIVERSITY_WINDOW: usize = 50;
+
+/// Hex characters of the trace hash kept by `MetaAgent::get_session_fingerprint`
+const SESSION_FINGERPRINT_LEN: usize = 12;
+
+/// Contributor id prefix marking anonymized provenance logs
+const ANONYMIZED_PREFIX: &str = "anon_";
+
+/// Metadata key recording the language a Translation event translated from
+const SOURCE_LANGUAGE_KEY: &str = "source_language";
+
+/// Metadata key naming the expertise domain an event belongs to
+const DOMAIN_METADATA_KEY: &str = "domain";
+
+/// Number of output terms listed in `TraceReport::top_words`
+const TRACE_REPORT_TOP_WORDS: usize = 10;
+
+/// Shortest verbatim overlap counted as a back-reference
+const MIN_BACK_REFERENCE_CHARS: usize = 10;
+
+/// Credential type identifying provenance logs in verifiable credentials
+const VC_TRACE_TYPE: &str = "QuantumLimitGraphTrace";
+
+/// Needleman-Wunsch score for aligned identical agent types
+const ALIGNMENT_MATCH: f64 = 1.0;
+
+/// Needleman-Wunsch score for aligned differing agent types
+const ALIGNMENT_MISMATCH: f64 = -1.0;
+
+/// Needleman-Wunsch score for aligning an agent type with a gap
+const ALIGNMENT_GAP: f64 = -0.5;
+
+/// Namespace prefix for identifiers in W3C PROV exports
+const PROV_PREFIX: &str = "qlg";
+
+/// Namespace IRI bound to `PROV_PREFIX`
+const PROV_NAMESPACE: &str = "urn:quantum-limit-graph:";
+
+/// Common English words ignored by output word frequency analysis
+const STOPWORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as",
+    "at", "be", "been", "but", "by", "can", "do", "for", "from", "had",
+    "has", "have", "he", "her", "his", "how", "i", "if", "in", "into",
+    "is", "it", "its", "more", "no", "not", "of", "on", "or", "our",
+    "she", "so", "than", "that", "the", "their", "them", "then", "there", "these",
+    "they", "this", "to", "was", "we", "were", "what", "when", "which", "who",
+    "will", "with", "would", "you", "your",
+];
+
+/// Append events to a run-length encoded agent sequence
+fn extend_rle(runs: &mut Vec<(AgentType, usize)>, events: &[AgentEvent]) {
+    for event in events {
+        match runs.last_mut() {
+            Some((agent, count)) if *agent == event.agent => *count += 1,
+            _ => runs.push((event.agent.clone(), 1)),
+        }
+    }
+}
+
+/// Compact session summary built from the RLE agent flow
+fn format_summary(rle: &[(AgentType, usize)], language_count: usize, transition_count: usize) -> String {
+    let agent_flow = rle.iter()
+        .map(|(agent, count)| format!("{}×{}", agent, count))
+        .collect::<Vec<_>>()
+        .join(" → ");
+
+    format!(
+        "{} across {} languages, {} transitions",
+        agent_flow,
+        language_count,
+        transition_count
+    )
+}
+
+/// "A", "A and B", "A, B and C"
+fn join_with_and(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+/// Trace aggregates that key insights are derived from
+struct InsightSignals {
+    high_confidence_count: usize,
+    language_count: usize,
+    transition_count: usize,
+    transition_entropy: f64,
+    max_consecutive_same_agent: usize,
+    detected_loops: usize,
+}
+
+/// Key insights for a trace given its precomputed aggregates
+fn build_key_insights(signals: &InsightSignals) -> Vec<String> {
+    let mut insights = Vec::new();
+
+    // High confidence outputs
+    if signals.high_confidence_count > 0 {
+        insights.push(format!("{} high-confidence reasoning steps", signals.high_confidence_count));
+    }
+
+    // Multilingual reasoning
+    if signals.language_count > 1 {
+        insights.push(format!("Multilingual reasoning across {} languages", signals.language_count));
+    }
+
+    // Complex agent transitions
+    if signals.transition_count > 5 {
+        insights.push(format!("Complex reasoning with {} agent transitions", signals.transition_count));
+    }
+
+    // Unpredictable transition paths
+    if signals.transition_entropy > 2.0 {
+        insights.push(format!("Highly exploratory reasoning (transition entropy {:.2})", signals.transition_entropy));
+    }
+
+    // Long single-agent runs suggest a loop or stall
+    if signals.max_consecutive_same_agent > 5 {
+        insights.push(format!(
+            "Possible reasoning loop: {} consecutive events from one agent",
+            signals.max_consecutive_same_agent
+        ));
+    }
+
+    if signals.detected_loops > 0 {
+        insights.push(format!("Warning: {} reasoning loops detected", signals.detected_loops));
+    }
+
+    insights
+}
+
+/// Repeated cycles along an agent path; see `MetaAgent::detect_reasoning_loops`.
+/// Transition `k` moves from `path[k]` to `path[k + 1]`.
+fn reasoning_loops(path: &[AgentType], min_cycle_length: usize, min_repetitions: usize) -> Vec<ReasoningLoop> {
+    let min_cycle_length = min_cycle_length.max(1);
+    let min_repetitions = min_repetitions.max(2);
+    let mut covered = vec![false; path.len()];
+    let mut loops = Vec::new();
+
+    for length in min_cycle_length..=path.len() / min_repetitions {
+        let mut i = 0;
+        while i + length * min_repetitions <= path.len() {
+            let cycle = &path[i..i + length];
+            let is_primitive = (1..length).all(|d| length % d != 0 || cycle[d..] != cycle[..length - d]);
+            let mut repetitions = 1;
+            while path[i + repetitions * length..].starts_with(cycle) {
+                repetitions += 1;
+            }
+
+            let span = repetitions * length;
+            if is_primitive && repetitions >= min_repetitions && !covered[i..i + span].contains(&true) {
+                covered[i..i + span].iter_mut().for_each(|c| *c = true);
+                loops.push(ReasoningLoop {
+                    cycle: cycle.to_vec(),
+                    repetitions,
+                    start_index: i,
+                    end_index: i + span - 2,
+                });
+                i += span;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    loops.sort_by_key(|l| l.start_index);
+    loops
+}
+
+/// Mean confidence of up to three events preceding index `i`
+fn preceding_confidence(events: &[AgentEvent], i: usize) -> f64 {
+    let window = &events[i.saturating_sub(3)..i];
+    window.iter().map(|e| e.confidence).sum::<f64>() / window.len() as f64
+}
+
+/// Length of `metadata` serialized as compact JSON, or 0 when it is empty
+fn serialized_metadata_bytes(metadata: &HashMap<String, String>) -> usize {
+    if metadata.is_empty() {
+        return 0;
+    }
+    serde_json::to_vec(metadata).map_or(0, |json| json.len())
+}
+
+/// Back-reference pairs for a trace; see `MetaAgent::get_back_reference_events`
+fn back_reference_pairs(events: &[AgentEvent]) -> Vec<(usize, usize)> {
+    (0..events.len())
+        .flat_map(|later| quoted_events(events, later).into_iter().map(move |earlier| (later, earlier)))
+        .collect()
+}
+
+/// Indices of events before `later` whose output the input of `events[later]` quotes
+fn quoted_events(events: &[AgentEvent], later: usize) -> Vec<usize> {
+    let input = &events[later].input;
+    let boundaries: Vec<usize> = input.char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(input.len()))
+        .collect();
+    let windows: Vec<&str> = boundaries.windows(MIN_BACK_REFERENCE_CHARS + 1)
+        .map(|w| &input[w[0]..w[MIN_BACK_REFERENCE_CHARS]])
+        .collect();
+    if windows.is_empty() {
+        return Vec::new();
+    }
+
+    events[..later].iter()
+        .enumerate()
+        .filter(|(_, previous)| windows.iter().any(|window| previous.output.contains(window)))
+        .map(|(earlier, _)| earlier)
+        .collect()
+}
+
+/// Translation pairs for events with uncompressed metadata; see `MetaAgent::get_translation_pairs`
+fn translation_pairs(events: &[AgentEvent]) -> Vec<(String, String)> {
+    events.iter()
+        .filter(|e| e.agent == AgentType::Translation)
+        .filter_map(|e| Some((e.metadata_value(SOURCE_LANGUAGE_KEY)?.to_string(), e.language.clone())))
+        .collect()
+}
+
+/// Normalized HHI of per-agent-type event counts; see `ContributorProfile::compute_specialization_index`
+fn specialization_index(counts: impl Iterator<Item = usize>) -> f64 {
+    let counts: Vec<usize> = counts.collect();
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    let hhi: f64 = counts.iter().map(|&c| (c as f64 / total as f64).powi(2)).sum();
+    let floor = 1.0 / AgentType::count() as f64;
+    ((hhi - floor) / (1.0 - floor)).clamp(0.0, 1.0)
+}
+
+/// Lowercased words of `text` with surrounding punctuation removed
+fn normalized_words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split_whitespace()
+        .map(|token| token.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|word| !word.is_empty())
+}
+
+/// Moving-average type-token ratio across `texts`; see `MetaAgent::compute_output_diversity`
+fn moving_type_token_ratio<'a>(texts: impl Iterator<Item = &'a str>) -> f64 {
+    let mut ratio = MovingTypeTokenRatio::default();
+    for word in texts.flat_map(normalized_words) {
+        ratio.push(word);
+    }
+    ratio.value()
+}
+
+/// Running MATTR state: the last `OUTPUT_DIVERSITY_WINDOW` words and the sum of
+/// the type-token ratios of every full window seen so far
+#[derive(Debug, Clone, Default)]
+struct MovingTypeTokenRatio {
+    window: std::collections::VecDeque<String>,
+    window_counts: HashMap<String, usize>,
+    window_ratio_sum: f64,
+    full_windows: usize,
+}
+
+impl MovingTypeTokenRatio {
+    fn push(&mut self, word: String) {
+        *self.window_counts.entry(word.clone()).or_insert(0) += 1;
+        self.window.push_back(word);
+        if self.window.len() > OUTPUT_DIVERSITY_WINDOW {
+            if let Some(oldest) = self.window.pop_front() {
+                if let Some(count) = self.window_counts.get_mut(&oldest) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.window_counts.remove(&oldest);
+                    }
+                }
+            }
+        }
+        if self.window.len() == OUTPUT_DIVERSITY_WINDOW {
+            self.window_ratio_sum += self.window_counts.len() as f64 / OUTPUT_DIVERSITY_WINDOW as f64;
+            self.full_windows += 1;
+        }
+    }
+
+    /// Mean ratio over full windows; before the first one fills, the missing
+    /// words count as repeats
+    fn value(&self) -> f64 {
+        match self.full_windows {
+            0 => self.window_counts.len() as f64 / OUTPUT_DIVERSITY_WINDOW as f64,
+            windows => self.window_ratio_sum / windows as f64,
+        }
+    }
+}
+
+/// Mean adjacent-output Jaccard similarity; see `MetaAgent::compute_semantic_coherence_proxy`
+fn output_coherence(events: &[AgentEvent]) -> f64 {
+    if events.len() < 2 {
+        return 0.0;
+    }
+    let word_sets: Vec<std::collections::HashSet<String>> = events.iter()
+        .map(|e| normalized_words(&e.output).collect())
+        .collect();
+    let total: f64 = word_sets.windows(2)
+        .map(|pair| jaccard_similarity(&pair[0], &pair[1]))
+        .sum();
+    total / (events.len() - 1) as f64
+}
+
+/// Shared words over all words of two word sets (0.0 when both are empty)
+fn jaccard_similarity(a: &std::collections::HashSet<String>, b: &std::collections::HashSet<String>) -> f64 {
+    match a.union(b).count() {
+        0 => 0.0,
+        n => a.intersection(b).count() as f64 / n as f64,
+    }
+}
+
+/// The `top_n` most frequent words, ties broken alphabetically
+fn ranked_words(counts: &HashMap<String, usize>, top_n: usize) -> Vec<(String, usize)> {
+    let mut ranked: Vec<(String, usize)> = counts.iter().map(|(word, count)| (word.clone(), *count)).collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(top_n);
+    ranked
+}
+
+/// Primary task for a sequence of events; see `MetaAgent::infer_primary_task`
+fn infer_task(events: &[AgentEvent]) -> Task {
+    let mut counts: HashMap<AgentType, usize> = HashMap::new();
+    for event in events {
+        *counts.entry(event.agent.clone()).or_insert(0) += 1;
+    }
+    infer_task_from_counts(&counts, events.len())
+}
+
+/// Primary task given per-agent event counts over `total` events
+fn infer_task_from_counts(counts: &HashMap<AgentType, usize>, total: usize) -> Task {
+    if total == 0 {
+        return Task::Unknown;
+    }
+    let share = |agent: &AgentType| counts.get(agent).copied().unwrap_or(0) as f64 / total as f64;
+
+    if share(&AgentType::Translation) > 0.4 {
+        Task::Translation
+    } else if share(&AgentType::Classification) > 0.4 {
+        Task::Classification
+    } else if share(&AgentType::Synthesis) > 0.3 && share(&AgentType::Retrieval) > 0.2 {
+        Task::Research
+    } else if share(&AgentType::Validation) > 0.4 {
+        Task::Validation
+    } else {
+        let max = counts.values().copied().max().unwrap_or(0);
+        let min = counts.values().copied().min().unwrap_or(0);
+        if counts.len() >= 4 && max <= 2 * min {
+            Task::MultiStep
+        } else {
+            Task::Unknown
+        }
+    }
+}
+
+/// Agent type fractions per language; see `MetaAgent::compute_per_language_agent_affinity`
+fn language_agent_affinity(events: &[AgentEvent]) -> HashMap<String, HashMap<AgentType, f64>> {
+    let mut counts: HashMap<String, HashMap<AgentType, usize>> = HashMap::new();
+    for event in events {
+        *counts.entry(event.language.clone())
+            .or_default()
+            .entry(event.agent.clone())
+            .or_insert(0) += 1;
+    }
+    affinity_fractions(&counts)
+}
+
+/// Per-language agent counts turned into fractions of that language's events
+fn affinity_fractions(counts: &HashMap<String, HashMap<AgentType, usize>>) -> HashMap<String, HashMap<AgentType, f64>> {
+    counts.iter()
+        .map(|(language, agents)| {
+            let total = agents.values().sum::<usize>() as f64;
+            let fractions = agents.iter()
+                .map(|(agent, count)| (agent.clone(), *count as f64 / total))
+                .collect();
+            (language.clone(), fractions)
+        })
+        .collect()
+}
+
+/// Affinity map with agent types replaced by their display names
+fn affinity_by_name(affinity: HashMap<String, HashMap<AgentType, f64>>) -> HashMap<String, HashMap<String, f64>> {
+    affinity.into_iter()
+        .map(|(language, agents)| {
+            (language, agents.into_iter().map(|(agent, fraction)| (agent.to_string(), fraction)).collect())
+        })
+        .collect()
+}
+
+/// Optimal global alignment score and gapped sequences; see `MetaAgent::align_against_reference`
+fn needleman_wunsch(a: &[AgentType], b: &[AgentType]) -> (f64, Vec<Option<AgentType>>, Vec<Option<AgentType>>) {
+    let (n, m) = (a.len(), b.len());
+    let mut scores = vec![vec![0.0; m + 1]; n + 1];
+    for (i, row) in scores.iter_mut().enumerate() {
+        row[0] = i as f64 * ALIGNMENT_GAP;
+    }
+    for (j, cell) in scores[0].iter_mut().enumerate() {
+        *cell = j as f64 * ALIGNMENT_GAP;
+    }
+    let pair_score = |i: usize, j: usize| if a[i - 1] == b[j - 1] { ALIGNMENT_MATCH } else { ALIGNMENT_MISMATCH };
+    for i in 1..=n {
+        for j in 1..=m {
+            scores[i][j] = (scores[i - 1][j - 1] + pair_score(i, j))
+                .max(scores[i - 1][j] + ALIGNMENT_GAP)
+                .max(scores[i][j - 1] + ALIGNMENT_GAP);
+        }
+    }
+
+    let (mut aligned_a, mut aligned_b) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && scores[i][j] == scores[i - 1][j - 1] + pair_score(i, j) {
+            aligned_a.push(Some(a[i - 1].clone()));
+            aligned_b.push(Some(b[j - 1].clone()));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && (j == 0 || scores[i][j] == scores[i - 1][j] + ALIGNMENT_GAP) {
+            aligned_a.push(Some(a[i - 1].clone()));
+            aligned_b.push(None);
+            i -= 1;
+        } else {
+            aligned_a.push(None);
+            aligned_b.push(Some(b[j - 1].clone()));
+            j -= 1;
+        }
+    }
+    aligned_a.reverse();
+    aligned_b.reverse();
+    (scores[n][m], aligned_a, aligned_b)
+}
+
+/// Minimum insertions, deletions, and substitutions turning `a` into `b`
+fn levenshtein(a: &[AgentType], b: &[AgentType]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, y) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(x != y);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// PROV-N local name: characters other than ASCII alphanumerics, `_`, and `-` become `_`
+fn prov_local_name(value: &str) -> String {
+    value.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// Escape a value for a PROV-N string literal
+fn prov_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Feed a length-prefixed field to `hasher` so adjacent fields cannot run together
+fn hash_field(hasher: &mut Sha256, value: &str) {
+    hasher.update((value.len() as u64).to_le_bytes());
+    hasher.update(value.as_bytes());
+}
+
+/// Distinct event languages in order of first appearance
+fn distinct_language_sequence(events: &[AgentEvent]) -> Vec<String> {
+    let mut sequence: Vec<String> = Vec::new();
+    for event in events {
+        if !sequence.contains(&event.language) {
+            sequence.push(event.language.clone());
+        }
+    }
+    sequence
+}
+
+/// Number of consecutive event pairs whose languages differ
+fn language_change_count(events: &[AgentEvent]) -> usize {
+    events.windows(2).filter(|pair| pair[0].language != pair[1].language).count()
+}
+
+/// Approximate LLM token count of a text; see `tokens_for_words`
+fn estimate_text_tokens(text: &str) -> usize {
+    tokens_for_words(text.split_whitespace().count())
+}
+
+/// Approximate LLM token count of `words` words (words / 0.75, rounded up)
+fn tokens_for_words(words: usize) -> usize {
+    (words as f64 / 0.75).ceil() as usize
+}
+
+/// Distinct later indices among back-reference pairs (pairs are grouped by later index)
+fn count_later_events(pairs: &[(usize, usize)]) -> usize {
+    let mut count = 0;
+    let mut last = None;
+    for &(later, _) in pairs {
+        if last != Some(later) {
+            count += 1;
+            last = Some(later);
+        }
+    }
+    count
+}
+
+/// Mean of reasoning and language entropy, normalized by log2(8) into [0.0, 1.0]
+fn normalized_diversity(reasoning_entropy: f64, language_entropy: f64) -> f64 {
+    ((reasoning_entropy + language_entropy) / 2.0 / 8f64.log2()).min(1.0)
+}
+
+/// Session quality score for a trace and the scores of its transitions
+fn score_session(events: &[AgentEvent], transition_scores: &[f64]) -> SessionScore {
+    let mut agent_counts: HashMap<&AgentType, usize> = HashMap::new();
+    let mut language_counts: HashMap<&str, usize> = HashMap::new();
+    for event in events {
+        *agent_counts.entry(&event.agent).or_insert(0) += 1;
+        *language_counts.entry(event.language.as_str()).or_insert(0) += 1;
+    }
+    score_session_totals(
+        events.len(),
+        events.iter().map(|e| e.confidence).sum(),
+        agent_counts.into_values(),
+        language_counts.into_values(),
+        (transition_scores.iter().sum(), transition_scores.len()),
+    )
+}
+
+/// Session quality score from a trace's totals: event count, confidence sum,
+/// per-agent and per-language counts, and the sum and count of transition scores
+fn score_session_totals(
+    event_count: usize,
+    confidence_sum: f64,
+    agent_counts: impl Iterator<Item = usize>,
+    language_counts: impl Iterator<Item = usize>,
+    (transition_score_sum, transition_count): (f64, usize),
+) -> SessionScore {
+    let confidence_score = if event_count == 0 {
+        0.0
+    } else {
+        confidence_sum / event_count as f64
+    };
+    let diversity_score = normalized_diversity(shannon_entropy(agent_counts), shannon_entropy(language_counts));
+
+    let depth_score = (event_count as f64 / 50.0).min(1.0);
+    let transition_quality_score = if transition_count == 0 {
+        1.0
+    } else {
+        transition_score_sum / transition_count as f64
+    };
+
+    let overall = 0.3 * confidence_score
+        + 0.2 * diversity_score
+        + 0.25 * depth_score
+        + 0.25 * transition_quality_score;
+
+    SessionScore {
+        confidence_score,
+        diversity_score,
+        depth_score,
+        transition_quality_score,
+        overall,
+    }
+}
+
+/// Shannon entropy (bits) over a set of frequency counts
+fn shannon_entropy(counts: impl Iterator<Item = usize>) -> f64 {
+    let counts: Vec<usize> = counts.filter(|&c| c > 0).collect();
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+
+    counts.iter()
+        .map(|&c| {
+            let p = c as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Cosine similarity of two bags of (agent type, language) pairs (0.0 if either is empty)
+pub(crate) fn pair_cosine_similarity<'a>(
+    a: impl Iterator<Item = (&'a AgentType, &'a str)>,
+    b: impl Iterator<Item = (&'a AgentType, &'a str)>,
+) -> f64 {
+    let mut counts_a: HashMap<(&AgentType, &str), f64> = HashMap::new();
+    for pair in a {
+        *counts_a.entry(pair).or_insert(0.0) += 1.0;
+    }
+    let mut counts_b: HashMap<(&AgentType, &str), f64> = HashMap::new();
+    for pair in b {
+        *counts_b.entry(pair).or_insert(0.0) += 1.0;
+    }
+
+    let norm_a = counts_a.values().map(|c| c * c).sum::<f64>().sqrt();
+    let norm_b = counts_b.values().map(|c| c * c).sum::<f64>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+
+    let dot: f64 = counts_a.iter()
+        .filter_map(|(pair, count)| counts_b.get(pair).map(|other| count * other))
+        .sum();
+    (dot / (norm_a * norm_b)).min(1.0)
+}
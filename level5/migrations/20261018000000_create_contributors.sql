@@ -0,0 +1,26 @@
+-- Contributor stats persisted by Leaderboard::export_to_postgres
+CREATE TABLE IF NOT EXISTS contributors (
+    contributor_id TEXT PRIMARY KEY,
+    total_submissions BIGINT NOT NULL,
+    trace_depth BIGINT NOT NULL,
+    weighted_trace_depth DOUBLE PRECISION NOT NULL,
+    avg_trace_depth DOUBLE PRECISION NOT NULL,
+    uniqueness_score DOUBLE PRECISION NOT NULL,
+    languages_used TEXT[] NOT NULL,
+    backend_used TEXT NOT NULL,
+    provenance_hash TEXT NOT NULL,
+    last_updated TIMESTAMPTZ NOT NULL,
+    combined_score DOUBLE PRECISION NOT NULL,
+    rank BIGINT NOT NULL,
+    normalized_submission_rate DOUBLE PRECISION NOT NULL,
+    reasoning_entropy DOUBLE PRECISION NOT NULL,
+    total_estimated_tokens BIGINT NOT NULL,
+    deleted BOOLEAN NOT NULL,
+    confidence_profile JSONB NOT NULL,
+    improvement_score DOUBLE PRECISION NOT NULL,
+    recent_improvement_rate DOUBLE PRECISION NOT NULL,
+    streak BIGINT NOT NULL,
+    output_diversity_score DOUBLE PRECISION NOT NULL,
+    percentile_rank DOUBLE PRECISION NOT NULL,
+    profile JSONB NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE contributors ADD COLUMN IF NOT EXISTS network_centrality DOUBLE PRECISION NOT NULL DEFAULT 0;
@@ -16,18 +16,73 @@ pub use meta_agent::{
     MetaAgent,
     AgentType,
     AgentEvent,
+    InternedMetadata,
     AgentTransition,
     MemoryFold,
     ProvenanceLog,
     ContributorProfile,
+    OrderingViolation,
+    SessionScore,
+    ConditionalMetaAgent,
+    ProfileValidationError,
+    ChainError,
+    ChainVerificationResult,
+    ContextWindowUsage,
+    EmbeddingConfig,
+    CostModel,
+    EmbeddingProvider,
+    ProvenanceSigner,
+    SignatureError,
+    VcError,
+    SessionState,
+    AgentStats,
+    TransitionMatrix,
+    TraceReport,
+    AgentEventBuilder,
+    BuilderError,
+    MetadataLimitError,
+    LogEventError,
+    SchemaValidationError,
+    DomainCoverageReport,
+    ProfileMergeError,
+    Task,
+    ReasoningLoop,
+    ConfidenceStats,
+    ConfidenceCalibrator,
+    AlignmentResult,
 };
 
 pub use leaderboard::{
     Leaderboard,
     ContributorStats,
     RankingCriteria,
+    BackendStats,
+    LeaderboardSnapshot,
+    Tier,
+    ContributorGraph,
+    SimulationResult,
+    SuspicionFlag,
+    TimeSeriesPoint,
+    ReportCard,
+    LoadError,
+    CohortReport,
 };
 
+#[cfg(feature = "sqlite")]
+pub use leaderboard::SqliteError;
+
+#[cfg(feature = "kafka")]
+pub use leaderboard::{KafkaError, LeaderboardProducer, RdKafkaProducer, RANK_TOPIC_SUFFIX};
+
+#[cfg(feature = "qrcode")]
+pub use meta_agent::QrError;
+
+#[cfg(feature = "parquet")]
+pub use meta_agent::ParquetError;
+
+#[cfg(feature = "redis")]
+pub use meta_agent::RedisError;
+
 pub use sample_integration::{
     demo_meta_agent,
     demo_leaderboard,
@@ -1,7 +1,9 @@
 // tests/test_level5.rs - Level 5 MetaAgent Integration Tests
 
+use std::collections::HashMap;
+
 use quantum_limit_graph::level5::{
-    MetaAgent, AgentType, Leaderboard, RankingCriteria, ContributorProfile,
+    MetaAgent, AgentType, Leaderboard, RankingCriteria, ContributorProfile, ProfileValidationError,
 };
 
 #[test]
@@ -22,6 +24,8 @@ fn test_meta_agent_with_profile() {
         reasoning_style: "analytical".to_string(),
         total_traces: 5,
         avg_trace_depth: 12.5,
+        language_fluency_scores: HashMap::new(),
+        specialization_index: 0.0,
     };
 
     let meta = MetaAgent::with_profile("test_user", "test_backend", profile.clone());
@@ -40,7 +44,7 @@ fn test_event_logging() {
         "test output",
         "en",
         0.95,
-    );
+    ).unwrap();
 
     assert_eq!(meta.get_trace_depth(), 1);
     assert_eq!(meta.trace[0].language, "en");
@@ -51,9 +55,9 @@ fn test_event_logging() {
 fn test_agent_transitions() {
     let mut meta = MetaAgent::new("test_user", "test_backend");
     
-    meta.log_event(AgentType::Classification, "input1", "output1", "en", 0.9);
-    meta.log_event(AgentType::Reasoning, "input2", "output2", "en", 0.92);
-    meta.log_event(AgentType::Retrieval, "input3", "output3", "en", 0.88);
+    meta.log_event(AgentType::Classification, "input1", "output1", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input2", "output2", "en", 0.92).unwrap();
+    meta.log_event(AgentType::Retrieval, "input3", "output3", "en", 0.88).unwrap();
 
     assert_eq!(meta.get_transition_count(), 2); // 2 transitions between 3 different agents
 }
@@ -62,9 +66,9 @@ fn test_agent_transitions() {
 fn test_multilingual_logging() {
     let mut meta = MetaAgent::new("test_user", "test_backend");
     
-    meta.log_event(AgentType::Classification, "English input", "output", "en", 0.9);
-    meta.log_event(AgentType::Translation, "Indonesian input", "output", "id", 0.85);
-    meta.log_event(AgentType::Reasoning, "Chinese input", "output", "zh", 0.88);
+    meta.log_event(AgentType::Classification, "English input", "output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Translation, "Indonesian input", "output", "id", 0.85).unwrap();
+    meta.log_event(AgentType::Reasoning, "Chinese input", "output", "zh", 0.88).unwrap();
 
     let folded = meta.fold_memory();
     assert_eq!(folded.language_distribution.len(), 3);
@@ -84,7 +88,7 @@ fn test_memory_folding() {
             &format!("output {}", i),
             "en",
             0.9,
-        );
+        ).unwrap();
     }
 
     let folded = meta.fold_memory();
@@ -98,8 +102,8 @@ fn test_memory_folding() {
 fn test_provenance_generation() {
     let mut meta = MetaAgent::new("test_user", "test_backend");
     
-    meta.log_event(AgentType::Classification, "input1", "output1", "en", 0.9);
-    meta.log_event(AgentType::Reasoning, "input2", "output2", "en", 0.92);
+    meta.log_event(AgentType::Classification, "input1", "output1", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input2", "output2", "en", 0.92).unwrap();
 
     let provenance = meta.emit_provenance();
     
@@ -117,8 +121,8 @@ fn test_provenance_uniqueness() {
     let mut meta2 = MetaAgent::new("user2", "backend2");
     
     // Same inputs should produce same hash
-    meta1.log_event(AgentType::Reasoning, "test", "result", "en", 0.9);
-    meta2.log_event(AgentType::Reasoning, "test", "result", "en", 0.9);
+    meta1.log_event(AgentType::Reasoning, "test", "result", "en", 0.9).unwrap();
+    meta2.log_event(AgentType::Reasoning, "test", "result", "en", 0.9).unwrap();
     
     let prov1 = meta1.emit_provenance();
     let prov2 = meta2.emit_provenance();
@@ -127,7 +131,7 @@ fn test_provenance_uniqueness() {
     
     // Different inputs should produce different hash
     let mut meta3 = MetaAgent::new("user3", "backend3");
-    meta3.log_event(AgentType::Reasoning, "different", "result", "en", 0.9);
+    meta3.log_event(AgentType::Reasoning, "different", "result", "en", 0.9).unwrap();
     let prov3 = meta3.emit_provenance();
     
     assert_ne!(prov1.trace_hash, prov3.trace_hash);
@@ -138,7 +142,7 @@ fn test_profile_update() {
     let mut meta = MetaAgent::new("test_user", "test_backend");
     
     for i in 0..5 {
-        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9);
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
     }
     
     assert_eq!(meta.profile.total_traces, 0);
@@ -160,7 +164,7 @@ fn test_leaderboard_add_entry() {
     let mut meta = MetaAgent::new("user1", "backend1");
     
     for i in 0..10 {
-        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9);
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
     }
     
     let provenance = meta.emit_provenance();
@@ -178,7 +182,7 @@ fn test_leaderboard_multiple_submissions() {
     for _ in 0..3 {
         let mut meta = MetaAgent::new("user1", "backend1");
         for i in 0..5 {
-            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9);
+            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
         }
         let provenance = meta.emit_provenance();
         leaderboard.add_entry(provenance, vec!["en".to_string()]);
@@ -195,14 +199,14 @@ fn test_leaderboard_ranking_by_depth() {
     // User 1: 5 steps
     let mut meta1 = MetaAgent::new("user1", "backend1");
     for _ in 0..5 {
-        meta1.log_event(AgentType::Reasoning, "input", "output", "en", 0.9);
+        meta1.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
     }
     leaderboard.add_entry(meta1.emit_provenance(), vec!["en".to_string()]);
     
     // User 2: 10 steps
     let mut meta2 = MetaAgent::new("user2", "backend2");
     for _ in 0..10 {
-        meta2.log_event(AgentType::Reasoning, "input", "output", "en", 0.9);
+        meta2.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
     }
     leaderboard.add_entry(meta2.emit_provenance(), vec!["en".to_string()]);
     
@@ -217,7 +221,7 @@ fn test_leaderboard_ranking_criteria() {
     
     let mut meta = MetaAgent::new("user1", "backend1");
     for _ in 0..10 {
-        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9);
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
     }
     leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
     
@@ -240,7 +244,7 @@ fn test_leaderboard_top_n() {
     for i in 0..5 {
         let mut meta = MetaAgent::new(&format!("user{}", i), "backend");
         for _ in 0..(i + 1) * 5 {
-            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9);
+            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
         }
         leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
     }
@@ -254,7 +258,7 @@ fn test_leaderboard_export_json() {
     let mut leaderboard = Leaderboard::new();
     let mut meta = MetaAgent::new("user1", "backend1");
     
-    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9);
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
     leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
     
     let json = leaderboard.export_json(RankingCriteria::Combined);
@@ -268,7 +272,7 @@ fn test_contributor_history() {
     
     for _ in 0..3 {
         let mut meta = MetaAgent::new("user1", "backend1");
-        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9);
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
         leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
     }
     
@@ -280,7 +284,7 @@ fn test_contributor_history() {
 #[test]
 fn test_json_export() {
     let mut meta = MetaAgent::new("test_user", "test_backend");
-    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9);
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
     
     let trace_json = meta.export_trace_json();
     assert!(trace_json.is_ok());
@@ -307,14 +311,3239 @@ fn test_complex_multilingual_scenario() {
     for i in 0..20 {
         let agent = agents[i % agents.len()].clone();
         let lang = languages[i % languages.len()];
-        meta.log_event(agent, "input", "output", lang, 0.85 + (i as f64 * 0.005));
+        meta.log_event(agent, "input", "output", lang, 0.85 + (i as f64 * 0.005)).unwrap();
     }
     
     let folded = meta.fold_memory();
     assert_eq!(folded.language_distribution.len(), 5);
     assert!(folded.key_insights.len() > 0);
+    assert_eq!(folded.language_sequence, vec!["en", "id", "zh", "es", "fr"]);
+    assert_eq!(folded.language_transition_count, 19);
     
     let provenance = meta.emit_provenance();
     assert_eq!(provenance.trace_depth, 20);
     assert!(provenance.transitions.len() > 0);
 }
+
+#[test]
+fn test_rle_agent_sequence() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+
+    for _ in 0..100 {
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    }
+
+    let rle = meta.build_rle_agent_sequence();
+    assert_eq!(rle, vec![(AgentType::Reasoning, 100)]);
+
+    let folded = meta.fold_memory();
+    assert_eq!(folded.rle_agent_sequence, rle);
+    assert!(folded.summary.chars().count() < 50);
+}
+
+#[test]
+fn test_trace_ordering_validation() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+
+    meta.log_event(AgentType::Classification, "input1", "output1", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input2", "output2", "en", 0.92).unwrap();
+    meta.log_event(AgentType::Synthesis, "input3", "output3", "en", 0.88).unwrap();
+    assert!(meta.validate_trace_ordering().is_empty());
+
+    // Inject an out-of-order event
+    meta.trace[1].timestamp = meta.trace[0].timestamp - chrono::Duration::seconds(10);
+
+    let violations = meta.validate_trace_ordering();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].index, 1);
+    assert!(violations[0].event_timestamp < violations[0].previous_timestamp);
+
+    meta.fix_trace_ordering();
+    assert!(meta.validate_trace_ordering().is_empty());
+    assert_eq!(meta.trace[0].agent, AgentType::Reasoning);
+    assert_eq!(meta.get_transition_count(), 2);
+}
+
+#[test]
+fn test_leaderboard_backend_stats() {
+    let mut leaderboard = Leaderboard::new();
+
+    for (user, backend, depth) in [("user1", "backend_a", 10), ("user2", "backend_a", 20), ("user3", "backend_b", 5)] {
+        let mut meta = MetaAgent::new(user, backend);
+        for _ in 0..depth {
+            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+
+    let stats = leaderboard.get_backend_stats();
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats[0].backend_used, "backend_a");
+    assert_eq!(stats[0].contributor_count, 2);
+    assert_eq!(stats[0].mean_trace_depth, 15.0);
+    assert_eq!(stats[0].max_trace_depth, 20);
+    assert_eq!(stats[1].backend_used, "backend_b");
+
+    let quality = leaderboard.rank_by_backend_quality();
+    assert_eq!(quality.len(), 2);
+    assert!(quality.iter().any(|(name, _)| *name == "backend_a"));
+    assert!(quality.iter().any(|(name, _)| *name == "backend_b"));
+}
+
+#[test]
+fn test_language_entropy() {
+    let mut english = MetaAgent::new("test_user", "test_backend");
+    for _ in 0..8 {
+        english.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    }
+    assert_eq!(english.compute_language_entropy(), 0.0);
+    assert_eq!(english.fold_memory().language_entropy, 0.0);
+
+    let mut multilingual = MetaAgent::new("test_user", "test_backend");
+    for lang in ["en", "id", "zh", "es", "fr", "de", "ja", "ar"] {
+        multilingual.log_event(AgentType::Reasoning, "input", "output", lang, 0.9).unwrap();
+    }
+    assert!((multilingual.compute_language_entropy() - 3.0).abs() < 1e-9);
+
+    // Single agent type contributes zero reasoning entropy
+    assert!((multilingual.combined_diversity_score() - 0.5).abs() < 1e-9);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn test_leaderboard_sqlite_round_trip() {
+    let mut leaderboard = Leaderboard::new();
+
+    for (user, langs) in [("user1", vec!["en", "id"]), ("user2", vec!["zh"])] {
+        for _ in 0..2 {
+            let mut meta = MetaAgent::new(user, "backend1");
+            meta.log_event(AgentType::Classification, "input", "output", langs[0], 0.9).unwrap();
+            meta.log_event(AgentType::Reasoning, "input", "output", langs[0], 0.9).unwrap();
+            leaderboard.add_entry(meta.emit_provenance(), langs.iter().map(|l| l.to_string()).collect());
+        }
+    }
+
+    // user2 builds on user1, so user1 has a centrality to restore from the history
+    let parent = leaderboard.get_contributor_history("user1").unwrap()[0].clone();
+    let mut meta = MetaAgent::new("user2", "backend1");
+    meta.log_event(AgentType::Synthesis, "input", "output", "zh", 0.9).unwrap();
+    leaderboard.add_entry(meta.emit_provenance().with_parent(&parent), vec!["zh".to_string()]);
+    assert_eq!(leaderboard.entries["user1"].network_centrality, 1.0);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("leaderboard.db");
+    leaderboard.export_to_sqlite(&path).unwrap();
+
+    let loaded = Leaderboard::load_from_sqlite(&path).unwrap();
+    assert_eq!(loaded.total_contributors(), leaderboard.total_contributors());
+    assert_eq!(loaded.total_submissions(), leaderboard.total_submissions());
+
+    for (id, original) in &leaderboard.entries {
+        let restored = &loaded.entries[id];
+        assert_eq!(restored.languages_used, original.languages_used);
+        assert_eq!(restored.trace_depth, original.trace_depth);
+        assert_eq!(restored.weighted_trace_depth, original.weighted_trace_depth);
+        assert_eq!(restored.uniqueness_score, original.uniqueness_score);
+        assert_eq!(restored.rank, original.rank);
+        assert_eq!(restored.percentile_rank, original.percentile_rank);
+        assert_eq!(restored.last_updated, original.last_updated);
+        assert_eq!(restored.network_centrality, original.network_centrality);
+
+        let original_history = leaderboard.get_contributor_history(id).unwrap();
+        let restored_history = loaded.get_contributor_history(id).unwrap();
+        assert_eq!(restored_history.len(), original_history.len());
+        assert_eq!(restored_history[0].trace_hash, original_history[0].trace_hash);
+    }
+}
+
+#[tokio::test]
+async fn test_event_stream() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    let mut receiver = meta.enable_event_stream();
+
+    let handle = tokio::spawn(async move {
+        let mut received = Vec::new();
+        while received.len() < 5 {
+            match receiver.recv().await {
+                Ok(event) => received.push(event),
+                Err(_) => break,
+            }
+        }
+        received
+    });
+
+    for i in 0..5 {
+        meta.log_event(AgentType::Reasoning, &format!("input {}", i), "output", "en", 0.9).unwrap();
+    }
+
+    let received = tokio::time::timeout(std::time::Duration::from_secs(5), handle)
+        .await
+        .expect("receiver timed out")
+        .unwrap();
+    assert_eq!(received.len(), 5);
+    assert_eq!(received[4].input, "input 4");
+
+    meta.disable_event_stream();
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    assert_eq!(meta.get_trace_depth(), 6);
+}
+
+#[test]
+fn test_language_fluency_scores() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+
+    for _ in 0..3 {
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.7).unwrap();
+        meta.log_event(AgentType::Translation, "input", "output", "zh", 0.95).unwrap();
+    }
+
+    assert_eq!(meta.profile.top_fluency_language(), None);
+    meta.update_profile();
+
+    assert!((meta.profile.language_fluency_scores["en"] - 0.7).abs() < 1e-9);
+    assert!((meta.profile.language_fluency_scores["zh"] - 0.95).abs() < 1e-9);
+    assert_eq!(meta.profile.top_fluency_language(), Some("zh"));
+}
+
+#[test]
+fn test_time_range_queries() {
+    use chrono::{TimeZone, Utc};
+
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    assert_eq!(meta.get_session_duration(), None);
+
+    meta.log_event(AgentType::Classification, "input1", "output1", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input2", "output2", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Synthesis, "input3", "output3", "en", 0.9).unwrap();
+
+    let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+    for (i, event) in meta.trace.iter_mut().enumerate() {
+        event.timestamp = base + chrono::Duration::minutes(i as i64 * 10);
+    }
+    meta.transitions[0].timestamp = base + chrono::Duration::minutes(10);
+    meta.transitions[1].timestamp = base + chrono::Duration::minutes(20);
+
+    let events = meta.get_events_in_time_range(base, base + chrono::Duration::minutes(10));
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].agent, AgentType::Classification);
+    assert_eq!(events[1].agent, AgentType::Reasoning);
+
+    let transitions = meta.get_transitions_in_time_range(
+        base + chrono::Duration::minutes(15),
+        base + chrono::Duration::minutes(20),
+    );
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].to_agent, AgentType::Synthesis);
+
+    assert_eq!(meta.get_session_duration(), Some(std::time::Duration::from_secs(20 * 60)));
+}
+
+#[test]
+fn test_memory_fold_apply_delta() {
+    use quantum_limit_graph::level5::MemoryFold;
+
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    let agents = [AgentType::Classification, AgentType::Reasoning, AgentType::Synthesis];
+    let languages = ["en", "id", "zh"];
+
+    for i in 0..10 {
+        meta.log_event(agents[i % 3].clone(), &format!("input {}", i), "output", languages[i % 2], 0.9).unwrap();
+    }
+    let mut fold = meta.fold_memory();
+    let mut restored: MemoryFold = serde_json::from_str(&serde_json::to_string(&fold).unwrap()).unwrap();
+
+    for i in 10..20 {
+        meta.log_event(agents[i % 3].clone(), &format!("input {}", i), "output", languages[i % 3], 0.75).unwrap();
+    }
+    fold.apply_delta(&meta.trace[10..]);
+
+    let fresh = meta.fold_memory();
+    assert_eq!(fold.folded_trace.len(), fresh.folded_trace.len());
+    assert_eq!(fold.language_distribution, fresh.language_distribution);
+    assert_eq!(fold.rle_agent_sequence, fresh.rle_agent_sequence);
+    assert_eq!(fold.summary, fresh.summary);
+    assert_eq!(fold.key_insights, fresh.key_insights);
+    assert_eq!(fold.compression_ratio, fresh.compression_ratio);
+    assert!((fold.language_entropy - fresh.language_entropy).abs() < 1e-9);
+    assert!((fold.session_score - fresh.session_score).abs() < 1e-9);
+    assert!((fold.transition_entropy - fresh.transition_entropy).abs() < 1e-9);
+    assert_eq!(fold.total_estimated_tokens, fresh.total_estimated_tokens);
+    assert_eq!(fold.max_consecutive_same_agent, fresh.max_consecutive_same_agent);
+    assert_eq!(fold.top_output_words, fresh.top_output_words);
+    assert_eq!(fold.back_reference_count, fresh.back_reference_count);
+    assert_eq!(fold.translation_pairs, fresh.translation_pairs);
+    assert_eq!(fold.language_agent_affinity, fresh.language_agent_affinity);
+    assert_eq!(fold.domain_coverage_ratio, fresh.domain_coverage_ratio);
+    assert!((fold.output_diversity - fresh.output_diversity).abs() < 1e-9);
+    assert!((fold.semantic_coherence_proxy - fresh.semantic_coherence_proxy).abs() < 1e-9);
+    assert!((fold.total_cost_usd - fresh.total_cost_usd).abs() < 1e-9);
+    assert_eq!(fold.language_sequence, fresh.language_sequence);
+    assert_eq!(fold.language_transition_count, fresh.language_transition_count);
+    assert_eq!(fold.inferred_task, fresh.inferred_task);
+    assert_eq!(fold.detected_loops, fresh.detected_loops);
+    assert_eq!(fold.transition_narrative, fresh.transition_narrative);
+
+    // A deserialized fold has no running totals and rebuilds them on first use
+    restored.apply_delta(&meta.trace[10..15]);
+    restored.apply_delta(&meta.trace[15..]);
+    assert_eq!(restored.key_insights, fresh.key_insights);
+    assert_eq!(restored.top_output_words, fresh.top_output_words);
+    assert_eq!(restored.inferred_task, fresh.inferred_task);
+    assert!((restored.session_score - fresh.session_score).abs() < 1e-9);
+    assert!((restored.semantic_coherence_proxy - fresh.semantic_coherence_proxy).abs() < 1e-9);
+}
+
+#[test]
+fn test_redact_by_pattern() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+
+    let mut metadata = HashMap::new();
+    metadata.insert("card".to_string(), "4111-1111-1111-1111".to_string());
+    meta.log_event_with_metadata(
+        AgentType::Classification,
+        "Charge card 1234-5678-9012-3456 and 4111-1111-1111-1111",
+        "Charged 1234-5678-9012-3456",
+        "en",
+        0.9,
+        metadata,
+    ).unwrap();
+    meta.log_event(AgentType::Reasoning, "no pii here", "none", "en", 0.9).unwrap();
+    let hash_before = meta.emit_provenance().trace_hash;
+
+    let pattern = regex::Regex::new(r"\b\d{4}-\d{4}-\d{4}-\d{4}\b").unwrap();
+    assert_eq!(meta.redact_by_pattern(&pattern, "[REDACTED]"), 4);
+
+    assert_eq!(meta.trace[0].input, "Charge card [REDACTED] and [REDACTED]");
+    assert_eq!(meta.trace[0].output, "Charged [REDACTED]");
+    assert_eq!(meta.trace[0].metadata["card"], "[REDACTED]");
+    assert!(meta.trace.iter().all(|e| !pattern.is_match(&e.input) && !pattern.is_match(&e.output)));
+    assert_ne!(meta.emit_provenance().trace_hash, hash_before);
+}
+
+#[test]
+fn test_contributor_rank_at() {
+    let mut leaderboard = Leaderboard::new();
+
+    let mut meta1 = MetaAgent::new("user1", "backend1");
+    for _ in 0..10 {
+        meta1.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    }
+    leaderboard.add_entry(meta1.emit_provenance(), vec!["en".to_string()]);
+    leaderboard.take_snapshot();
+
+    let mut meta2 = MetaAgent::new("user2", "backend1");
+    for _ in 0..50 {
+        meta2.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    }
+    leaderboard.add_entry(meta2.emit_provenance(), vec!["en".to_string()]);
+    leaderboard.take_snapshot();
+
+    let first = leaderboard.snapshots[0].timestamp;
+    leaderboard.snapshots[1].timestamp = first + chrono::Duration::hours(2);
+
+    assert_eq!(leaderboard.get_contributor_rank_at("user1", first - chrono::Duration::seconds(1)), None);
+    assert_eq!(leaderboard.get_contributor_rank_at("user1", first + chrono::Duration::hours(1)), Some(1));
+    assert_eq!(leaderboard.get_contributor_rank_at("user1", first + chrono::Duration::hours(3)), Some(2));
+    assert_eq!(leaderboard.get_contributor_rank_at("user2", first + chrono::Duration::hours(1)), None);
+}
+
+#[test]
+fn test_score_trace() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    for _ in 0..5 {
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    }
+
+    let before = meta.score_trace();
+    assert!((before.confidence_score - 0.9).abs() < 1e-9);
+    assert_eq!(before.diversity_score, 0.0);
+    assert!((before.depth_score - 0.1).abs() < 1e-9);
+    assert_eq!(before.transition_quality_score, 1.0);
+
+    let agents = [AgentType::Classification, AgentType::Retrieval, AgentType::Synthesis, AgentType::Validation];
+    let languages = ["id", "zh", "es", "fr"];
+    for i in 0..4 {
+        meta.log_event(agents[i].clone(), "input", "output", languages[i], 0.9).unwrap();
+    }
+
+    let after = meta.score_trace();
+    assert!(after.diversity_score > before.diversity_score);
+    assert!(after.overall > before.overall);
+    assert!((meta.fold_memory().session_score - after.overall).abs() < 1e-9);
+}
+
+#[test]
+fn test_agent_type_all() {
+    let all: Vec<AgentType> = AgentType::all().collect();
+    assert_eq!(all.len(), AgentType::count());
+    assert_eq!(all[0], AgentType::Classification);
+
+    let unique: std::collections::HashSet<_> = all.iter().collect();
+    assert_eq!(unique.len(), AgentType::count());
+}
+
+#[test]
+fn test_conditional_logging() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+
+    let only_reasoning = |_: f64, agent: &AgentType| *agent == AgentType::Reasoning;
+    assert!(meta.log_event_conditional(AgentType::Reasoning, "input", "output", "en", 0.9, only_reasoning));
+    assert!(!meta.log_event_conditional(AgentType::Action, "input", "output", "en", 0.9, only_reasoning));
+    assert_eq!(meta.get_trace_depth(), 1);
+
+    let mut gated = meta.with_confidence_gate(0.8);
+    assert!(gated.log_event(AgentType::Synthesis, "input", "output", "en", 0.85));
+    assert!(!gated.log_event(AgentType::Synthesis, "input", "output", "en", 0.5));
+    assert_eq!(gated.get_trace_depth(), 2);
+
+    let mut meta = gated.into_inner();
+    assert_eq!(meta.get_trace_depth(), 2);
+
+    // Passing the gate is not enough if a pre-log hook then drops the event
+    meta.set_pre_log_hook(|_, input, _, _, _| input != "blocked");
+    assert!(!meta.log_event_conditional(AgentType::Reasoning, "blocked", "output", "en", 0.9, only_reasoning));
+    assert_eq!(meta.get_trace_depth(), 2);
+    let mut gated = meta.with_confidence_gate(0.8);
+    assert!(!gated.log_event(AgentType::Synthesis, "blocked", "output", "en", 0.9));
+}
+
+#[test]
+fn test_profile_validation() {
+    let mut profile = MetaAgent::new("test_user", "test_backend").profile;
+    assert!(profile.validate().is_ok());
+
+    profile.contributor_id = String::new();
+    profile.reasoning_style = "guesswork".to_string();
+    let errors = profile.validate().unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(errors.contains(&ProfileValidationError::EmptyContributorId));
+    assert!(errors.contains(&ProfileValidationError::UnrecognizedReasoningStyle("guesswork".to_string())));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "invalid contributor profile")]
+fn test_with_profile_rejects_invalid_profile_in_debug() {
+    let mut profile = MetaAgent::new("test_user", "test_backend").profile;
+    profile.avg_trace_depth = -1.0;
+    MetaAgent::with_profile("test_user", "test_backend", profile);
+}
+
+#[test]
+fn test_cohort_normalize() {
+    use chrono::{TimeZone, Utc};
+
+    let mut leaderboard = Leaderboard::new();
+    let base = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+    // Older contributor: 30 submissions over 30 days
+    for day in 0..30 {
+        let mut meta = MetaAgent::new("veteran", "backend1");
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        let mut provenance = meta.emit_provenance();
+        provenance.timestamp = base + chrono::Duration::days(day);
+        leaderboard.add_entry(provenance, vec!["en".to_string()]);
+    }
+
+    // New contributor: 5 submissions over the last 5 days
+    for day in 25..30 {
+        let mut meta = MetaAgent::new("newcomer", "backend1");
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        let mut provenance = meta.emit_provenance();
+        provenance.timestamp = base + chrono::Duration::days(day);
+        leaderboard.add_entry(provenance, vec!["en".to_string()]);
+    }
+
+    leaderboard.cohort_normalize(1);
+    let veteran = leaderboard.entries["veteran"].normalized_submission_rate;
+    let newcomer = leaderboard.entries["newcomer"].normalized_submission_rate;
+    assert!((veteran - 1.0).abs() < 1e-9);
+    assert!((newcomer - veteran).abs() < 1e-9);
+
+    let ranked = leaderboard.get_top_n(2, RankingCriteria::NormalizedSubmissionRate);
+    assert_eq!(ranked.len(), 2);
+}
+
+#[test]
+fn test_verify_full_chain() {
+    use quantum_limit_graph::level5::{ChainError, ProvenanceLog, ProvenanceSigner};
+
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    let mut chain: Vec<ProvenanceLog> = Vec::new();
+    for i in 0..5 {
+        meta.log_event(AgentType::Reasoning, &format!("input {}", i), "output", "en", 0.9).unwrap();
+        let provenance = match chain.last() {
+            Some(parent) => meta.emit_provenance().with_parent(parent),
+            None => meta.emit_provenance(),
+        };
+        chain.push(provenance);
+    }
+
+    let result = ProvenanceLog::verify_full_chain(&chain);
+    assert!(result.valid);
+    assert_eq!(result.broken_at, None);
+
+    chain[3].parent_hash = Some("tampered".to_string());
+    let result = ProvenanceLog::verify_full_chain(&chain);
+    assert!(!result.valid);
+    assert_eq!(result.broken_at, Some(3));
+    assert_eq!(result.error, Some(ChainError::HashMismatch(3)));
+
+    chain[3].parent_hash = None;
+    let result = ProvenanceLog::verify_full_chain(&chain);
+    assert_eq!(result.error, Some(ChainError::MissingParentHash(3)));
+
+    // Linking keeps the content hash; the link is part of the signed payload
+    let root = meta.emit_provenance();
+    assert_eq!(root.clone().with_parent(&chain[0]).trace_hash, root.trace_hash);
+    assert_eq!(root.clone().with_parent(&chain[1]).trace_hash, root.trace_hash);
+    assert!(root.trace_hash.starts_with(&meta.get_session_fingerprint()));
+    let signer = ProvenanceSigner::new(&[3u8; 32]);
+    let mut linked = root.with_parent(&chain[0]);
+    signer.sign(&mut linked);
+    assert!(ProvenanceSigner::verify(&linked, &signer.public_key()).is_ok());
+    linked.parent_hash = Some(chain[1].trace_hash.clone());
+    assert!(ProvenanceSigner::verify(&linked, &signer.public_key()).is_err());
+
+    // With signatures, a rewritten trace hash is reported at the log itself
+    let mut signed: Vec<ProvenanceLog> = Vec::new();
+    for mut log in chain.iter().cloned() {
+        log.parent_hash = signed.last().map(|parent| parent.trace_hash.clone());
+        signer.sign(&mut log);
+        signed.push(log);
+    }
+    assert!(ProvenanceLog::verify_full_chain_signed(&signed, &signer.public_key()).valid);
+    signed[2].trace_hash = "rewritten".to_string();
+    assert_eq!(ProvenanceLog::verify_full_chain(&signed).broken_at, Some(3));
+    let result = ProvenanceLog::verify_full_chain_signed(&signed, &signer.public_key());
+    assert_eq!(result.broken_at, Some(2));
+    assert_eq!(result.error, Some(ChainError::InvalidSignature(2)));
+}
+
+#[test]
+fn test_export_trace_csv() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    meta.log_event(AgentType::Classification, "secret patient record", "three word output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input, with \"quotes\"", "output", "id", 0.8).unwrap();
+    meta.trace[1].tags = vec!["physics".to_string(), "review".to_string()];
+
+    let csv_text = meta.export_trace_csv().unwrap();
+    assert!(!csv_text.contains("secret"));
+
+    let mut reader = csv::Reader::from_reader(csv_text.as_bytes());
+    let headers = reader.headers().unwrap().clone();
+    assert_eq!(headers.len(), 9);
+    assert_eq!(&headers[0], "index");
+
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(&rows[0][2], "Classification");
+    assert_eq!(&rows[0][3], "3");
+    assert_eq!(&rows[1][8], "physics;review");
+
+    let full = meta.export_trace_csv_full().unwrap();
+    let mut reader = csv::Reader::from_reader(full.as_bytes());
+    assert_eq!(reader.headers().unwrap().len(), 11);
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+    assert_eq!(&rows[1][9], "input, with \"quotes\"");
+
+    let empty = MetaAgent::new("test_user", "test_backend").export_trace_csv().unwrap();
+    assert!(empty.starts_with("index,timestamp"));
+}
+
+#[test]
+fn test_transition_entropy() {
+    let mut predictable = MetaAgent::new("test_user", "test_backend");
+    predictable.log_event(AgentType::Classification, "input", "output", "en", 0.9).unwrap();
+    predictable.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    assert_eq!(predictable.compute_transition_entropy(), 0.0);
+
+    // Classification → Reasoning twice, Reasoning → Classification once
+    predictable.log_event(AgentType::Classification, "input", "output", "en", 0.9).unwrap();
+    predictable.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    let expected = -(2.0 / 3.0f64) * (2.0 / 3.0f64).log2() - (1.0 / 3.0f64) * (1.0 / 3.0f64).log2();
+    assert!((predictable.compute_transition_entropy() - expected).abs() < 1e-9);
+
+    // Eight distinct transition pairs
+    let mut exploratory = MetaAgent::new("test_user", "test_backend");
+    for agent in AgentType::all().chain(std::iter::once(AgentType::Classification)) {
+        exploratory.log_event(agent, "input", "output", "en", 0.9).unwrap();
+    }
+    let folded = exploratory.fold_memory();
+    assert!((folded.transition_entropy - 3.0).abs() < 1e-9);
+    assert!(folded.key_insights.iter().any(|i| i.starts_with("Highly exploratory reasoning")));
+}
+
+#[cfg(feature = "prometheus")]
+#[test]
+fn test_export_prometheus_metrics() {
+    let mut leaderboard = Leaderboard::new();
+    for (user, depth) in [("user1", 5), ("user2", 12)] {
+        let mut meta = MetaAgent::new(user, "backend1");
+        for _ in 0..depth {
+            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+
+    let metrics = leaderboard.export_prometheus_metrics();
+    let mut samples = HashMap::new();
+    for line in metrics.lines().filter(|l| !l.starts_with('#') && !l.is_empty()) {
+        let (name, value) = line.rsplit_once(' ').unwrap();
+        samples.insert(name.to_string(), value.parse::<f64>().unwrap());
+    }
+
+    assert_eq!(samples["leaderboard_total_contributors"], 2.0);
+    assert_eq!(samples["leaderboard_total_submissions"], 2.0);
+    assert_eq!(samples["leaderboard_top_trace_depth"], 12.0);
+    let rank = samples["leaderboard_contributor_rank{contributor_id=\"user2\",backend=\"backend1\"}"];
+    assert_eq!(rank, leaderboard.entries["user2"].rank as f64);
+}
+
+#[test]
+fn test_fork() {
+    let mut original = MetaAgent::new("test_user", "test_backend");
+    for _ in 0..5 {
+        original.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    }
+
+    let mut fork = original.fork();
+    assert!(fork.session_id.starts_with(&format!("fork_{}_", original.session_id)));
+    assert_eq!(fork.current_agent, original.current_agent);
+
+    for _ in 0..3 {
+        fork.log_event(AgentType::Synthesis, "input", "output", "en", 0.9).unwrap();
+    }
+    for _ in 0..2 {
+        original.log_event(AgentType::Validation, "input", "output", "en", 0.9).unwrap();
+    }
+
+    assert_eq!(original.get_trace_depth(), 7);
+    assert_eq!(fork.get_trace_depth(), 8);
+    assert_eq!(original.get_transition_count(), 1);
+    assert_eq!(fork.get_transition_count(), 1);
+    assert_eq!(fork.profile.contributor_id, original.profile.contributor_id);
+}
+
+#[test]
+fn test_rank_by_reasoning_entropy() {
+    let mut leaderboard = Leaderboard::new();
+
+    let mut focused = MetaAgent::new("focused", "backend");
+    for _ in 0..12 {
+        focused.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    }
+    leaderboard.add_entry(focused.emit_provenance(), vec!["en".to_string()]);
+
+    let mut diverse = MetaAgent::new("diverse", "backend");
+    let agents: Vec<AgentType> = AgentType::all().collect();
+    for agent in agents.iter().cycle().take(12) {
+        diverse.log_event(agent.clone(), "input", "output", "en", 0.9).unwrap();
+    }
+    leaderboard.add_entry(diverse.emit_provenance(), vec!["en".to_string()]);
+
+    let ranked = leaderboard.get_top_n(2, RankingCriteria::ReasoningEntropy);
+    assert_eq!(ranked[0].contributor_id, "diverse");
+    assert_eq!(ranked[1].contributor_id, "focused");
+    assert!(ranked[0].reasoning_entropy > 0.0);
+    assert_eq!(ranked[1].reasoning_entropy, 0.0);
+
+    // Logs serialized before the field existed default to zero entropy
+    let mut value = serde_json::to_value(diverse.emit_provenance()).unwrap();
+    value.as_object_mut().unwrap().remove("reasoning_entropy");
+    let old_log: quantum_limit_graph::level5::ProvenanceLog = serde_json::from_value(value).unwrap();
+    assert_eq!(old_log.reasoning_entropy, 0.0);
+}
+
+#[test]
+fn test_token_estimate() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    meta.log_event(AgentType::Reasoning, "one two three", "four", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Synthesis, "one two three four five", "six", "en", 0.9).unwrap();
+
+    // 4 words / 0.75 = 5.33 -> 6, 6 words / 0.75 = 8
+    assert_eq!(meta.trace[0].estimate_tokens(), 6);
+    assert_eq!(meta.trace[1].estimate_tokens(), 8);
+    assert_eq!(meta.total_estimated_tokens(), 14);
+    assert_eq!(meta.fold_memory().total_estimated_tokens, 14);
+    assert_eq!(meta.emit_provenance().total_tokens_estimate, 14);
+
+    let mut leaderboard = Leaderboard::new();
+    leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+
+    let mut verbose = MetaAgent::new("verbose_user", "test_backend");
+    verbose.log_event(AgentType::Reasoning, &"word ".repeat(30), &"word ".repeat(30), "en", 0.9).unwrap();
+    verbose.log_event(AgentType::Synthesis, &"word ".repeat(30), &"word ".repeat(30), "en", 0.9).unwrap();
+    leaderboard.add_entry(verbose.emit_provenance(), vec!["en".to_string()]);
+
+    let ranked = leaderboard.get_top_n(2, RankingCriteria::TokenEfficiency);
+    assert_eq!(ranked[0].contributor_id, "test_user");
+    assert_eq!(ranked[0].total_estimated_tokens, 14);
+    assert_eq!(ranked[1].total_estimated_tokens, 160);
+}
+
+#[test]
+fn test_soft_delete_and_restore_contributor() {
+    let mut leaderboard = Leaderboard::new();
+    for (contributor, depth) in [("alice", 30), ("bob", 20), ("carol", 10)] {
+        let mut meta = MetaAgent::new(contributor, "backend");
+        for _ in 0..depth {
+            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+
+    assert!(leaderboard.soft_delete_contributor("bob"));
+    assert!(!leaderboard.soft_delete_contributor("bob"));
+    assert!(!leaderboard.soft_delete_contributor("unknown"));
+
+    let ranked: Vec<&str> = leaderboard.rank_by_depth().iter().map(|s| s.contributor_id.as_str()).collect();
+    assert_eq!(ranked, vec!["alice", "carol"]);
+    assert_eq!(leaderboard.total_contributors(), 2);
+    assert_eq!(leaderboard.total_submissions(), 2);
+    assert!(leaderboard.get_contributor_history("bob").is_some());
+
+    assert!(leaderboard.restore_contributor("bob"));
+    assert!(!leaderboard.restore_contributor("bob"));
+
+    let ranked: Vec<&str> = leaderboard.rank_by_depth().iter().map(|s| s.contributor_id.as_str()).collect();
+    assert_eq!(ranked, vec!["alice", "bob", "carol"]);
+    assert_eq!(leaderboard.total_contributors(), 3);
+    assert_eq!(leaderboard.total_submissions(), 3);
+}
+
+#[test]
+fn test_context_window_usage() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    // Each event has 6 words -> 8 estimated tokens
+    meta.log_event(AgentType::Classification, "a b c", "d e f", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "a b c", "d e f", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "a b c", "d e f", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Synthesis, "a b c", "d e f", "en", 0.9).unwrap();
+
+    let usage = meta.compute_context_window_usage(20);
+    assert_eq!(usage.estimated_tokens, 32);
+    assert!((usage.fraction_used - 1.6).abs() < 1e-9);
+    assert_eq!(usage.events_that_fit, 2);
+    assert!(usage.overflow);
+
+    let usage = meta.compute_context_window_usage(32);
+    assert_eq!(usage.events_that_fit, 4);
+    assert!(!usage.overflow);
+
+    meta.trim_to_context_window(20);
+    assert_eq!(meta.get_trace_depth(), 2);
+    assert_eq!(meta.trace[0].agent, AgentType::Reasoning);
+    assert_eq!(meta.get_transition_count(), 1);
+    assert!(!meta.compute_context_window_usage(20).overflow);
+}
+
+#[test]
+fn test_generate_badge_svg() {
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+
+    let mut leaderboard = Leaderboard::new();
+    for (contributor, depth) in [("alice", 30), ("bob", 20), ("carol", 10)] {
+        let mut meta = MetaAgent::new(contributor, "backend");
+        for _ in 0..depth {
+            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+
+    assert!(leaderboard.generate_badge_svg("unknown", RankingCriteria::TraceDepth).is_none());
+
+    let svg = leaderboard.generate_badge_svg("bob", RankingCriteria::TraceDepth).unwrap();
+    let mut reader = Reader::from_str(&svg);
+    let mut texts = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Text(text)) => texts.push(text.unescape().unwrap().into_owned()),
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => panic!("invalid SVG: {}", e),
+        }
+    }
+    assert!(texts.contains(&"qlg-rank".to_string()));
+    assert!(texts.contains(&"#2 / 3".to_string()));
+}
+
+#[test]
+fn test_event_at_percentile() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    // Log out of order so the method has to sort
+    for i in [3, 7, 1, 9, 5, 10, 2, 8, 4, 6] {
+        meta.log_event(AgentType::Reasoning, &format!("event {}", i), "output", "en", i as f64 / 10.0).unwrap();
+    }
+
+    let p50 = meta.event_at_percentile(0.5).unwrap();
+    assert_eq!(p50.input, "event 5");
+    let p90 = meta.event_at_percentile(0.9).unwrap();
+    assert_eq!(p90.input, "event 9");
+
+    assert_eq!(meta.percentile_confidence(0.0), Some(0.1));
+    assert_eq!(meta.percentile_confidence(1.0), Some(1.0));
+    assert!(meta.event_at_percentile(-0.1).is_none());
+    assert!(meta.event_at_percentile(1.1).is_none());
+    assert!(MetaAgent::new("empty", "backend").percentile_confidence(0.5).is_none());
+}
+
+#[test]
+fn test_memory_fold_merge() {
+    use quantum_limit_graph::level5::MemoryFold;
+
+    let mut english = MetaAgent::new("test_user", "test_backend");
+    let mut indonesian = MetaAgent::new("test_user", "test_backend");
+    for i in 0..4 {
+        english.log_event(AgentType::Reasoning, &format!("en {}", i), "output", "en", 0.9).unwrap();
+        indonesian.log_event(AgentType::Translation, &format!("id {}", i), "output", "id", 0.8).unwrap();
+    }
+    english.log_event(AgentType::Synthesis, "en 4", "output", "en", 0.9).unwrap();
+
+    let english_fold = english.fold_memory();
+    let indonesian_fold = indonesian.fold_memory();
+    let merged = MemoryFold::merge(&[english_fold.clone(), indonesian_fold.clone()]);
+
+    assert_eq!(merged.session_id, format!("{}_merged", english_fold.session_id));
+    assert_eq!(merged.folded_trace.len(), 9);
+    assert_eq!(merged.language_distribution.get("en"), Some(&5));
+    assert_eq!(merged.language_distribution.get("id"), Some(&4));
+    assert!(merged.folded_trace.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+
+    let unique: std::collections::HashSet<&String> = merged.key_insights.iter().collect();
+    assert_eq!(unique.len(), merged.key_insights.len());
+    for insight in english_fold.key_insights.iter().chain(&indonesian_fold.key_insights) {
+        assert!(merged.key_insights.contains(insight));
+    }
+    assert!(merged.compression_ratio > 0.0);
+
+    let empty = MemoryFold::merge(&[]);
+    assert!(empty.folded_trace.is_empty());
+    assert!(empty.language_distribution.is_empty());
+}
+
+#[cfg(feature = "qrcode")]
+#[test]
+fn test_provenance_qr_code() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    let provenance = meta.emit_provenance();
+
+    let svg = provenance.to_qr_code_svg().unwrap();
+    assert!(svg.contains("<svg"));
+
+    let png = provenance.to_qr_code_bytes().unwrap();
+    let image = image::load_from_memory(&png).unwrap().to_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare(image);
+    let grids = prepared.detect_grids();
+    assert_eq!(grids.len(), 1);
+    let (_, content) = grids[0].decode().unwrap();
+    assert_eq!(content, provenance.trace_hash[..32]);
+}
+
+#[test]
+fn test_sample_representative_events() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    for i in 0..15 {
+        let agent = if i % 3 == 0 { AgentType::Classification } else { AgentType::Reasoning };
+        meta.log_event(agent, &format!("event {}", i), "output", "en", i as f64 / 20.0).unwrap();
+    }
+
+    let sample = meta.sample_representative_events(3);
+    assert_eq!(sample.len(), 6);
+    assert_eq!(sample.iter().filter(|e| e.agent == AgentType::Reasoning).count(), 3);
+    assert_eq!(sample.iter().filter(|e| e.agent == AgentType::Classification).count(), 3);
+    assert!(sample.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+    assert_eq!(sample.first().unwrap().input, "event 0");
+    assert_eq!(sample.last().unwrap().input, "event 14");
+
+    let top = meta.sample_highest_confidence_events(2);
+    assert_eq!(top[0].input, "event 14");
+    assert_eq!(top[1].input, "event 13");
+
+    let empty = MetaAgent::new("empty", "backend");
+    assert!(empty.sample_representative_events(3).is_empty());
+    assert!(empty.sample_highest_confidence_events(3).is_empty());
+}
+
+#[test]
+fn test_add_entry_batch() {
+    let entries: Vec<_> = (0..100)
+        .map(|i| {
+            let mut meta = MetaAgent::new(&format!("contributor_{}", i), "backend");
+            for _ in 0..(i % 20 + 1) {
+                meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+            }
+            (meta.emit_provenance(), vec!["en".to_string()])
+        })
+        .collect();
+
+    let mut individual = Leaderboard::new();
+    for (provenance, languages) in entries.clone() {
+        individual.add_entry(provenance, languages);
+    }
+
+    let mut batch = Leaderboard::new();
+    batch.add_entry_batch(entries);
+
+    assert_eq!(batch.total_contributors(), 100);
+    assert_eq!(batch.total_submissions(), individual.total_submissions());
+    for (id, stats) in &individual.entries {
+        assert_eq!(batch.entries[id].rank, stats.rank);
+    }
+    // One re-rank per submission individually, one for the whole batch
+    assert_eq!(individual.rank_update_count(), 100);
+    assert_eq!(batch.rank_update_count(), 1);
+}
+
+#[test]
+fn test_contributor_confidence_profile() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    for (agent, confidence) in [
+        (AgentType::Classification, 0.6),
+        (AgentType::Reasoning, 0.9),
+        (AgentType::Retrieval, 0.75),
+        (AgentType::Synthesis, 0.8),
+    ] {
+        for _ in 0..3 {
+            meta.log_event(agent.clone(), "input", "output", "en", confidence).unwrap();
+        }
+    }
+
+    let mut leaderboard = Leaderboard::new();
+    leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+
+    let stats = &leaderboard.entries["test_user"];
+    assert_eq!(stats.confidence_profile.len(), 3);
+    assert!((stats.confidence_profile["Classification"] - 0.6).abs() < 1e-9);
+    assert!((stats.confidence_profile["Reasoning"] - 0.9).abs() < 1e-9);
+    assert!((stats.confidence_profile["Retrieval"] - 0.75).abs() < 1e-9);
+    assert_eq!(stats.weakest_agent(), Some("Classification"));
+    assert_eq!(stats.strongest_agent(), Some("Reasoning"));
+
+    let json = leaderboard.export_json(RankingCriteria::Combined).unwrap();
+    let parsed: Vec<quantum_limit_graph::level5::ContributorStats> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed[0].confidence_profile, stats.confidence_profile);
+
+    let csv = leaderboard.export_csv(RankingCriteria::Combined).unwrap();
+    assert!(csv.lines().next().unwrap().ends_with("confidence_profile"));
+    assert!(csv.contains("Classification=0.6;Reasoning=0.9;Retrieval=0.75"));
+}
+
+#[test]
+fn test_trace_similarity() {
+    let mut a = MetaAgent::new("alice", "backend");
+    let mut b = MetaAgent::new("bob", "backend");
+    let mut c = MetaAgent::new("carol", "backend");
+    let mut d = MetaAgent::new("dave", "backend");
+    for _ in 0..3 {
+        a.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        a.log_event(AgentType::Synthesis, "input", "output", "id", 0.9).unwrap();
+        b.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        b.log_event(AgentType::Synthesis, "input", "output", "id", 0.9).unwrap();
+        c.log_event(AgentType::Retrieval, "input", "output", "en", 0.9).unwrap();
+        c.log_event(AgentType::Validation, "input", "output", "id", 0.9).unwrap();
+        d.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        d.log_event(AgentType::Validation, "input", "output", "en", 0.9).unwrap();
+    }
+
+    assert!((MetaAgent::trace_similarity(&a, &b) - 1.0).abs() < 1e-9);
+    assert_eq!(MetaAgent::trace_similarity(&a, &c), 0.0);
+    assert!((MetaAgent::trace_similarity(&a, &d) - 0.5).abs() < 1e-9);
+    assert_eq!(MetaAgent::trace_similarity(&a, &MetaAgent::new("empty", "backend")), 0.0);
+
+    let mut leaderboard = Leaderboard::new();
+    for meta in [&a, &b, &c, &d] {
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+
+    let similar: Vec<&str> = leaderboard.find_similar_contributors("alice", 2)
+        .iter()
+        .map(|s| s.contributor_id.as_str())
+        .collect();
+    assert_eq!(similar, vec!["bob", "dave"]);
+    assert!(leaderboard.find_similar_contributors("unknown", 2).is_empty());
+}
+
+#[test]
+fn test_same_agent_runs() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    assert!(meta.get_longest_same_agent_run().is_none());
+
+    meta.log_event(AgentType::Classification, "input", "output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Retrieval, "input", "output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Retrieval, "input", "output", "en", 0.9).unwrap();
+    for _ in 0..8 {
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    }
+    meta.log_event(AgentType::Synthesis, "input", "output", "en", 0.9).unwrap();
+
+    assert_eq!(meta.get_longest_same_agent_run(), Some((AgentType::Reasoning, 3, 8)));
+    assert_eq!(
+        meta.get_all_same_agent_runs(),
+        vec![(AgentType::Retrieval, 1, 2), (AgentType::Reasoning, 3, 8)]
+    );
+
+    let fold = meta.fold_memory();
+    assert_eq!(fold.max_consecutive_same_agent, 8);
+    assert!(fold.key_insights.iter().any(|i| i.contains("8 consecutive events")));
+}
+
+#[test]
+fn test_export_html() {
+    use scraper::{Html, Selector};
+
+    let mut leaderboard = Leaderboard::new();
+    for i in 0..12 {
+        let mut meta = MetaAgent::new(&format!("contributor_{}", i), "backend");
+        for _ in 0..(i + 1) {
+            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+
+    let html = leaderboard.export_html(RankingCriteria::TraceDepth, "Q <LIMIT> Graph");
+    assert!(!html.contains("http://cdn") && !html.contains("https://"));
+
+    let document = Html::parse_document(&html);
+    let title = document.select(&Selector::parse("title").unwrap()).next().unwrap();
+    assert_eq!(title.text().collect::<String>(), "Q <LIMIT> Graph");
+
+    let rows: Vec<_> = document.select(&Selector::parse("tbody tr").unwrap()).collect();
+    assert_eq!(rows.len(), leaderboard.total_contributors());
+    let first_row: Vec<String> = rows[0]
+        .select(&Selector::parse("td").unwrap())
+        .map(|td| td.text().collect())
+        .collect();
+    assert_eq!(first_row[0], "1");
+    assert_eq!(first_row[1], "contributor_11");
+    assert_eq!(first_row[2], "Diamond");
+    assert_eq!(first_row[3], "12");
+
+    let bars = document.select(&Selector::parse("svg rect").unwrap()).count();
+    assert_eq!(bars, 10);
+}
+
+#[test]
+fn test_log_hooks() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    let pre_calls = Rc::new(Cell::new(0));
+    let post_calls = Rc::new(Cell::new(0));
+
+    let counter = Rc::clone(&pre_calls);
+    meta.set_pre_log_hook(move |_, _, _, _, confidence| {
+        counter.set(counter.get() + 1);
+        confidence >= 0.5
+    });
+    let counter = Rc::clone(&post_calls);
+    meta.set_post_log_hook(move |event| {
+        assert!(event.confidence >= 0.5);
+        counter.set(counter.get() + 1);
+    });
+
+    meta.log_event(AgentType::Classification, "input", "output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.3).unwrap();
+    meta.log_event(AgentType::Synthesis, "input", "output", "en", 0.7).unwrap();
+
+    assert_eq!(pre_calls.get(), 3);
+    assert_eq!(post_calls.get(), 2);
+    assert_eq!(meta.get_trace_depth(), 2);
+    // The blocked Reasoning event must not leave a transition behind
+    assert_eq!(meta.get_transition_count(), 1);
+    assert_eq!(meta.transitions[0].to_agent, AgentType::Synthesis);
+}
+
+#[test]
+fn test_word_frequency_analysis() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    meta.log_event(AgentType::Reasoning, "input", "The Quantum circuit is stable.", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Retrieval, "input", "Found a quantum paper, and a circuit.", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Synthesis, "input", "quantum advantage is likely", "en", 0.9).unwrap();
+
+    let fold = meta.fold_memory();
+    let top = fold.word_frequency_analysis(2);
+    assert_eq!(top, vec![("quantum".to_string(), 3), ("circuit".to_string(), 2)]);
+    assert_eq!(fold.top_output_words[0], "quantum");
+    assert!(fold.top_output_words.len() <= 5);
+    // quantum, circuit, stable, found, paper, advantage, likely
+    assert_eq!(fold.unique_word_count(), 7);
+}
+
+#[test]
+fn test_contributor_graph() {
+    use chrono::{Duration, TimeZone, Utc};
+
+    let base = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+    let mut leaderboard = Leaderboard::new();
+    for (contributor, offset_seconds) in [
+        ("alice", 0),
+        ("bob", 30),
+        ("alice", 600),
+        ("bob", 650),
+        ("carol", 700),
+        ("carol", 5000),
+    ] {
+        let mut meta = MetaAgent::new(contributor, "backend");
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        let mut provenance = meta.emit_provenance();
+        provenance.timestamp = base + Duration::seconds(offset_seconds);
+        leaderboard.add_entry(provenance, vec!["en".to_string()]);
+    }
+
+    let graph = leaderboard.contributor_graph(60);
+    assert_eq!(
+        graph.edges,
+        vec![
+            ("alice".to_string(), "bob".to_string(), 2),
+            ("bob".to_string(), "carol".to_string(), 1),
+        ]
+    );
+    assert_eq!(graph.most_connected_contributor(), Some("bob"));
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("graph contributors {"));
+    assert!(dot.contains("\"alice\" -- \"bob\" [weight=2"));
+
+    assert!(leaderboard.contributor_graph(10).edges.is_empty());
+    assert_eq!(leaderboard.contributor_graph(10).most_connected_contributor(), None);
+}
+
+#[test]
+fn test_session_metadata() {
+    let mut a = MetaAgent::new("test_user", "test_backend");
+    let mut b = MetaAgent::new("test_user", "test_backend");
+    for meta in [&mut a, &mut b] {
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    }
+    assert_eq!(a.emit_provenance().trace_hash, b.emit_provenance().trace_hash);
+
+    a.set_session_metadata("experiment", "baseline");
+    b.set_session_metadata("experiment", "ablation");
+    assert_eq!(a.get_session_metadata("experiment"), Some("baseline"));
+    assert_eq!(a.get_session_metadata("missing"), None);
+    assert_ne!(a.emit_provenance().trace_hash, b.emit_provenance().trace_hash);
+
+    // Field boundaries are part of the hash, so shifting characters between key and value matters
+    let mut c = MetaAgent::new("test_user", "test_backend");
+    let mut d = MetaAgent::new("test_user", "test_backend");
+    c.set_session_metadata("ab", "c");
+    d.set_session_metadata("a", "bc");
+    assert_ne!(c.emit_provenance().trace_hash, d.emit_provenance().trace_hash);
+
+    let fold = a.fold_memory();
+    assert_eq!(fold.session_metadata.get("experiment").map(String::as_str), Some("baseline"));
+    assert!(a.export_provenance_json().unwrap().contains("\"experiment\": \"baseline\""));
+}
+
+#[test]
+fn test_manual_transitions_only() {
+    let mut meta = MetaAgent::new("test_user", "test_backend").with_auto_transitions(false);
+    meta.log_event(AgentType::Classification, "input", "output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Synthesis, "input", "output", "en", 0.9).unwrap();
+    assert_eq!(meta.get_transition_count(), 0);
+
+    meta.track_transition(AgentType::Classification, AgentType::Synthesis, "manual_skip");
+    assert_eq!(meta.get_transition_count(), 1);
+    assert_eq!(meta.transitions[0].reason, "manual_skip");
+
+    let mut auto = MetaAgent::new("test_user", "test_backend").with_auto_transitions(true);
+    auto.log_event(AgentType::Classification, "input", "output", "en", 0.9).unwrap();
+    auto.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    assert_eq!(auto.get_transition_count(), 1);
+}
+
+#[test]
+fn test_score_distribution() {
+    let scores = [0.125, 0.25, 0.25, 0.375, 0.5, 0.5, 0.5, 0.625, 0.875, 1.0];
+    let mut leaderboard = Leaderboard::new();
+    for i in 0..scores.len() {
+        let mut meta = MetaAgent::new(&format!("contributor_{}", i), "backend");
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+    for (i, score) in scores.iter().enumerate() {
+        leaderboard.entries.get_mut(&format!("contributor_{}", i)).unwrap().combined_score = *score;
+    }
+
+    assert_eq!(
+        leaderboard.score_distribution(4),
+        vec![(0.0, 0.25, 1), (0.25, 0.5, 3), (0.5, 0.75, 4), (0.75, 1.0, 2)]
+    );
+    assert!((leaderboard.score_mean() - 0.5).abs() < 1e-12);
+    assert!((leaderboard.score_median() - 0.5).abs() < 1e-12);
+    assert!((leaderboard.score_stddev() - 0.06875f64.sqrt()).abs() < 1e-12);
+
+    let empty = Leaderboard::new();
+    assert!(empty.score_distribution(4).is_empty());
+    assert_eq!(empty.score_mean(), 0.0);
+}
+
+#[test]
+fn test_event_embeddings() {
+    use quantum_limit_graph::level5::{EmbeddingConfig, EmbeddingProvider};
+
+    /// Fixed vectors keyed on whether the text mentions "quantum"
+    struct ConstantEmbeddingProvider;
+
+    impl EmbeddingProvider for ConstantEmbeddingProvider {
+        fn embed(&self, text: &str) -> Vec<f32> {
+            if text.contains("quantum") {
+                vec![1.0, 0.0, 0.0]
+            } else {
+                vec![0.0, 1.0, 0.0]
+            }
+        }
+    }
+
+    let config = EmbeddingConfig {
+        dimension: 3,
+        provider: Box::new(ConstantEmbeddingProvider),
+    };
+    let mut meta = MetaAgent::new("test_user", "test_backend").with_embedding_config(config);
+    meta.log_event(AgentType::Reasoning, "quantum input", "output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input", "quantum output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "classical input", "output", "en", 0.9).unwrap();
+
+    assert_eq!(meta.get_event_embedding(0), Some(vec![1.0, 0.0, 0.0]));
+    assert_eq!(meta.get_event_embedding(3), None);
+    assert!((meta.compute_embedding_similarity(0, 1).unwrap() - 1.0).abs() < 1e-9);
+    assert_eq!(meta.compute_embedding_similarity(0, 2), Some(0.0));
+
+    let plain = MetaAgent::new("test_user", "test_backend");
+    assert!(plain.get_event_embedding(0).is_none());
+}
+
+#[test]
+fn test_memory_fold_to_prompt_string() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    for i in 0..6 {
+        let agent = if i < 4 { AgentType::Reasoning } else { AgentType::Synthesis };
+        meta.log_event(agent, "input", "output", if i % 3 == 0 { "id" } else { "en" }, 0.9).unwrap();
+    }
+    let fold = meta.fold_memory();
+
+    let full = fold.to_prompt_string(1000);
+    assert!(full.starts_with(&format!("[Session {}]", fold.session_id)));
+    assert!(full.contains(&fold.summary));
+    assert!(full.contains("Key insights: 6 high-confidence reasoning steps"));
+    assert!(full.contains("Dominant language: en."));
+    assert!(full.contains("Agent mix: Reasoning×4, Synthesis×2."));
+
+    let short = fold.to_prompt_string(10);
+    assert_eq!(short.trim_end_matches('…').split_whitespace().count(), 7);
+    assert!(short.ends_with('…'));
+    assert!(!fold.to_prompt_string(0).is_empty());
+}
+
+#[test]
+fn test_add_entry_signed() {
+    use ed25519_dalek::SigningKey;
+    use quantum_limit_graph::level5::{ProvenanceSigner, SignatureError};
+
+    let signing_key = SigningKey::generate(&mut rand::rngs::OsRng);
+    let signer = ProvenanceSigner::new(&signing_key.to_bytes());
+    let public_key = signer.public_key();
+
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+
+    let mut leaderboard = Leaderboard::with_strict_signatures();
+    let mut provenance = meta.emit_provenance();
+    signer.sign(&mut provenance);
+    assert!(leaderboard.add_entry_signed(provenance.clone(), vec!["en".to_string()], &public_key).is_ok());
+    assert_eq!(leaderboard.total_submissions(), 1);
+
+    let mut tampered = provenance.clone();
+    tampered.trace_hash = "0".repeat(64);
+    assert_eq!(
+        leaderboard.add_entry_signed(tampered, vec!["en".to_string()], &public_key),
+        Err(SignatureError::InvalidSignature)
+    );
+
+    let other_key = ProvenanceSigner::new(&[7u8; 32]).public_key();
+    assert_eq!(
+        leaderboard.add_entry_signed(provenance, vec!["en".to_string()], &other_key),
+        Err(SignatureError::InvalidSignature)
+    );
+
+    let unsigned = meta.emit_provenance();
+    assert_eq!(
+        leaderboard.add_entry_signed(unsigned.clone(), vec!["en".to_string()], &public_key),
+        Err(SignatureError::MissingSignature)
+    );
+    assert_eq!(leaderboard.total_submissions(), 1);
+
+    let mut lenient = Leaderboard::new();
+    assert_eq!(
+        lenient.add_entry_signed(unsigned.clone(), vec!["en".to_string()], &public_key),
+        Err(SignatureError::MissingSignature)
+    );
+    assert_eq!(lenient.total_submissions(), 0);
+
+    // Strict mode governs what plain `add_entry` takes
+    leaderboard.add_entry(unsigned.clone(), vec!["en".to_string()]);
+    leaderboard.add_entry_batch(vec![(unsigned.clone(), vec!["en".to_string()])]);
+    assert_eq!(leaderboard.total_submissions(), 1);
+    lenient.add_entry(unsigned, vec!["en".to_string()]);
+    assert_eq!(lenient.total_submissions(), 1);
+}
+
+#[test]
+fn test_compress_metadata() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    for i in 0..1000 {
+        let mut metadata = HashMap::new();
+        metadata.insert("source".to_string(), "arxiv_quantum_database".to_string());
+        metadata.insert("model".to_string(), "gpt-4-turbo-preview".to_string());
+        metadata.insert("shard".to_string(), format!("shard_{}", i % 4));
+        meta.log_event_with_metadata(AgentType::Reasoning, "input", "output", "en", 0.9, metadata).unwrap();
+    }
+
+    let before_json = meta.export_trace_json().unwrap();
+    let before = meta.metadata_memory_estimate();
+    meta.compress_metadata();
+    let after = meta.metadata_memory_estimate();
+
+    assert!(after * 2 < before, "expected large reduction: {} -> {}", before, after);
+    assert!(meta.trace[0].metadata.is_empty());
+    assert_eq!(
+        meta.event_metadata(5).unwrap().get("shard").map(String::as_str),
+        Some("shard_1")
+    );
+    let parse = |json: &str| serde_json::from_str::<serde_json::Value>(json).unwrap();
+    assert_eq!(parse(&meta.export_trace_json().unwrap()), parse(&before_json));
+    assert_eq!(meta.fold_memory().folded_trace[0].metadata.len(), 3);
+
+    let redacted = meta.redact_by_pattern(&regex::Regex::new("gpt-4").unwrap(), "[MODEL]");
+    assert_eq!(redacted, 1000);
+    assert_eq!(
+        meta.event_metadata(999).unwrap().get("model").map(String::as_str),
+        Some("[MODEL]-turbo-preview")
+    );
+
+    // Queries returning events see the full metadata through the accessors
+    let sample = meta.sample_highest_confidence_events(1);
+    assert_eq!(sample[0].full_metadata().len(), 3);
+    assert_eq!(sample[0].metadata_value("source"), Some("arxiv_quantum_database"));
+
+    // Interned values travel with their events, even when the trace is edited directly
+    meta.trace.swap(0, 2);
+    assert_eq!(meta.trace[0].metadata_value("shard"), Some("shard_2"));
+    let moved = meta.trace.remove(0);
+    let mut other = MetaAgent::new("other_user", "test_backend");
+    other.trace.push(moved);
+    assert_eq!(other.event_metadata(0).unwrap().get("shard").map(String::as_str), Some("shard_2"));
+
+    meta.trim_to_context_window(meta.trace[0].estimate_tokens() * 3);
+    assert_eq!(meta.trace.len(), 3);
+    assert_eq!(meta.trace[0].metadata_value("shard"), Some("shard_1"));
+}
+
+#[test]
+fn test_verifiable_credential_round_trip() {
+    use quantum_limit_graph::level5::ProvenanceLog;
+
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    meta.log_event(AgentType::Classification, "input", "output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input", "output", "id", 0.9).unwrap();
+    let provenance = meta.emit_provenance();
+
+    let vc = provenance.to_verifiable_credential();
+    assert_eq!(vc["@context"][0], "https://www.w3.org/2018/credentials/v1");
+    assert_eq!(vc["type"], serde_json::json!(["VerifiableCredential", "QuantumLimitGraphTrace"]));
+    assert_eq!(vc["issuer"], "test_user");
+    assert_eq!(vc["credentialSubject"]["agentCount"], 2);
+
+    let restored = ProvenanceLog::from_verifiable_credential(&vc).unwrap();
+    assert_eq!(restored.trace_hash, provenance.trace_hash);
+    assert_eq!(restored.contributor_id, provenance.contributor_id);
+    assert_eq!(restored.trace_depth, provenance.trace_depth);
+    assert_eq!(restored.timestamp, provenance.timestamp);
+
+    let mut wrong_type = vc.clone();
+    wrong_type["type"] = serde_json::json!(["VerifiableCredential"]);
+    assert!(ProvenanceLog::from_verifiable_credential(&wrong_type).is_err());
+}
+
+#[test]
+fn test_reset_session() {
+    use quantum_limit_graph::level5::SessionState;
+
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    meta.set_session_metadata("experiment", "baseline");
+    meta.log_event(AgentType::Classification, "input", "output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    meta.complete();
+    let original_session_id = meta.session_id.clone();
+    let preferred_languages = meta.profile.preferred_languages.clone();
+
+    meta.reset_session();
+
+    assert_eq!(meta.get_trace_depth(), 0);
+    assert_eq!(meta.get_transition_count(), 0);
+    assert_ne!(meta.session_id, original_session_id);
+    assert!(meta.current_agent.is_none());
+    assert!(meta.get_session_metadata("experiment").is_none());
+    assert_eq!(meta.state, SessionState::Active);
+    assert_eq!(meta.profile.total_traces, 1);
+    assert_eq!(meta.contributor_id, "test_user");
+    assert_eq!(meta.backend_used, "test_backend");
+    assert_eq!(meta.profile.preferred_languages, preferred_languages);
+}
+
+#[test]
+fn test_rank_by_improvement() {
+    let mut leaderboard = Leaderboard::new();
+    let mut submit = |contributor: &str, depth: usize| {
+        let mut meta = MetaAgent::new(contributor, "test_backend");
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        let mut provenance = meta.emit_provenance();
+        provenance.trace_depth = depth;
+        leaderboard.add_entry(provenance, vec!["en".to_string()]);
+    };
+
+    for _ in 0..10 {
+        submit("improver", 2);
+    }
+    for _ in 0..5 {
+        submit("improver", 12);
+    }
+    for _ in 0..15 {
+        submit("steady", 8);
+    }
+    for _ in 0..5 {
+        submit("newcomer", 30);
+    }
+
+    let ranked = leaderboard.rank_by_improvement();
+    assert_eq!(ranked[0].contributor_id, "improver");
+    assert!((ranked[0].improvement_score - 10.0).abs() < 1e-9);
+
+    let stats = leaderboard.get_top_n(3, RankingCriteria::Improvement);
+    let newcomer = stats.iter().find(|s| s.contributor_id == "newcomer").unwrap();
+    let steady = stats.iter().find(|s| s.contributor_id == "steady").unwrap();
+    assert_eq!(newcomer.improvement_score, 0.0);
+    assert_eq!(steady.improvement_score, 0.0);
+}
+
+#[test]
+fn test_count_back_references() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    meta.log_event(AgentType::Retrieval, "find sources", "Entanglement entropy scales with area", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "derive bound", "the bound holds for pure states", "en", 0.8).unwrap();
+    meta.log_event(AgentType::Synthesis, "combine results", "summary of the derivation", "en", 0.85).unwrap();
+    meta.log_event(
+        AgentType::Validation,
+        "check claim: Entanglement entropy scales with area",
+        "claim verified",
+        "en",
+        0.9,
+    ).unwrap();
+
+    assert_eq!(meta.count_back_references(), 1);
+    assert_eq!(meta.get_back_reference_events(), vec![(3, 0)]);
+    assert_eq!(meta.fold_memory().back_reference_count, 1);
+
+    // A fold extended with the same events counts the same back-references
+    let mut fold = MetaAgent::new("test_user", "test_backend").fold_memory();
+    fold.apply_delta(&meta.trace[..2]);
+    assert_eq!(fold.back_reference_count, 0);
+    fold.apply_delta(&meta.trace[2..]);
+    assert_eq!(fold.back_reference_count, 1);
+}
+
+#[test]
+fn test_profile_from_leaderboard_stats() {
+    let mut leaderboard = Leaderboard::new();
+    for depth in [2, 4] {
+        let mut meta = MetaAgent::new("test_user", "test_backend");
+        for _ in 0..depth {
+            meta.log_event(AgentType::Retrieval, "input", "output", "id", 0.95).unwrap();
+            meta.log_event(AgentType::Reasoning, "input", "output", "id", 0.6).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["id".to_string(), "en".to_string()]);
+    }
+
+    let stats = leaderboard.get_top_n(1, RankingCriteria::Combined)[0];
+    let profile = ContributorProfile::from_leaderboard_stats(stats);
+    assert_eq!(profile.contributor_id, "test_user");
+    assert_eq!(profile.total_traces, 2);
+    assert_eq!(profile.preferred_languages[0], "id");
+    assert_eq!(profile.avg_trace_depth, stats.avg_trace_depth);
+    assert_eq!(profile.reasoning_style, "empirical");
+    assert!(profile.expertise_domains.is_empty());
+    assert!(profile.validate().is_ok());
+}
+
+#[test]
+fn test_export_to_markdown_table() {
+    let mut leaderboard = Leaderboard::new();
+    for (contributor, depth) in [("alice", 3), ("bob", 6), ("carol", 1), ("dave", 4)] {
+        let mut meta = MetaAgent::new(contributor, "test_backend");
+        for _ in 0..depth {
+            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string(), "id".to_string()]);
+    }
+
+    let table = leaderboard.export_to_markdown_table(RankingCriteria::TraceDepth, 3);
+    assert_eq!(table.matches('\n').count(), 3 + 2);
+
+    let lines: Vec<&str> = table.lines().collect();
+    assert!(lines[0].contains("Rank") && lines[0].contains("Backend"));
+    assert!(lines[1].trim_matches('|').split('|').all(|cell| !cell.is_empty() && cell.chars().all(|c| c == '-')));
+    assert!(lines[2].contains("bob"));
+    for line in &lines {
+        assert!(line.starts_with('|') && line.ends_with('|'));
+        assert_eq!(line.chars().count(), lines[0].chars().count());
+    }
+}
+
+#[test]
+fn test_generate_trace_report() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    meta.log_event(AgentType::Classification, "classify", "quantum entanglement query", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "reason", "entanglement implies correlation", "en", 0.7).unwrap();
+    meta.log_event(AgentType::Reasoning, "reason more", "correlation persists", "id", 0.8).unwrap();
+    meta.log_event(AgentType::Synthesis, "synthesize", "entanglement summary", "en", 0.95).unwrap();
+
+    let report = meta.generate_trace_report();
+    assert_eq!(report.session_id, meta.session_id);
+    assert_eq!(report.contributor_id, "test_user");
+    assert_eq!(report.provenance.trace_hash, meta.emit_provenance().trace_hash);
+    assert_eq!(report.memory_fold.folded_trace.len(), 4);
+    assert_eq!(report.agent_stats.len(), 3);
+    let reasoning = report.agent_stats.iter().find(|s| s.agent == AgentType::Reasoning).unwrap();
+    assert_eq!(reasoning.event_count, 2);
+    assert!((reasoning.mean_confidence - 0.75).abs() < 1e-9);
+    assert_eq!(report.transition_matrix.get(&AgentType::Classification, &AgentType::Reasoning), 1);
+    assert_eq!(report.transition_matrix.get(&AgentType::Reasoning, &AgentType::Reasoning), 0);
+    assert_eq!(report.top_words[0], ("entanglement".to_string(), 3));
+    assert!((report.reasoning_entropy - meta.compute_reasoning_entropy()).abs() < 1e-9);
+    assert_eq!(report.session_score.overall, report.memory_fold.session_score);
+    assert_eq!(report.provenance.total_tokens_estimate, report.memory_fold.total_estimated_tokens);
+    assert_eq!(report.provenance.output_diversity, meta.compute_output_diversity());
+    assert_eq!(report.confidence_drift.len(), 3);
+    assert!((report.confidence_drift[0] + 0.2).abs() < 1e-9);
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+    assert_eq!(json["contributor_id"], "test_user");
+    assert_eq!(json["agent_stats"].as_array().unwrap().len(), 3);
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_trace_report_to_yaml() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+
+    let yaml = meta.generate_trace_report().to_yaml().unwrap();
+    assert!(yaml.contains("contributor_id: test_user"));
+}
+
+#[test]
+fn test_translation_source_language() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    meta.log_event(AgentType::Classification, "classify", "physics question", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Translation, "translate", "pertanyaan fisika", "id", 0.85).unwrap();
+
+    assert_eq!(meta.trace[1].metadata.get("source_language").map(String::as_str), Some("en"));
+    assert_eq!(meta.get_translation_pairs(), vec![("en".to_string(), "id".to_string())]);
+    assert_eq!(meta.fold_memory().translation_pairs, vec![("en".to_string(), "id".to_string())]);
+
+    // An explicit source language is kept
+    let mut metadata = HashMap::new();
+    metadata.insert("source_language".to_string(), "ar".to_string());
+    meta.log_event_with_metadata(AgentType::Translation, "translate", "output", "en", 0.8, metadata).unwrap();
+    meta.compress_metadata();
+    assert_eq!(meta.get_translation_pairs()[1], ("ar".to_string(), "en".to_string()));
+}
+
+#[test]
+fn test_percentile_for_metric() {
+    let mut leaderboard = Leaderboard::new();
+    assert_eq!(leaderboard.percentile_for_metric(RankingCriteria::TraceDepth, 0.5), None);
+
+    for depth in 1..=10 {
+        let mut meta = MetaAgent::new(&format!("contributor_{}", depth), "test_backend");
+        for _ in 0..depth {
+            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+
+    assert_eq!(leaderboard.percentile_for_metric(RankingCriteria::TraceDepth, 0.5), Some(5.0));
+    assert_eq!(leaderboard.percentile_for_metric(RankingCriteria::TraceDepth, 0.9), Some(9.0));
+    assert_eq!(leaderboard.percentile_for_metric(RankingCriteria::TraceDepth, 0.0), Some(1.0));
+    assert_eq!(leaderboard.percentile_for_metric(RankingCriteria::TraceDepth, 1.0), Some(10.0));
+    assert_eq!(leaderboard.percentile_for_metric(RankingCriteria::TraceDepth, 1.5), None);
+
+    let above = leaderboard.contributors_above_percentile(RankingCriteria::TraceDepth, 0.5);
+    assert_eq!(above.len(), 5);
+    assert_eq!(above[0].contributor_id, "contributor_10");
+    assert!(above.iter().all(|s| s.trace_depth > 5));
+}
+
+#[test]
+fn test_truncate_to_context_window_smart() {
+    let build = || {
+        let mut meta = MetaAgent::new("test_user", "test_backend");
+        for i in 0..3 {
+            meta.log_event(AgentType::Reasoning, "early input", &format!("early {}", i), "en", 0.95).unwrap();
+        }
+        for i in 0..3 {
+            meta.log_event(AgentType::Synthesis, "late input", &format!("late {}", i), "en", 0.2).unwrap();
+        }
+        meta
+    };
+    // Every event is 4 words -> 6 tokens, so 18 tokens fit three events
+    let mut meta = build();
+    assert_eq!(meta.trace[0].estimate_tokens(), 6);
+
+    meta.truncate_to_context_window_smart(18, 0.9);
+    assert_eq!(meta.get_trace_depth(), 3);
+    assert!(meta.trace.iter().all(|e| e.output.starts_with("early")));
+    assert_eq!(meta.trace[0].output, "early 0");
+    assert_eq!(meta.get_transition_count(), 0);
+
+    // With no quality weight the most recent events win, as in trim_to_context_window
+    let mut meta = build();
+    meta.truncate_to_context_window_smart(18, 0.0);
+    let outputs: Vec<&str> = meta.trace.iter().map(|e| e.output.as_str()).collect();
+    assert_eq!(outputs, vec!["late 0", "late 1", "late 2"]);
+}
+
+#[test]
+fn test_provenance_anonymize() {
+    let mut alice = MetaAgent::new("alice", "gpt-4");
+    alice.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    alice.log_event(AgentType::Synthesis, "input", "output", "en", 0.9).unwrap();
+    let provenance = alice.emit_provenance();
+
+    let anonymized = provenance.anonymize("salt-a");
+    assert!(anonymized.is_anonymized());
+    assert!(!provenance.is_anonymized());
+    assert_eq!(anonymized.contributor_id.len(), "anon_".len() + 8);
+    assert_eq!(anonymized.backend_used, "anonymized");
+    assert_eq!(anonymized.trace_hash, provenance.trace_hash);
+    assert_eq!(anonymized.trace_depth, provenance.trace_depth);
+    assert_eq!(anonymized.uniqueness_score, provenance.uniqueness_score);
+    assert_eq!(anonymized.agent_sequence, provenance.agent_sequence);
+
+    // Deterministic for the same salt, different across salts and contributors
+    assert_eq!(provenance.anonymize("salt-a").contributor_id, anonymized.contributor_id);
+    assert_ne!(provenance.anonymize("salt-b").contributor_id, anonymized.contributor_id);
+
+    let mut bob = MetaAgent::new("bob", "gpt-4");
+    bob.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    assert_ne!(bob.emit_provenance().anonymize("salt-b").contributor_id, anonymized.contributor_id);
+}
+
+#[test]
+fn test_simulate_submission() {
+    let mut leaderboard = Leaderboard::new();
+    for (contributor, depth) in [("alice", 5), ("bob", 10), ("carol", 15)] {
+        let mut meta = MetaAgent::new(contributor, "test_backend");
+        for _ in 0..depth {
+            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+    let before = leaderboard.export_json(RankingCriteria::Combined).unwrap();
+
+    let mut deep = MetaAgent::new("dave", "test_backend");
+    for i in 0..200 {
+        let agent = if i % 2 == 0 { AgentType::Reasoning } else { AgentType::Validation };
+        deep.log_event(agent, "input", "output", "en", 0.95).unwrap();
+    }
+    let result = leaderboard.simulate_submission(&deep.emit_provenance(), &["en".to_string()]);
+    assert_eq!(result.projected_rank, 1);
+    assert_eq!(result.lose_to_count, 0);
+    assert_eq!(result.beat_count + result.lose_to_count, leaderboard.total_contributors());
+    assert_eq!(result.score_gap_to_next, 0.0);
+    assert!(result.score_gap_from_prev > 0.0);
+
+    let mut shallow = MetaAgent::new("erin", "test_backend");
+    shallow.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    let result = leaderboard.simulate_submission(&shallow.emit_provenance(), &["en".to_string()]);
+    assert_eq!(result.beat_count + result.lose_to_count, leaderboard.total_contributors());
+    assert!(result.projected_rank > 1);
+    assert!(result.score_gap_to_next > 0.0);
+
+    // Simulation leaves the leaderboard untouched
+    assert_eq!(leaderboard.total_contributors(), 3);
+    assert_eq!(leaderboard.export_json(RankingCriteria::Combined).unwrap(), before);
+}
+
+#[test]
+fn test_agent_event_builder() {
+    use quantum_limit_graph::level5::{AgentEvent, BuilderError};
+
+    let timestamp = chrono::Utc::now();
+    let mut metadata = HashMap::new();
+    metadata.insert("model".to_string(), "gpt-4".to_string());
+
+    let direct = AgentEvent {
+        timestamp,
+        agent: AgentType::Reasoning,
+        input: "question".to_string(),
+        output: "answer".to_string(),
+        language: "id".to_string(),
+        confidence: 0.8,
+        metadata: metadata.clone(),
+        tags: vec!["draft".to_string()],
+        estimated_cost_usd: 0.0,
+        interned_metadata: Default::default(),
+    };
+    let built = AgentEvent::builder()
+        .timestamp(timestamp)
+        .agent(AgentType::Reasoning)
+        .input("question")
+        .output("answer")
+        .language("id")
+        .confidence(0.8)
+        .metadata(metadata)
+        .tags(vec!["draft".to_string()])
+        .build()
+        .unwrap();
+    assert_eq!(serde_json::to_value(&built).unwrap(), serde_json::to_value(&direct).unwrap());
+
+    let minimal = AgentEvent::builder().agent(AgentType::Synthesis).output("done").build().unwrap();
+    assert_eq!(minimal.language, "en");
+    assert_eq!(minimal.confidence, 1.0);
+    assert!(minimal.input.is_empty());
+    assert!(minimal.metadata.is_empty() && minimal.tags.is_empty());
+
+    assert_eq!(AgentEvent::builder().input("x").build().unwrap_err(), BuilderError::MissingAgent);
+    assert_eq!(
+        AgentEvent::builder().agent(AgentType::Meta).confidence(1.5).build().unwrap_err(),
+        BuilderError::InvalidConfidence
+    );
+}
+
+#[test]
+fn test_per_language_agent_affinity() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    for _ in 0..5 {
+        meta.log_event(AgentType::Translation, "input", "output", "id", 0.9).unwrap();
+    }
+    for _ in 0..5 {
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    }
+
+    let affinity = meta.compute_per_language_agent_affinity();
+    assert_eq!(affinity["id"][&AgentType::Translation], 1.0);
+    assert_eq!(affinity["en"][&AgentType::Reasoning], 1.0);
+    assert_eq!(affinity["id"].len(), 1);
+
+    let fold = meta.fold_memory();
+    assert_eq!(fold.language_agent_affinity["id"]["Translation"], 1.0);
+    assert_eq!(fold.language_agent_affinity["en"]["Reasoning"], 1.0);
+
+    meta.log_event(AgentType::Validation, "input", "output", "id", 0.9).unwrap();
+    let affinity = meta.compute_per_language_agent_affinity();
+    assert!((affinity["id"][&AgentType::Translation] - 5.0 / 6.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_detect_suspicious_entries() {
+    use quantum_limit_graph::level5::SuspicionFlag;
+
+    let submit = |leaderboard: &mut Leaderboard, contributor: &str, depth: usize, uniqueness: f64| {
+        let mut meta = MetaAgent::new(contributor, "test_backend");
+        meta.log_event(AgentType::Reasoning, contributor, "output", "en", 0.9).unwrap();
+        let mut provenance = meta.emit_provenance();
+        provenance.trace_depth = depth;
+        provenance.uniqueness_score = uniqueness;
+        leaderboard.add_entry(provenance, vec!["en".to_string()]);
+    };
+
+    let mut leaderboard = Leaderboard::new();
+    submit(&mut leaderboard, "normal", 12, 0.5);
+    submit(&mut leaderboard, "suspicious", 2, 0.97);
+    assert_eq!(
+        leaderboard.detect_suspicious_entries(),
+        vec![("suspicious".to_string(), vec![SuspicionFlag::HighUniquenessLowDepth])]
+    );
+
+    // Depth outliers need enough contributors for a 3-sigma deviation to exist
+    for i in 0..12 {
+        submit(&mut leaderboard, &format!("peer_{}", i), 10 + i % 3, 0.5);
+    }
+    submit(&mut leaderboard, "outlier", 500, 0.5);
+    let flagged = leaderboard.detect_suspicious_entries();
+    let ids: Vec<&str> = flagged.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids, vec!["outlier", "suspicious"]);
+    assert_eq!(flagged[0].1, vec![SuspicionFlag::UnrealisticDepthRate(500.0)]);
+
+    // Copying another contributor's provenance hash
+    let mut copy = MetaAgent::new("copycat", "test_backend").emit_provenance();
+    copy.trace_hash = leaderboard.get_contributor_history("normal").unwrap()[0].trace_hash.clone();
+    copy.trace_depth = 11;
+    leaderboard.add_entry(copy, vec!["en".to_string()]);
+    let flagged = leaderboard.detect_suspicious_entries();
+    assert!(flagged.contains(&("copycat".to_string(), vec![SuspicionFlag::DuplicateHash])));
+    assert!(flagged.contains(&("normal".to_string(), vec![SuspicionFlag::DuplicateHash])));
+}
+
+#[test]
+fn test_reasoning_velocity() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    meta.log_event(AgentType::Classification, "input", "output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    // Logged back to back, so the session is too short to measure
+    assert_eq!(meta.compute_event_velocity(), None);
+    assert_eq!(meta.compute_reasoning_velocity(), None);
+
+    meta.trace[1].timestamp = meta.trace[0].timestamp + chrono::Duration::minutes(1);
+    assert_eq!(meta.compute_event_velocity(), Some(2.0));
+    assert_eq!(meta.compute_reasoning_velocity(), Some(1.0));
+
+    let report = meta.generate_trace_report();
+    assert_eq!(report.event_velocity, Some(2.0));
+    assert_eq!(report.reasoning_velocity, Some(1.0));
+
+    meta.log_event(AgentType::Synthesis, "input", "output", "en", 0.9).unwrap();
+    meta.trace[2].timestamp = meta.trace[0].timestamp + chrono::Duration::minutes(2);
+    assert_eq!(meta.compute_event_velocity(), Some(1.5));
+}
+
+#[test]
+fn test_rank_by_streak() {
+    let mut leaderboard = Leaderboard::new();
+    let start = chrono::Utc::now() - chrono::Duration::days(10);
+    for (contributor, day_offsets) in [("steady", [0, 1, 2, 3]), ("sporadic", [0, 2, 4, 6])] {
+        for day in day_offsets {
+            let mut meta = MetaAgent::new(contributor, "test_backend");
+            for _ in 0..5 {
+                meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+            }
+            let mut provenance = meta.emit_provenance();
+            provenance.uniqueness_score = 0.5;
+            provenance.timestamp = start + chrono::Duration::days(day);
+            leaderboard.add_entry(provenance, vec!["en".to_string()]);
+        }
+    }
+
+    let streaks = leaderboard.rank_by_streak();
+    assert_eq!(streaks[0].contributor_id, "steady");
+    assert_eq!(streaks[0].streak, 4);
+    assert_eq!(streaks[1].streak, 1);
+
+    let combined = leaderboard.rank_combined();
+    assert_eq!(combined[0].trace_depth, combined[1].trace_depth);
+    assert_eq!(combined[0].uniqueness_score, combined[1].uniqueness_score);
+    assert_eq!(combined[0].contributor_id, "steady");
+    assert!(combined[0].combined_score > combined[1].combined_score);
+
+    leaderboard.compute_streaks();
+    assert_eq!(leaderboard.get_top_n(1, RankingCriteria::Streak)[0].streak, 4);
+}
+
+#[test]
+fn test_transition_frequency_map() {
+    let mut meta = MetaAgent::new("test_user", "test_backend").with_auto_transitions(false);
+    assert_eq!(meta.most_frequent_transition(), None);
+
+    meta.track_transition(AgentType::Classification, AgentType::Reasoning, "route");
+    meta.track_transition(AgentType::Reasoning, AgentType::Synthesis, "summarize");
+    meta.track_transition(AgentType::Classification, AgentType::Reasoning, "route");
+    meta.track_transition(AgentType::Classification, AgentType::Reasoning, "route");
+
+    assert_eq!(
+        meta.get_transition_frequency_map(),
+        vec![
+            ((AgentType::Classification, AgentType::Reasoning), 3),
+            ((AgentType::Reasoning, AgentType::Synthesis), 1),
+        ]
+    );
+    assert_eq!(meta.most_frequent_transition(), Some((AgentType::Classification, AgentType::Reasoning)));
+    assert_eq!(meta.least_frequent_transition(), Some((AgentType::Reasoning, AgentType::Synthesis)));
+}
+
+#[test]
+fn test_export_trace_json_with_language_priority() {
+    let profile = ContributorProfile {
+        contributor_id: "test_user".to_string(),
+        preferred_languages: vec!["id".to_string(), "en".to_string(), "zh".to_string()],
+        expertise_domains: vec![],
+        reasoning_style: "analytical".to_string(),
+        total_traces: 0,
+        avg_trace_depth: 0.0,
+        language_fluency_scores: HashMap::new(),
+        specialization_index: 0.0,
+    };
+    let mut meta = MetaAgent::with_profile("test_user", "test_backend", profile);
+    meta.log_event(AgentType::Classification, "input", "english 1", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input", "french", "fr", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input", "indonesian 1", "id", 0.9).unwrap();
+    meta.log_event(AgentType::Synthesis, "input", "english 2", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Translation, "input", "indonesian 2", "id", 0.9).unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&meta.export_trace_json_with_language_priority().unwrap()).unwrap();
+    let events = json.as_array().unwrap();
+    assert_eq!(events.len(), 5);
+
+    let languages: Vec<&str> = events.iter().map(|e| e["language"].as_str().unwrap()).collect();
+    assert_eq!(languages, vec!["id", "id", "en", "en", "fr"]);
+    let indices: Vec<u64> = events.iter().map(|e| e["index"].as_u64().unwrap()).collect();
+    assert_eq!(indices, vec![2, 4, 0, 3, 1]);
+    for event in events {
+        let original = &meta.trace[event["index"].as_u64().unwrap() as usize];
+        assert_eq!(event["output"], original.output.as_str());
+        assert_eq!(event["timestamp"], serde_json::to_value(original.timestamp).unwrap());
+    }
+}
+
+#[test]
+fn test_max_metadata_bytes() {
+    use quantum_limit_graph::level5::{LogEventError, MetadataLimitError};
+
+    let payload = |bytes: usize| {
+        let mut metadata = HashMap::new();
+        // Serialized as {"data":"xx..."}
+        metadata.insert("data".to_string(), "x".repeat(bytes - r#"{"data":""}"#.len()));
+        metadata
+    };
+
+    let mut meta = MetaAgent::new("test_user", "test_backend").with_max_metadata_bytes(100);
+    meta.log_event_with_metadata(AgentType::Reasoning, "input", "output", "en", 0.9, payload(50)).unwrap();
+    assert_eq!(meta.metadata_bytes_total, 50);
+
+    let result = meta.log_event_with_metadata(AgentType::Reasoning, "input", "output", "en", 0.9, payload(60));
+    assert_eq!(result, Err(LogEventError::MetadataLimit(MetadataLimitError::ExceedsLimit { limit: 100, actual: 110 })));
+    assert_eq!(meta.get_trace_depth(), 1);
+    assert_eq!(meta.metadata_bytes_total, 50);
+
+    // Events without metadata are unaffected
+    meta.log_event(AgentType::Synthesis, "input", "output", "en", 0.9).unwrap();
+    assert_eq!(meta.get_trace_depth(), 2);
+    meta.log_event_with_metadata(AgentType::Reasoning, "input", "output", "en", 0.9, payload(50)).unwrap();
+    assert_eq!(meta.metadata_bytes_total, 100);
+
+    // A translation's automatic source_language entry counts too, and is left out when it does not fit
+    let mut meta = MetaAgent::new("test_user", "test_backend").with_max_metadata_bytes(100);
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Translation, "input", "output", "id", 0.9).unwrap();
+    let source_bytes = r#"{"source_language":"en"}"#.len();
+    assert_eq!(meta.metadata_bytes_total, source_bytes);
+    meta.log_event_with_metadata(AgentType::Reasoning, "input", "output", "id", 0.9, payload(100 - source_bytes)).unwrap();
+    meta.log_event(AgentType::Translation, "input", "output", "en", 0.9).unwrap();
+    assert_eq!(meta.get_trace_depth(), 4);
+    assert!(meta.trace[3].metadata.is_empty());
+    assert_eq!(meta.metadata_bytes_total, 100);
+}
+
+#[test]
+fn test_rank_by_token_efficiency() {
+    let mut leaderboard = Leaderboard::new();
+    let mut concise = MetaAgent::new("concise", "test_backend");
+    let mut verbose = MetaAgent::new("verbose", "test_backend");
+    for _ in 0..10 {
+        concise.log_event(AgentType::Reasoning, "q", "a", "en", 0.9).unwrap();
+        verbose.log_event(
+            AgentType::Reasoning,
+            "a long and winding question that repeats itself at considerable length",
+            "an equally long answer that circles around the point before reaching it",
+            "en",
+            0.9,
+        ).unwrap();
+    }
+    let mut concise_log = concise.emit_provenance();
+    let mut verbose_log = verbose.emit_provenance();
+    concise_log.uniqueness_score = 0.5;
+    verbose_log.uniqueness_score = 0.5;
+    leaderboard.add_entry(verbose_log, vec!["en".to_string()]);
+    leaderboard.add_entry(concise_log, vec!["en".to_string()]);
+
+    let ranked = leaderboard.rank_by_token_efficiency();
+    assert_eq!(ranked[0].contributor_id, "concise");
+    assert_eq!(ranked[0].trace_depth, ranked[1].trace_depth);
+
+    // The efficiency term breaks the tie in the combined score as well
+    assert_eq!(leaderboard.rank_combined()[0].contributor_id, "concise");
+
+    // Contributors without token data count as zero efficiency
+    let mut legacy = MetaAgent::new("legacy", "test_backend").emit_provenance();
+    legacy.total_tokens_estimate = 0;
+    leaderboard.add_entry(legacy, vec!["en".to_string()]);
+    assert_eq!(leaderboard.rank_by_token_efficiency().last().unwrap().contributor_id, "legacy");
+
+    // Efficiency compares per-submission means, so resubmitting does not dilute it:
+    // "steady" (3 tokens per event) stays ahead of "one_shot" (31 tokens over 10 events)
+    let mut leaderboard = Leaderboard::new();
+    for _ in 0..3 {
+        let mut steady = MetaAgent::new("steady", "test_backend");
+        for _ in 0..10 {
+            steady.log_event(AgentType::Reasoning, "q", "a", "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(steady.emit_provenance(), vec!["en".to_string()]);
+    }
+    let mut one_shot = MetaAgent::new("one_shot", "test_backend");
+    for i in 0..10 {
+        let input = if i == 0 { "q r" } else { "q" };
+        one_shot.log_event(AgentType::Reasoning, input, "a", "en", 0.9).unwrap();
+    }
+    leaderboard.add_entry(one_shot.emit_provenance(), vec!["en".to_string()]);
+    assert_eq!(leaderboard.rank_by_token_efficiency()[0].contributor_id, "steady");
+}
+
+#[test]
+fn test_export_openai_messages() {
+    let mut agent = MetaAgent::new("chat_user", "test_backend");
+    agent.log_event(AgentType::Classification, "classify A", "label A", "en", 0.9).unwrap();
+    agent.log_event(AgentType::Reasoning, "why A", "because A", "en", 0.9).unwrap();
+    agent.log_event(AgentType::Classification, "classify B", "label B", "en", 0.9).unwrap();
+    agent.log_event(AgentType::Reasoning, "why B", "because B", "en", 0.9).unwrap();
+    agent.log_event(AgentType::Reasoning, "summarize", "A and B", "en", 0.9).unwrap();
+
+    let messages = agent.export_openai_messages();
+    assert_eq!(messages.len(), 5);
+    let roles: Vec<&str> = messages.iter().map(|m| m["role"].as_str().unwrap()).collect();
+    assert_eq!(roles, vec!["user", "assistant", "user", "assistant", "assistant"]);
+    assert_eq!(messages[0]["content"], "classify A");
+    assert_eq!(messages[1]["content"], "because A");
+
+    let jsonl = agent.export_openai_jsonl().unwrap();
+    assert_eq!(jsonl.lines().count(), 1);
+    let record: serde_json::Value = serde_json::from_str(jsonl.trim_end()).unwrap();
+    assert_eq!(record["messages"].as_array().unwrap().len(), 5);
+}
+
+#[test]
+fn test_time_series_stats() {
+    use chrono::TimeZone;
+    use quantum_limit_graph::level5::TimeSeriesPoint;
+
+    let mut leaderboard = Leaderboard::new();
+    assert!(leaderboard.time_series_stats(3600).is_empty());
+
+    let base = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let submissions = [
+        ("alice", 0, 2, 0.2),
+        ("bob", 30, 4, 0.4),
+        ("alice", 60, 6, 0.6),
+        ("carol", 180, 8, 0.8),
+    ];
+    for (contributor, minutes, depth, uniqueness) in submissions {
+        let mut meta = MetaAgent::new(contributor, "test_backend");
+        for _ in 0..depth {
+            meta.log_event(AgentType::Reasoning, "q", "a", "en", 0.9).unwrap();
+        }
+        let mut provenance = meta.emit_provenance();
+        provenance.timestamp = base + chrono::Duration::minutes(minutes);
+        provenance.uniqueness_score = uniqueness;
+        leaderboard.add_entry(provenance, vec!["en".to_string()]);
+    }
+
+    let series: Vec<TimeSeriesPoint> = leaderboard.time_series_stats(3600);
+    assert_eq!(series.len(), 3);
+
+    // 00:00 bucket holds alice and bob
+    assert_eq!(series[0].timestamp, base);
+    assert_eq!(series[0].total_contributors, 2);
+    assert_eq!(series[0].total_submissions, 2);
+    assert!((series[0].mean_trace_depth - 3.0).abs() < 1e-9);
+    assert!((series[0].mean_uniqueness_score - 0.3).abs() < 1e-9);
+
+    // 01:00 starts a new bucket; alice is not counted twice
+    assert_eq!(series[1].timestamp, base + chrono::Duration::hours(1));
+    assert_eq!(series[1].total_contributors, 2);
+    assert_eq!(series[1].total_submissions, 3);
+    assert!((series[1].mean_trace_depth - 4.0).abs() < 1e-9);
+
+    // Empty 02:00 bucket is skipped
+    assert_eq!(series[2].timestamp, base + chrono::Duration::hours(3));
+    assert_eq!(series[2].total_contributors, 3);
+    assert_eq!(series[2].total_submissions, 4);
+    assert!((series[2].mean_trace_depth - 5.0).abs() < 1e-9);
+    assert!((series[2].mean_uniqueness_score - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_domain_coverage() {
+    let profile = ContributorProfile {
+        contributor_id: "domain_user".to_string(),
+        preferred_languages: vec!["en".to_string()],
+        expertise_domains: vec!["NLP".to_string(), "Physics".to_string(), "Biology".to_string()],
+        reasoning_style: "analytical".to_string(),
+        total_traces: 0,
+        avg_trace_depth: 0.0,
+        language_fluency_scores: HashMap::new(),
+        specialization_index: 0.0,
+    };
+    let mut meta = MetaAgent::with_profile("domain_user", "test_backend", profile);
+    meta.log_event(AgentType::Reasoning, "parse sentence", "tree", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "embed tokens", "vectors", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Synthesis, "combine", "summary", "en", 0.9).unwrap();
+    meta.trace[0].tags = vec!["nlp".to_string()];
+    meta.trace[1].tags = vec!["NLP".to_string(), "draft".to_string()];
+
+    let report = meta.get_domain_coverage();
+    assert_eq!(report.declared_domains.len(), 3);
+    assert_eq!(report.engaged_domains, vec!["NLP".to_string()]);
+    assert!((report.coverage_ratio - 1.0 / 3.0).abs() < 1e-9);
+
+    let mut metadata = HashMap::new();
+    metadata.insert("domain".to_string(), "PHYSICS".to_string());
+    meta.log_event_with_metadata(AgentType::Retrieval, "find paper", "paper", "en", 0.9, metadata).unwrap();
+
+    let report = meta.get_domain_coverage();
+    assert_eq!(report.engaged_domains, vec!["NLP".to_string(), "Physics".to_string()]);
+    assert!((meta.fold_memory().domain_coverage_ratio - 2.0 / 3.0).abs() < 1e-9);
+
+    let bare = MetaAgent::new("no_domains", "test_backend");
+    assert_eq!(bare.get_domain_coverage().coverage_ratio, 0.0);
+}
+
+#[test]
+fn test_profile_merge() {
+    use quantum_limit_graph::level5::ProfileMergeError;
+
+    let laptop = ContributorProfile {
+        contributor_id: "shared_user".to_string(),
+        preferred_languages: vec!["en".to_string(), "id".to_string()],
+        expertise_domains: vec!["NLP".to_string()],
+        reasoning_style: "analytical".to_string(),
+        total_traces: 2,
+        avg_trace_depth: 10.0,
+        language_fluency_scores: HashMap::new(),
+        specialization_index: 0.0,
+    };
+    let server = ContributorProfile {
+        contributor_id: "shared_user".to_string(),
+        preferred_languages: vec!["zh".to_string(), "en".to_string()],
+        expertise_domains: vec!["Physics".to_string(), "NLP".to_string()],
+        reasoning_style: "systematic".to_string(),
+        total_traces: 6,
+        avg_trace_depth: 20.0,
+        language_fluency_scores: HashMap::new(),
+        specialization_index: 0.0,
+    };
+
+    let merged = ContributorProfile::merge(&laptop, &server).unwrap();
+    // en backs 8 traces, zh 6, id 2
+    assert_eq!(merged.preferred_languages, vec!["en", "zh", "id"]);
+    assert_eq!(merged.expertise_domains, vec!["NLP", "Physics"]);
+    assert_eq!(merged.total_traces, 8);
+    assert!((merged.avg_trace_depth - 17.5).abs() < 1e-9);
+    assert_eq!(merged.reasoning_style, "systematic");
+
+    let mut other = server.clone();
+    other.contributor_id = "someone_else".to_string();
+    assert!(matches!(
+        ContributorProfile::merge(&laptop, &other),
+        Err(ProfileMergeError::ContributorIdMismatch)
+    ));
+}
+
+#[test]
+fn test_output_diversity() {
+    let mut repetitive = MetaAgent::new("repetitive", "test_backend");
+    for _ in 0..5 {
+        repetitive.log_event(AgentType::Reasoning, "q", "result", "en", 0.9).unwrap();
+    }
+    // Short of one 50-word window: 1 type, the missing words counting as repeats
+    assert!((repetitive.compute_output_diversity() - 1.0 / 50.0).abs() < 1e-9);
+
+    let mut varied = MetaAgent::new("varied", "test_backend");
+    varied.log_event(AgentType::Reasoning, "q", "alpha beta", "en", 0.9).unwrap();
+    varied.log_event(AgentType::Reasoning, "q", "gamma delta", "en", 0.9).unwrap();
+    assert!((varied.compute_output_diversity() - 4.0 / 50.0).abs() < 1e-9);
+
+    // Case and punctuation are ignored: 5 types
+    varied.log_event(AgentType::Synthesis, "q", "Alpha, epsilon!", "en", 0.9).unwrap();
+    assert!((varied.compute_output_diversity() - 5.0 / 50.0).abs() < 1e-9);
+
+    let by_agent = varied.compute_output_diversity_by_agent();
+    assert!((by_agent[&AgentType::Reasoning] - 4.0 / 50.0).abs() < 1e-9);
+    assert!((by_agent[&AgentType::Synthesis] - 2.0 / 50.0).abs() < 1e-9);
+    assert!(!by_agent.contains_key(&AgentType::Action));
+
+    assert!((varied.fold_memory().output_diversity - 5.0 / 50.0).abs() < 1e-9);
+    assert_eq!(MetaAgent::new("empty", "test_backend").compute_output_diversity(), 0.0);
+
+    // Past one 50-word window, repeating a 50-word vocabulary keeps every window distinct
+    let vocabulary: Vec<String> = (0..50).map(|i| format!("w{}", i)).collect();
+    let mut long = MetaAgent::new("long", "test_backend");
+    for _ in 0..3 {
+        long.log_event(AgentType::Reasoning, "q", &vocabulary.join(" "), "en", 0.9).unwrap();
+    }
+    assert!((long.compute_output_diversity() - 1.0).abs() < 1e-9);
+    assert!((long.fold_memory().output_diversity - 1.0).abs() < 1e-9);
+
+    // Same vocabulary, half of it: every 50-word window holds 25 distinct words
+    let mut narrow = MetaAgent::new("narrow", "test_backend");
+    for _ in 0..6 {
+        narrow.log_event(AgentType::Reasoning, "q", &vocabulary[..25].join(" "), "en", 0.9).unwrap();
+    }
+    assert!((narrow.compute_output_diversity() - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_rank_by_output_diversity() {
+    let mut leaderboard = Leaderboard::new();
+
+    let mut repetitive = MetaAgent::new("repetitive", "test_backend");
+    let mut varied = MetaAgent::new("varied", "test_backend");
+    let outputs = ["entropy rises", "gradients vanish", "tokens compress", "models drift"];
+    for output in outputs {
+        repetitive.log_event(AgentType::Reasoning, "q", "same answer", "en", 0.9).unwrap();
+        varied.log_event(AgentType::Reasoning, "q", output, "en", 0.9).unwrap();
+    }
+
+    let varied_log = varied.emit_provenance();
+    assert!((varied_log.output_diversity - 8.0 / 50.0).abs() < 1e-9);
+    leaderboard.add_entry(repetitive.emit_provenance(), vec!["en".to_string()]);
+    leaderboard.add_entry(varied_log, vec!["en".to_string()]);
+
+    let ranked = leaderboard.rank_by_output_diversity();
+    assert_eq!(ranked[0].contributor_id, "varied");
+    assert!((ranked[1].output_diversity_score - 2.0 / 50.0).abs() < 1e-9);
+    assert_eq!(leaderboard.get_top_n(1, RankingCriteria::OutputDiversity)[0].contributor_id, "varied");
+    assert_eq!(RankingCriteria::OutputDiversity.to_string(), "Output Diversity");
+}
+
+#[test]
+fn test_infer_primary_task() {
+    use quantum_limit_graph::level5::Task;
+
+    let session = |agents: &[AgentType]| {
+        let mut meta = MetaAgent::new("task_user", "test_backend");
+        for agent in agents {
+            meta.log_event(agent.clone(), "input", "output", "en", 0.9).unwrap();
+        }
+        meta
+    };
+
+    assert_eq!(MetaAgent::new("empty", "test_backend").infer_primary_task(), Task::Unknown);
+
+    let translation = session(&[AgentType::Translation, AgentType::Translation, AgentType::Reasoning, AgentType::Reasoning, AgentType::Translation]);
+    assert_eq!(translation.infer_primary_task(), Task::Translation);
+
+    let classification = session(&[AgentType::Classification, AgentType::Classification, AgentType::Classification, AgentType::Reasoning]);
+    assert_eq!(classification.infer_primary_task(), Task::Classification);
+
+    // Synthesis 40%, Retrieval 30%
+    let research = session(&[
+        AgentType::Retrieval, AgentType::Retrieval, AgentType::Retrieval,
+        AgentType::Synthesis, AgentType::Synthesis, AgentType::Synthesis, AgentType::Synthesis,
+        AgentType::Reasoning, AgentType::Reasoning, AgentType::Reasoning,
+    ]);
+    assert_eq!(research.infer_primary_task(), Task::Research);
+
+    let validation = session(&[AgentType::Validation, AgentType::Validation, AgentType::Validation, AgentType::Reasoning, AgentType::Action]);
+    assert_eq!(validation.infer_primary_task(), Task::Validation);
+
+    let multi_step = session(&[
+        AgentType::Reasoning, AgentType::Action, AgentType::Meta, AgentType::Validation,
+        AgentType::Reasoning, AgentType::Action, AgentType::Meta,
+    ]);
+    assert_eq!(multi_step.infer_primary_task(), Task::MultiStep);
+    assert_eq!(multi_step.fold_memory().inferred_task, Task::MultiStep);
+
+    let lopsided = session(&[AgentType::Reasoning, AgentType::Reasoning, AgentType::Reasoning, AgentType::Action]);
+    assert_eq!(lopsided.infer_primary_task(), Task::Unknown);
+}
+
+#[test]
+fn test_report_card_grades() {
+    let mut leaderboard = Leaderboard::new();
+    let mut meta = MetaAgent::new("graded", "test_backend");
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    let mut stats = leaderboard.entries["graded"].clone();
+
+    // A lone contributor tops the board
+    assert_eq!(stats.to_report_card().grade, 'A');
+
+    for (percentile, grade) in [
+        (1.0, 'A'), (0.9, 'A'),
+        (0.89, 'B'), (0.7, 'B'),
+        (0.69, 'C'), (0.4, 'C'),
+        (0.39, 'D'), (0.2, 'D'),
+        (0.19, 'F'), (0.0, 'F'),
+    ] {
+        stats.percentile_rank = percentile;
+        assert_eq!(stats.to_report_card().grade, grade, "percentile {}", percentile);
+    }
+
+    // A single shallow, monolingual submission has plenty to work on
+    let card = stats.to_report_card();
+    assert!(card.summary.contains("graded"));
+    assert!(card.areas_for_improvement.iter().any(|a| a.contains("longer reasoning traces")));
+    assert!(card.areas_for_improvement.iter().any(|a| a.contains("more than one language")));
+
+    stats.trace_depth = 60;
+    stats.languages_used = vec!["en".to_string(), "id".to_string(), "zh".to_string()];
+    let card = stats.to_report_card();
+    assert!(card.strengths.iter().any(|s| s.contains("max depth 60")));
+    assert!(card.strengths.iter().any(|s| s.contains("3 languages")));
+
+    let markdown = card.to_markdown();
+    assert!(markdown.starts_with("# Report Card\n\n**Grade: F**"));
+    assert!(markdown.contains("## Strengths\n\n- Deep reasoning traces"));
+    assert!(markdown.contains("## Areas for Improvement\n\n- "));
+
+    // Grades follow rank, not absolute score: of 11 contributors the deepest gets
+    // an A and the shallowest an F even though every combined score is low
+    let mut leaderboard = Leaderboard::new();
+    for depth in 1..=11 {
+        let mut meta = MetaAgent::new(&format!("user{:02}", depth), "test_backend");
+        for _ in 0..depth {
+            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+    let grades: Vec<char> = leaderboard.rank_combined().iter().map(|s| s.to_report_card().grade).collect();
+    assert_eq!(grades, vec!['A', 'A', 'B', 'B', 'C', 'C', 'C', 'D', 'D', 'F', 'F']);
+    assert!(leaderboard.entries["user11"].combined_score < 0.9);
+}
+
+#[test]
+fn test_detect_reasoning_loops() {
+    use quantum_limit_graph::level5::ReasoningLoop;
+
+    let mut meta = MetaAgent::new("loop_user", "test_backend");
+    meta.log_event(AgentType::Classification, "input", "output", "en", 0.9).unwrap();
+    for _ in 0..3 {
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        meta.log_event(AgentType::Validation, "input", "output", "en", 0.9).unwrap();
+    }
+    meta.log_event(AgentType::Synthesis, "input", "output", "en", 0.9).unwrap();
+
+    // Path: Classification, (Reasoning, Validation) x3, Synthesis
+    let loops = meta.detect_reasoning_loops(2, 3);
+    assert_eq!(loops, vec![ReasoningLoop {
+        cycle: vec![AgentType::Reasoning, AgentType::Validation],
+        repetitions: 3,
+        start_index: 1,
+        end_index: 5,
+    }]);
+    assert_eq!(meta.transitions[1].from_agent, AgentType::Reasoning);
+    assert_eq!(meta.transitions[5].to_agent, AgentType::Validation);
+
+    assert!(meta.detect_reasoning_loops(2, 4).is_empty());
+    // Longer non-primitive cycles such as (R, V, R, V) are not reported separately
+    assert_eq!(meta.detect_reasoning_loops(2, 2).len(), 1);
+
+    let fold = meta.fold_memory();
+    assert_eq!(fold.detected_loops, 1);
+    assert!(fold.key_insights.contains(&"Warning: 1 reasoning loops detected".to_string()));
+
+    let mut linear = MetaAgent::new("linear_user", "test_backend");
+    for agent in [AgentType::Retrieval, AgentType::Reasoning, AgentType::Synthesis] {
+        linear.log_event(agent, "input", "output", "en", 0.9).unwrap();
+    }
+    assert!(linear.detect_reasoning_loops(2, 2).is_empty());
+    assert_eq!(linear.fold_memory().detected_loops, 0);
+}
+
+#[test]
+fn test_global_percentile_ranks() {
+    let mut leaderboard = Leaderboard::new();
+    let mut solo = MetaAgent::new("user_0", "test_backend");
+    solo.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    leaderboard.add_entry(solo.emit_provenance(), vec!["en".to_string()]);
+    assert_eq!(leaderboard.compute_global_percentile_ranks(RankingCriteria::TraceDepth)["user_0"], 1.0);
+    assert_eq!(leaderboard.entries["user_0"].percentile_rank, 1.0);
+
+    for i in 1..10 {
+        let mut meta = MetaAgent::new(&format!("user_{}", i), "test_backend");
+        for _ in 0..=i {
+            meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+
+    let percentiles = leaderboard.compute_global_percentile_ranks(RankingCriteria::TraceDepth);
+    assert_eq!(percentiles.len(), 10);
+    assert_eq!(percentiles["user_9"], 1.0);
+    assert_eq!(percentiles["user_0"], 0.0);
+    assert!((percentiles["user_5"] - 5.0 / 9.0).abs() < 1e-9);
+
+    // Stored percentiles follow the combined-score ranks
+    for stats in leaderboard.entries.values() {
+        assert!((stats.percentile_rank - (10 - stats.rank) as f64 / 9.0).abs() < 1e-9);
+    }
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_trace_parquet_round_trip() {
+    use quantum_limit_graph::level5::CostModel;
+
+    let mut meta = MetaAgent::new("parquet_user", "test_backend").with_cost_model(CostModel::openai_gpt4());
+    let mut metadata = HashMap::new();
+    metadata.insert("source".to_string(), "arxiv".to_string());
+    meta.log_event(AgentType::Retrieval, "find papers", "3 papers", "en", 0.8).unwrap();
+    meta.log_event_with_metadata(AgentType::Reasoning, "compare", "paper 2 is strongest", "en", 0.9, metadata).unwrap();
+    meta.log_event(AgentType::Translation, "translate", "makalah 2 paling kuat", "id", 0.85).unwrap();
+    meta.log_event(AgentType::Validation, "check", "consistent", "en", 0.95).unwrap();
+    meta.log_event(AgentType::Synthesis, "summarize", "paper 2 wins", "en", 0.9).unwrap();
+    meta.trace[0].tags = vec!["search".to_string(), "draft".to_string()];
+
+    let bytes = meta.export_trace_parquet().unwrap();
+    assert!(bytes.starts_with(b"PAR1"));
+
+    let reader = parquet::file::reader::SerializedFileReader::new(bytes::Bytes::from(bytes.clone())).unwrap();
+    let metadata = parquet::file::reader::FileReader::metadata(&reader);
+    assert_eq!(metadata.file_metadata().num_rows(), 5);
+    let columns: Vec<String> = metadata.file_metadata().schema_descr().root_schema().get_fields()
+        .iter()
+        .map(|f| f.name().to_string())
+        .collect();
+    assert_eq!(
+        columns,
+        vec!["timestamp", "agent", "input", "output", "language", "confidence", "metadata", "tags", "estimated_cost_usd"],
+    );
+
+    let restored = MetaAgent::import_trace_parquet(&bytes).unwrap();
+    assert_eq!(restored.contributor_id, "parquet_user");
+    assert_eq!(restored.session_id, meta.session_id);
+    assert_eq!(restored.get_trace_depth(), 5);
+    assert_eq!(restored.get_transition_count(), meta.get_transition_count());
+    for (original, event) in meta.trace.iter().zip(&restored.trace) {
+        // Parquet stores microsecond precision
+        assert_eq!(event.timestamp.timestamp_micros(), original.timestamp.timestamp_micros());
+        assert_eq!(event.agent, original.agent);
+        assert_eq!(event.output, original.output);
+        assert_eq!(event.language, original.language);
+        assert_eq!(event.confidence, original.confidence);
+        assert_eq!(event.tags, original.tags);
+        assert_eq!(event.estimated_cost_usd, original.estimated_cost_usd);
+    }
+    assert_eq!(restored.trace[1].metadata["source"], "arxiv");
+    assert!(restored.total_estimated_cost_usd() > 0.0);
+}
+
+#[test]
+fn test_confidence_outliers() {
+    let mut meta = MetaAgent::new("outlier_user", "test_backend");
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.1).unwrap();
+    // Too few events for a meaningful spread
+    assert!(meta.get_confidence_outliers(1.0).is_empty());
+
+    for confidence in [0.88, 0.9, 0.92, 0.89, 0.91, 0.9, 0.87, 0.93] {
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", confidence).unwrap();
+    }
+    let outliers = meta.get_confidence_outliers(2.0);
+    assert_eq!(outliers.len(), 1);
+    assert_eq!(outliers[0].0, 1);
+    assert_eq!(outliers[0].1.confidence, 0.1);
+
+    let stats = meta.confidence_stats();
+    assert_eq!(stats.min, 0.1);
+    assert_eq!(stats.max, 0.93);
+    assert_eq!(stats.p50, 0.9);
+    assert_eq!(stats.p95, 0.93);
+    assert!((stats.mean - 0.82).abs() < 1e-9);
+    assert!(stats.stddev > 0.2 && stats.stddev < 0.3);
+
+    let mut flat = MetaAgent::new("flat_user", "test_backend");
+    for _ in 0..5 {
+        flat.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    }
+    assert!(flat.get_confidence_outliers(0.5).is_empty());
+    assert_eq!(MetaAgent::new("empty", "test_backend").confidence_stats().mean, 0.0);
+}
+
+#[test]
+fn test_bulk_update_profiles() {
+    let mut leaderboard = Leaderboard::new();
+    for (contributor, agents) in [
+        ("retriever", [AgentType::Retrieval, AgentType::Reasoning, AgentType::Retrieval]),
+        ("validator", [AgentType::Validation, AgentType::Reasoning, AgentType::Validation]),
+    ] {
+        let mut meta = MetaAgent::new(contributor, "test_backend");
+        for (i, agent) in agents.into_iter().enumerate() {
+            let (language, confidence) = if i == 0 { ("id", 0.95) } else { ("en", 0.6) };
+            meta.log_event(agent, "input", "output", language, confidence).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+
+    let profile = &leaderboard.entries["retriever"].profile;
+    assert_eq!(profile.contributor_id, "retriever");
+    assert_eq!(profile.total_traces, 1);
+    assert_eq!(profile.preferred_languages, vec!["en", "id"]);
+
+    // Simulate profiles produced by an older inference rule
+    for stats in leaderboard.entries.values_mut() {
+        stats.profile.reasoning_style = "legacy".to_string();
+        stats.profile.expertise_domains = vec!["NLP".to_string()];
+        stats.streak = 9;
+    }
+
+    leaderboard.bulk_update_profiles();
+    for (contributor_id, stats) in &leaderboard.entries {
+        assert_eq!(&stats.profile.contributor_id, contributor_id);
+        assert_eq!(stats.profile.reasoning_style, ContributorProfile::infer_reasoning_style(&stats.confidence_profile));
+        assert_eq!(stats.profile.expertise_domains, vec!["NLP".to_string()]);
+        assert_eq!(stats.streak, 9);
+    }
+    assert_eq!(leaderboard.entries["retriever"].profile.reasoning_style, "empirical");
+    assert_eq!(leaderboard.entries["validator"].profile.reasoning_style, "systematic");
+}
+
+#[test]
+fn test_summarize_transitions() {
+    let mut meta = MetaAgent::new("narrative_user", "test_backend");
+    assert_eq!(meta.fold_memory().transition_narrative, "The session recorded no events.");
+
+    let agents = [
+        AgentType::Classification,
+        AgentType::Reasoning,
+        AgentType::Reasoning,
+        AgentType::Retrieval,
+        AgentType::Reasoning,
+        AgentType::Validation,
+        AgentType::Synthesis,
+    ];
+    for agent in agents.iter() {
+        meta.log_event(agent.clone(), "input", "output", "en", 0.9).unwrap();
+    }
+
+    let fold = meta.fold_memory();
+    assert_eq!(
+        fold.transition_narrative,
+        "The session began with Classification, transitioned 5 times through Reasoning and Retrieval, \
+         and concluded with Synthesis after a Validation pass."
+    );
+    assert_eq!(fold.summarize_transitions(), fold.transition_narrative);
+    for agent in agents.iter() {
+        assert!(fold.transition_narrative.contains(&agent.to_string()));
+    }
+
+    let mut single = MetaAgent::new("single_user", "test_backend");
+    single.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    single.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+    assert_eq!(single.fold_memory().transition_narrative, "The session consisted solely of Reasoning (2 events).");
+}
+
+#[test]
+fn test_session_fingerprint() {
+    let build = |final_output: &str| {
+        let mut meta = MetaAgent::new("fingerprint_user", "test_backend");
+        meta.log_event(AgentType::Retrieval, "find sources", "two papers", "en", 0.9).unwrap();
+        meta.log_event(AgentType::Synthesis, "combine", final_output, "en", 0.9).unwrap();
+        meta
+    };
+
+    let original = build("consensus reached");
+    let duplicate = build("consensus reached");
+    let divergent = build("consensus reachEd");
+
+    let fingerprint = original.get_session_fingerprint();
+    assert_eq!(fingerprint.len(), 12);
+    assert!(fingerprint.chars().all(|c| c.is_ascii_hexdigit()));
+    assert!(original.emit_provenance().trace_hash.starts_with(&fingerprint));
+
+    // Fingerprints depend only on event content
+    assert_eq!(duplicate.get_session_fingerprint(), fingerprint);
+    assert_ne!(divergent.get_session_fingerprint(), fingerprint);
+}
+
+#[test]
+fn test_export_langchain_compatible_json() {
+    let mut meta = MetaAgent::new("langchain_user", "test_backend");
+    meta.log_event(AgentType::Retrieval, "find docs", "two docs", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "compare", "doc 1 wins", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Synthesis, "summarize", "use doc 1", "en", 0.9).unwrap();
+    let base = meta.trace[0].timestamp;
+    meta.trace[1].timestamp = base + chrono::Duration::milliseconds(400);
+    meta.trace[2].timestamp = base + chrono::Duration::milliseconds(1000);
+
+    let json = meta.export_langchain_compatible_json().unwrap();
+    let runs: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let runs = runs.as_array().unwrap();
+    assert_eq!(runs.len(), meta.get_trace_depth());
+
+    for run in runs {
+        for key in ["run_id", "name", "inputs", "outputs", "run_type", "start_time", "end_time"] {
+            assert!(run.get(key).is_some(), "missing {}", key);
+        }
+        assert_eq!(run["run_type"], "tool");
+    }
+    assert_eq!(runs[0]["run_id"], format!("{}_0", meta.session_id));
+    assert_eq!(runs[0]["name"], "Retrieval");
+    assert_eq!(runs[0]["inputs"]["text"], "find docs");
+    assert_eq!(runs[1]["outputs"]["text"], "doc 1 wins");
+    assert_eq!(runs[0]["end_time"], runs[1]["start_time"]);
+
+    // The last run lasts as long as the 600ms gap before it
+    let parse = |v: &serde_json::Value| chrono::DateTime::parse_from_rfc3339(v.as_str().unwrap()).unwrap();
+    assert_eq!((parse(&runs[2]["end_time"]) - parse(&runs[2]["start_time"])).num_milliseconds(), 600);
+}
+
+#[test]
+fn test_tournament_rank() {
+    let mut leaderboard = Leaderboard::new();
+    for contributor in ["alice", "bob", "carol", "dave"] {
+        let mut meta = MetaAgent::new(contributor, "test_backend");
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+
+    // (depth, uniqueness, submissions, avg depth)
+    let grid = [
+        ("alice", 10, 0.90, 5, 8.0),
+        ("bob", 8, 0.80, 6, 7.0),
+        ("carol", 5, 0.50, 2, 4.0),
+        // dave tops uniqueness but loses every other dimension
+        ("dave", 3, 0.95, 1, 2.0),
+    ];
+    for (contributor, depth, uniqueness, submissions, avg_depth) in grid {
+        let stats = leaderboard.entries.get_mut(contributor).unwrap();
+        stats.trace_depth = depth;
+        stats.weighted_trace_depth = depth as f64;
+        stats.uniqueness_score = uniqueness;
+        stats.total_submissions = submissions;
+        stats.avg_trace_depth = avg_depth;
+    }
+
+    let order: Vec<&str> = leaderboard.tournament_rank().iter().map(|s| s.contributor_id.as_str()).collect();
+    assert_eq!(order, vec!["alice", "bob", "carol", "dave"]);
+    assert_eq!(leaderboard.get_top_n(1, RankingCriteria::Tournament)[0].contributor_id, "alice");
+
+    // alice and bob no longer beat each other; their equal win counts fall back to combined score
+    let bob = leaderboard.entries.get_mut("bob").unwrap();
+    bob.trace_depth = 10;
+    bob.weighted_trace_depth = 10.0;
+    bob.avg_trace_depth = 8.0;
+    let winners: Vec<&str> = leaderboard.tournament_rank().iter().take(2).map(|s| s.contributor_id.as_str()).collect();
+    assert_eq!(winners, vec!["alice", "bob"]);
+}
+
+#[cfg(feature = "redis")]
+#[test]
+fn test_trace_redis_stream_round_trip() {
+    use redis::Value;
+    use redis_test::{MockCmd, MockRedisConnection};
+
+    use quantum_limit_graph::level5::CostModel;
+
+    let mut meta = MetaAgent::new("redis_user", "test_backend").with_cost_model(CostModel::openai_gpt4());
+    meta.log_event(AgentType::Retrieval, "find papers", "3 papers", "en", 0.8).unwrap();
+    meta.log_event(AgentType::Translation, "translate", "makalah 2 paling kuat", "id", 0.85).unwrap();
+    meta.log_event(AgentType::Synthesis, "summarize", "paper 2 wins", "en", 0.9).unwrap();
+    meta.trace[0].metadata.insert("source".to_string(), "arxiv".to_string());
+    meta.trace[1].tags = vec!["search".to_string(), "draft".to_string()];
+
+    let fields: Vec<Vec<(&str, String)>> = meta
+        .trace
+        .iter()
+        .map(|e| {
+            vec![
+                ("timestamp", e.timestamp.to_rfc3339()),
+                ("agent", e.agent.to_string()),
+                ("input", e.input.clone()),
+                ("output", e.output.clone()),
+                ("language", e.language.clone()),
+                ("confidence", e.confidence.to_string()),
+                ("metadata", serde_json::to_string(&e.metadata).unwrap()),
+                ("tags", serde_json::to_string(&e.tags).unwrap()),
+                ("estimated_cost_usd", e.estimated_cost_usd.to_string()),
+            ]
+        })
+        .collect();
+    let ids: Vec<String> = (0..fields.len()).map(|i| format!("1700000000000-{}", i)).collect();
+
+    let mut commands: Vec<MockCmd> = fields
+        .iter()
+        .zip(&ids)
+        .map(|(f, id)| MockCmd::new(redis::cmd("XADD").arg("trace").arg("*").arg(f), Ok(id.as_str())))
+        .collect();
+    let entries = fields
+        .iter()
+        .zip(&ids)
+        .map(|(f, id)| {
+            let flat = f
+                .iter()
+                .flat_map(|(k, v)| [Value::BulkString(k.as_bytes().to_vec()), Value::BulkString(v.as_bytes().to_vec())])
+                .collect();
+            Value::Array(vec![Value::BulkString(id.as_bytes().to_vec()), Value::Array(flat)])
+        })
+        .collect();
+    commands.push(MockCmd::new(redis::cmd("XRANGE").arg("trace").arg("-").arg("+"), Ok(Value::Array(entries))));
+    let mut conn = MockRedisConnection::new(commands);
+
+    for (event, id) in meta.trace.iter().zip(&ids) {
+        assert_eq!(&meta.push_event_to_redis_stream(event, &mut conn, "trace").unwrap(), id);
+    }
+    let restored = MetaAgent::read_trace_from_redis_stream(&mut conn, "trace", "redis_user", "test_backend").unwrap();
+
+    assert_eq!(restored.trace.len(), meta.trace.len());
+    for (a, b) in restored.trace.iter().zip(&meta.trace) {
+        assert_eq!(a.timestamp, b.timestamp);
+        assert_eq!(a.agent, b.agent);
+        assert_eq!(a.input, b.input);
+        assert_eq!(a.output, b.output);
+        assert_eq!(a.language, b.language);
+        assert_eq!(a.confidence, b.confidence);
+        assert_eq!(a.metadata, b.metadata);
+        assert_eq!(a.tags, b.tags);
+    }
+    assert_eq!(restored.total_estimated_cost_usd(), meta.total_estimated_cost_usd());
+    assert_eq!(restored.get_transition_count(), 2);
+}
+
+#[test]
+fn test_weighted_trace_depth() {
+    let mut synthesis = MetaAgent::new("synth_user", "test_backend");
+    let mut classification = MetaAgent::new("class_user", "test_backend");
+    for i in 0..10 {
+        synthesis.log_event(AgentType::Synthesis, "combine", &format!("summary {}", i), "en", 0.9).unwrap();
+        classification.log_event(AgentType::Classification, "label", &format!("label {}", i), "en", 0.9).unwrap();
+    }
+
+    assert!((synthesis.compute_weighted_trace_depth() - 15.0).abs() < 1e-9);
+    assert!((classification.compute_weighted_trace_depth() - 8.0).abs() < 1e-9);
+    assert_eq!(synthesis.get_trace_depth(), classification.get_trace_depth());
+
+    let mut leaderboard = Leaderboard::new();
+    leaderboard.add_entry(classification.emit_provenance(), vec!["en".to_string()]);
+    leaderboard.add_entry(synthesis.emit_provenance(), vec!["en".to_string()]);
+    let ranked = leaderboard.rank_by_depth();
+    assert_eq!(ranked[0].contributor_id, "synth_user");
+    assert!(ranked[0].weighted_trace_depth > ranked[1].weighted_trace_depth);
+
+    // Logs without a weighted depth (e.g. from credentials) rank on raw depth
+    let mut legacy = classification.emit_provenance();
+    legacy.contributor_id = "legacy_user".to_string();
+    legacy.weighted_trace_depth = 0.0;
+    leaderboard.add_entry(legacy, vec!["en".to_string()]);
+    assert_eq!(leaderboard.entries["legacy_user"].weighted_trace_depth, 0.0);
+    let order: Vec<&str> = leaderboard.rank_by_depth().iter().map(|s| s.contributor_id.as_str()).collect();
+    assert_eq!(order, vec!["synth_user", "legacy_user", "class_user"]);
+
+    // The weighted depth is signed
+    let signer = quantum_limit_graph::level5::ProvenanceSigner::new(&[5u8; 32]);
+    let mut signed = synthesis.emit_provenance();
+    signer.sign(&mut signed);
+    signed.weighted_trace_depth = 100.0;
+    assert!(quantum_limit_graph::level5::ProvenanceSigner::verify(&signed, &signer.public_key()).is_err());
+}
+
+#[test]
+fn test_load_from_jsonlines_round_trip() {
+    use quantum_limit_graph::level5::LoadError;
+
+    let mut leaderboard = Leaderboard::new();
+    for (contributor, depth) in [("alice", 6), ("bob", 3), ("carol", 9)] {
+        let mut meta = MetaAgent::new(contributor, "test_backend");
+        for i in 0..depth {
+            meta.log_event(AgentType::Reasoning, "input", &format!("step {}", i), "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+    let dump = leaderboard.export_jsonlines().unwrap();
+    assert_eq!(dump.lines().count(), 3);
+
+    let restored = Leaderboard::load_from_jsonlines(std::io::Cursor::new(dump.as_bytes())).unwrap();
+    assert_eq!(restored.total_contributors(), 3);
+    for (id, stats) in &leaderboard.entries {
+        let loaded = &restored.entries[id];
+        assert_eq!(loaded.rank, stats.rank);
+        assert_eq!(loaded.trace_depth, stats.trace_depth);
+        assert_eq!(loaded.provenance_hash, stats.provenance_hash);
+        assert_eq!(restored.get_contributor_history(id).unwrap().len(), 1);
+    }
+
+    // Good rows on either side of a corrupt line survive
+    let mut lines: Vec<&str> = dump.lines().collect();
+    lines.insert(1, "not json");
+    let corrupted = lines.join("\n");
+    match Leaderboard::load_from_jsonlines(std::io::Cursor::new(corrupted.as_bytes())) {
+        Err(LoadError::PartialLoad { leaderboard: partial, loaded, failed }) => {
+            assert_eq!(loaded, 3);
+            assert_eq!(failed, 1);
+            assert_eq!(partial.total_contributors(), 3);
+            for (id, stats) in &leaderboard.entries {
+                assert_eq!(partial.entries[id].rank, stats.rank);
+                assert_eq!(partial.get_contributor_history(id).unwrap().len(), 1);
+            }
+        }
+        other => panic!("expected partial load, got {:?}", other.map(|l| l.total_contributors())),
+    }
+}
+
+#[test]
+fn test_semantic_coherence_proxy() {
+    let mut coherent = MetaAgent::new("coherent_user", "test_backend");
+    coherent.log_event(AgentType::Retrieval, "search", "protein folding energy landscape", "en", 0.9).unwrap();
+    coherent.log_event(AgentType::Reasoning, "analyze", "protein folding energy minimum", "en", 0.9).unwrap();
+    coherent.log_event(AgentType::Synthesis, "summarize", "Protein folding energy minimum found.", "en", 0.9).unwrap();
+
+    let mut scattered = MetaAgent::new("scattered_user", "test_backend");
+    scattered.log_event(AgentType::Retrieval, "search", "protein folding energy landscape", "en", 0.9).unwrap();
+    scattered.log_event(AgentType::Reasoning, "analyze", "stock market volatility", "en", 0.9).unwrap();
+    scattered.log_event(AgentType::Synthesis, "summarize", "medieval poetry meter", "en", 0.9).unwrap();
+
+    // 3/5 then 4/5 shared words (punctuation and case ignored)
+    assert!((coherent.compute_semantic_coherence_proxy() - 0.7).abs() < 1e-9);
+    assert_eq!(scattered.compute_semantic_coherence_proxy(), 0.0);
+    assert!(coherent.fold_memory().semantic_coherence_proxy > scattered.fold_memory().semantic_coherence_proxy);
+
+    let mut single = MetaAgent::new("single_user", "test_backend");
+    single.log_event(AgentType::Reasoning, "analyze", "protein folding", "en", 0.9).unwrap();
+    assert_eq!(single.compute_semantic_coherence_proxy(), 0.0);
+}
+
+#[cfg(feature = "kafka")]
+#[test]
+fn test_kafka_producer_messages() {
+    use quantum_limit_graph::level5::{ContributorStats, KafkaError, LeaderboardProducer};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Default)]
+    struct MockProducer {
+        messages: Mutex<Vec<(String, String, String)>>,
+        flushes: Mutex<usize>,
+    }
+
+    impl LeaderboardProducer for MockProducer {
+        fn send(&self, topic: &str, key: &str, payload: &str) -> Result<(), KafkaError> {
+            self.messages.lock().unwrap().push((topic.to_string(), key.to_string(), payload.to_string()));
+            Ok(())
+        }
+
+        fn flush(&self) -> Result<(), KafkaError> {
+            *self.flushes.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    let mock = Arc::new(MockProducer::default());
+    let mut leaderboard = Leaderboard::with_producer(mock.clone(), "leaderboard");
+    for i in 0..5 {
+        let mut meta = MetaAgent::new(&format!("user{}", i), "test_backend");
+        for step in 0..=i {
+            meta.log_event(AgentType::Reasoning, "input", &format!("step {}", step), "en", 0.9).unwrap();
+        }
+        leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string()]);
+    }
+    leaderboard.flush_kafka().unwrap();
+
+    let messages = mock.messages.lock().unwrap();
+    let stats_messages: Vec<_> = messages.iter().filter(|(topic, _, _)| topic == "leaderboard").collect();
+    assert_eq!(stats_messages.len(), 5);
+    for (i, (_, key, payload)) in stats_messages.iter().enumerate() {
+        let stats: ContributorStats = serde_json::from_str(payload).unwrap();
+        assert_eq!(key, &format!("user{}", i));
+        assert_eq!(&stats.contributor_id, key);
+    }
+
+    // Every newcomer enters the ranks, so rank changes are published alongside
+    let rank_messages = messages.iter().filter(|(topic, _, _)| topic == "leaderboard.ranks").count();
+    assert!(rank_messages >= 5);
+    assert_eq!(*mock.flushes.lock().unwrap(), 1);
+}
+
+#[test]
+fn test_confidence_calibration() {
+    use quantum_limit_graph::level5::ConfidenceCalibrator;
+
+    let mut meta = MetaAgent::new("calibration_user", "test_backend");
+    for i in 0..10 {
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.5 + 0.05 * i as f64).unwrap();
+    }
+
+    let calibrator = ConfidenceCalibrator::from_trace(&meta.trace);
+    assert!((calibrator.mean - 0.725).abs() < 1e-9);
+    assert!((calibrator.calibrate(calibrator.mean) - 0.5).abs() < 1e-9);
+
+    meta.apply_confidence_calibration(&calibrator);
+    let calibrated: Vec<f64> = meta.trace.iter().map(|e| e.confidence).collect();
+    let mean = calibrated.iter().sum::<f64>() / calibrated.len() as f64;
+    assert!((mean - 0.5).abs() < 1e-6);
+    assert!(calibrated.iter().all(|c| *c > 0.0 && *c < 1.0));
+    assert!(calibrated.windows(2).all(|w| w[0] < w[1]));
+
+    // A zero-spread reference carries no information
+    let flat = ConfidenceCalibrator { mean: 0.8, stddev: 0.0 };
+    assert_eq!(flat.calibrate(0.3), 0.5);
+}
+
+#[test]
+fn test_specialization_index() {
+    let mut specialist = MetaAgent::new("specialist", "test_backend");
+    let mut generalist = MetaAgent::new("generalist", "test_backend");
+    for i in 0..8 {
+        specialist.log_event(AgentType::Reasoning, "input", &format!("step {}", i), "en", 0.9).unwrap();
+    }
+    for agent in AgentType::all() {
+        generalist.log_event(agent, "input", "output", "en", 0.9).unwrap();
+    }
+
+    let profile = &specialist.profile;
+    assert!((profile.compute_specialization_index(&specialist.compute_agent_stats()) - 1.0).abs() < 1e-9);
+    assert!(profile.compute_specialization_index(&generalist.compute_agent_stats()).abs() < 1e-9);
+    assert_eq!(profile.compute_specialization_index(&[]), 0.0);
+
+    specialist.update_profile();
+    generalist.update_profile();
+    assert!((specialist.profile.specialization_index - 1.0).abs() < 1e-9);
+    assert!(generalist.profile.specialization_index < 1e-9);
+
+    let mut leaderboard = Leaderboard::new();
+    leaderboard.add_entry(generalist.emit_provenance(), vec!["en".to_string()]);
+    leaderboard.add_entry(specialist.emit_provenance(), vec!["en".to_string()]);
+    let ranked = leaderboard.rank_by_specialization();
+    assert_eq!(ranked[0].contributor_id, "specialist");
+    assert_eq!(RankingCriteria::Specialization.to_string(), "Specialization");
+}
+
+#[test]
+fn test_w3c_prov_export() {
+    let mut meta = MetaAgent::new("prov_user", "test_backend");
+    meta.log_event(AgentType::Retrieval, "find papers", "3 papers", "en", 0.8).unwrap();
+    meta.log_event(AgentType::Reasoning, "compare", "paper \"2\" is strongest", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Synthesis, "summarize", "paper 2 wins", "en", 0.95).unwrap();
+
+    let prov_n = meta.export_w3c_prov_n();
+    assert!(prov_n.starts_with("document\n"));
+    assert!(prov_n.ends_with("endDocument\n"));
+    assert!(prov_n.contains(&format!("bundle qlg:{}\n", meta.session_id)));
+    let agent_line = prov_n.lines().find(|l| l.trim_start().starts_with("agent(")).unwrap();
+    assert!(agent_line.contains("prov_user"));
+    assert_eq!(prov_n.matches("wasGeneratedBy(").count(), 3);
+    assert_eq!(prov_n.matches("wasInfluencedBy(").count(), meta.get_transition_count());
+    assert!(prov_n.contains("wasInfluencedBy(qlg:activity_1, qlg:activity_0"));
+    assert!(prov_n.contains(r#"prov:value="paper \"2\" is strongest""#));
+
+    let prov_json: serde_json::Value = serde_json::from_str(&meta.export_w3c_prov_json().unwrap()).unwrap();
+    let bundle = &prov_json["bundle"][format!("qlg:{}", meta.session_id)];
+    assert_eq!(bundle["agent"]["qlg:contributor_prov_user"]["prov:label"], "prov_user");
+    assert_eq!(bundle["activity"].as_object().unwrap().len(), 3);
+    assert_eq!(bundle["wasInfluencedBy"]["_:wINF1"]["prov:influencee"], "qlg:activity_2");
+}
+
+#[test]
+fn test_rank_by_recent_improvement_rate() {
+    let mut leaderboard = Leaderboard::new();
+    let mut submit = |contributor: &str, depth: usize| {
+        let mut meta = MetaAgent::new(contributor, "test_backend");
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        let mut provenance = meta.emit_provenance();
+        provenance.trace_depth = depth;
+        leaderboard.add_entry(provenance, vec!["en".to_string()]);
+    };
+
+    for depth in [1, 2, 3, 4, 5, 6] {
+        submit("rising", depth);
+    }
+    for depth in [6, 5, 4, 3, 2, 1] {
+        submit("declining", depth);
+    }
+    for depth in [1, 50, 100] {
+        submit("newcomer", depth);
+    }
+
+    let ranked = leaderboard.rank_by_recent_improvement_rate();
+    let order: Vec<&str> = ranked.iter().map(|s| s.contributor_id.as_str()).collect();
+    assert_eq!(order, vec!["rising", "newcomer", "declining"]);
+    assert!((ranked[0].recent_improvement_rate - 3.0).abs() < 1e-9);
+    assert_eq!(ranked[1].recent_improvement_rate, 0.0);
+    assert!((ranked[2].recent_improvement_rate + 3.0).abs() < 1e-9);
+    assert_eq!(RankingCriteria::RecentImprovement.to_string(), "Recent Improvement");
+}
+
+#[cfg(feature = "jsonschema")]
+#[test]
+fn test_schema_validation_rejects_low_confidence() {
+    use quantum_limit_graph::level5::{LogEventError, SchemaValidationError};
+
+    let schema = serde_json::json!({
+        "type": "object",
+        "properties": { "confidence": { "type": "number", "minimum": 0.5 } },
+        "required": ["confidence"],
+    });
+    let mut meta = MetaAgent::new("schema_user", "test_backend").with_schema_validation(schema);
+
+    meta.log_event(AgentType::Retrieval, "find papers", "3 papers", "en", 0.9).unwrap();
+    let result = meta.log_event(AgentType::Reasoning, "guess", "maybe", "en", 0.3);
+    assert!(matches!(result, Err(SchemaValidationError::Violation(_))));
+    assert_eq!(meta.get_trace_depth(), 1);
+    assert_eq!(meta.get_transition_count(), 0);
+
+    // Events with metadata report the rejection too
+    let result = meta.log_event_with_metadata(AgentType::Reasoning, "guess", "maybe", "en", 0.3, HashMap::new());
+    assert!(matches!(result, Err(LogEventError::Schema(SchemaValidationError::Violation(_)))));
+    assert_eq!(meta.get_trace_depth(), 1);
+
+    let mut invalid = MetaAgent::new("schema_user", "test_backend").with_schema_validation(serde_json::json!({ "type": 12 }));
+    let result = invalid.log_event(AgentType::Reasoning, "find papers", "3 papers", "en", 0.9);
+    assert!(matches!(result, Err(SchemaValidationError::InvalidSchema(_))));
+    assert_eq!(invalid.get_trace_depth(), 0);
+
+    // Without a schema any confidence is accepted
+    let mut plain = MetaAgent::new("plain_user", "test_backend");
+    assert!(plain.log_event(AgentType::Reasoning, "guess", "maybe", "en", 0.3).is_ok());
+}
+
+#[test]
+fn test_generate_cohort_report() {
+    let day = |n: i64| chrono::DateTime::from_timestamp(n * 86_400, 0).unwrap();
+    let mut leaderboard = Leaderboard::new();
+    let mut submit = |contributor: &str, on_day: i64, depth: usize| {
+        let mut meta = MetaAgent::new(contributor, "test_backend");
+        meta.log_event(AgentType::Reasoning, "input", "output", "en", 0.9).unwrap();
+        let mut provenance = meta.emit_provenance();
+        provenance.timestamp = day(on_day);
+        provenance.trace_depth = depth;
+        leaderboard.add_entry(provenance, vec!["en".to_string()]);
+    };
+
+    // 30-day windows aligned to the epoch: [19710, 19740) and [19740, 19770)
+    submit("alice", 19711, 10);
+    submit("bob", 19720, 4);
+    submit("carol", 19742, 6);
+    // alice returns during the latest window without changing cohort
+    submit("alice", 19745, 12);
+
+    let report = leaderboard.generate_cohort_report(30);
+    assert_eq!(report.len(), 2);
+
+    assert_eq!(report[0].cohort_id, "2023-12-19");
+    assert_eq!(report[0].join_window_start, day(19710));
+    assert_eq!(report[0].join_window_end, day(19740));
+    assert_eq!(report[0].contributor_count, 2);
+    assert!((report[0].mean_trace_depth - 8.0).abs() < 1e-9);
+    assert!((report[0].retention_rate - 0.5).abs() < 1e-9);
+    assert_eq!(report[0].top_contributor_id, "alice");
+
+    assert_eq!(report[1].cohort_id, "2024-01-18");
+    assert_eq!(report[1].contributor_count, 1);
+    assert_eq!(report[1].retention_rate, 1.0);
+    assert_eq!(report[1].top_contributor_id, "carol");
+
+    assert!(Leaderboard::new().generate_cohort_report(30).is_empty());
+}
+
+#[test]
+fn test_align_against_reference() {
+    let mut meta = MetaAgent::new("align_user", "test_backend");
+    meta.log_event(AgentType::Classification, "label", "physics", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Reasoning, "analyze", "derivation", "en", 0.9).unwrap();
+    meta.log_event(AgentType::Synthesis, "summarize", "result", "en", 0.9).unwrap();
+
+    let reference = [AgentType::Classification, AgentType::Reasoning, AgentType::Validation, AgentType::Synthesis];
+    let result = meta.align_against_reference(&reference);
+    assert_eq!(result.edit_distance, 1);
+    // Three matches and one gap: (3 - 0.5) / 4
+    assert!((result.alignment_score - 0.625).abs() < 1e-9);
+    assert_eq!(
+        result.aligned_actual,
+        vec![Some(AgentType::Classification), Some(AgentType::Reasoning), None, Some(AgentType::Synthesis)]
+    );
+    assert_eq!(result.aligned_reference, reference.iter().cloned().map(Some).collect::<Vec<_>>());
+
+    let exact = meta.align_against_reference(&[AgentType::Classification, AgentType::Reasoning, AgentType::Synthesis]);
+    assert_eq!(exact.edit_distance, 0);
+    assert!((exact.alignment_score - 1.0).abs() < 1e-9);
+}
+
+/// Needs a Postgres server; `#[sqlx::test]` creates a scratch database per run:
+///
+/// ```text
+/// DATABASE_URL=postgres://postgres@localhost/postgres cargo test --features postgres -- --ignored postgres
+/// ```
+#[cfg(feature = "postgres")]
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a Postgres server at DATABASE_URL"]
+async fn test_leaderboard_postgres_round_trip(pool: sqlx::PgPool) {
+    Leaderboard::migrate_postgres(&pool).await.unwrap();
+    let mut leaderboard = Leaderboard::new();
+
+    for (user, langs) in [("user1", vec!["en", "id"]), ("user2", vec!["zh"])] {
+        let mut meta = MetaAgent::new(user, "backend1");
+        meta.log_event(AgentType::Classification, "input", "output", langs[0], 0.9).unwrap();
+        meta.log_event(AgentType::Reasoning, "input", "output", langs[0], 0.7).unwrap();
+        leaderboard.add_entry(meta.emit_provenance(), langs.iter().map(|l| l.to_string()).collect());
+    }
+    leaderboard.export_to_postgres(&pool).await.unwrap();
+
+    // A second export upserts the changed row instead of conflicting
+    let mut meta = MetaAgent::new("user1", "backend2");
+    meta.log_event(AgentType::Synthesis, "input", "output", "en", 0.8).unwrap();
+    leaderboard.add_entry(meta.emit_provenance(), vec!["en".to_string(), "ar".to_string()]);
+    leaderboard.export_to_postgres(&pool).await.unwrap();
+
+    let loaded = Leaderboard::load_from_postgres(&pool).await.unwrap();
+    assert_eq!(loaded.total_contributors(), leaderboard.total_contributors());
+
+    for (id, original) in &leaderboard.entries {
+        let restored = &loaded.entries[id];
+        assert_eq!(restored.total_submissions, original.total_submissions);
+        assert_eq!(restored.trace_depth, original.trace_depth);
+        assert_eq!(restored.weighted_trace_depth, original.weighted_trace_depth);
+        assert_eq!(restored.avg_trace_depth, original.avg_trace_depth);
+        assert_eq!(restored.uniqueness_score, original.uniqueness_score);
+        assert_eq!(restored.languages_used, original.languages_used);
+        assert_eq!(restored.backend_used, original.backend_used);
+        assert_eq!(restored.provenance_hash, original.provenance_hash);
+        assert_eq!(restored.combined_score, original.combined_score);
+        assert_eq!(restored.rank, original.rank);
+        assert_eq!(restored.normalized_submission_rate, original.normalized_submission_rate);
+        assert_eq!(restored.reasoning_entropy, original.reasoning_entropy);
+        assert_eq!(restored.total_estimated_tokens, original.total_estimated_tokens);
+        assert_eq!(restored.deleted, original.deleted);
+        assert_eq!(restored.confidence_profile, original.confidence_profile);
+        assert_eq!(restored.improvement_score, original.improvement_score);
+        assert_eq!(restored.recent_improvement_rate, original.recent_improvement_rate);
+        assert_eq!(restored.streak, original.streak);
+        assert_eq!(restored.output_diversity_score, original.output_diversity_score);
+        assert_eq!(restored.percentile_rank, original.percentile_rank);
+        assert_eq!(restored.network_centrality, original.network_centrality);
+        assert_eq!(restored.profile.specialization_index, original.profile.specialization_index);
+        assert_eq!(restored.last_updated.timestamp_micros(), original.last_updated.timestamp_micros());
+    }
+}
+
+#[test]
+fn test_cost_model_estimates_event_cost() {
+    use quantum_limit_graph::level5::CostModel;
+
+    // 3 input words -> 4 tokens, 6 output words -> 8 tokens
+    let mut meta = MetaAgent::new("test_user", "gpt-4").with_cost_model(CostModel::custom(1.0, 2.0));
+    meta.log_event(AgentType::Reasoning, "one two three", "a b c d e f", "en", 0.9).unwrap();
+    assert!((meta.trace[0].estimated_cost_usd - 0.02).abs() < 1e-12);
+
+    // 0 input tokens, 4 output tokens
+    meta.log_event(AgentType::Synthesis, "", "w x y", "en", 0.8).unwrap();
+    assert!((meta.trace[1].estimated_cost_usd - 0.008).abs() < 1e-12);
+    assert!((meta.total_estimated_cost_usd() - 0.028).abs() < 1e-12);
+    assert!((meta.fold_memory().total_cost_usd - 0.028).abs() < 1e-12);
+    assert!((meta.generate_trace_report().total_cost_usd - 0.028).abs() < 1e-12);
+
+    let gpt4 = CostModel::openai_gpt4();
+    assert_eq!(gpt4.input_cost_per_1k_tokens, 0.03);
+    assert_eq!(gpt4.output_cost_per_1k_tokens, 0.06);
+    assert!((gpt4.estimate_cost(&meta.trace[0]) - 0.0006).abs() < 1e-12);
+
+    // A replay reprices only the events whose text changed: 3 output words -> 4 tokens
+    let replay = meta.replay_with_modification(|i, mut event| {
+        if i == 0 {
+            event.output = "a b c".to_string();
+        }
+        event
+    });
+    assert!((replay.trace[0].estimated_cost_usd - 0.012).abs() < 1e-12);
+    assert_eq!(replay.trace[1].estimated_cost_usd, meta.trace[1].estimated_cost_usd);
+
+    // Folds add the cost of appended events
+    let mut fold = meta.fold_memory();
+    meta.log_event(AgentType::Validation, "", "ok", "en", 0.9).unwrap();
+    fold.apply_delta(&meta.trace[2..]);
+    assert!((fold.total_cost_usd - meta.total_estimated_cost_usd()).abs() < 1e-12);
+
+    let mut unpriced = MetaAgent::new("test_user", "gpt-4");
+    unpriced.log_event(AgentType::Reasoning, "one two three", "a b c d e f", "en", 0.9).unwrap();
+    assert_eq!(unpriced.total_estimated_cost_usd(), 0.0);
+    assert_eq!(unpriced.trace[0].estimated_cost_usd, 0.0);
+}
+
+#[test]
+fn test_memory_fold_language_sequence_keeps_first_appearances() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    for lang in ["en", "en", "id", "en", "id", "zh"] {
+        meta.log_event(AgentType::Reasoning, "input", "output", lang, 0.9).unwrap();
+    }
+
+    let folded = meta.fold_memory();
+    assert_eq!(folded.language_sequence, vec!["en", "id", "zh"]);
+    assert_eq!(folded.language_transition_count, 4);
+
+    let empty = MetaAgent::new("test_user", "test_backend").fold_memory();
+    assert!(empty.language_sequence.is_empty());
+    assert_eq!(empty.language_transition_count, 0);
+}
+
+#[test]
+fn test_replay_with_modification() {
+    let mut meta = MetaAgent::new("test_user", "test_backend");
+    let agents = [AgentType::Classification, AgentType::Reasoning, AgentType::Retrieval];
+    for i in 0..6 {
+        meta.log_event(agents[i % 3].clone(), "input", "output", "en", 0.4).unwrap();
+    }
+
+    let replay = meta.replay_with_modification(|i, mut event| {
+        if i == 3 {
+            event.confidence *= 2.0;
+        }
+        if i == 5 {
+            event.agent = AgentType::Reasoning;
+        }
+        event
+    });
+
+    assert_eq!(replay.session_id, format!("replay_{}", meta.session_id));
+    assert_eq!(replay.trace.len(), 6);
+    assert!((replay.trace[3].confidence - 0.8).abs() < 1e-12);
+    assert_eq!(meta.trace[3].confidence, 0.4);
+    assert_eq!(replay.trace[2].confidence, 0.4);
+
+    // Making event 5 Reasoning removes the final Reasoning -> Retrieval transition
+    assert_eq!(meta.get_transition_count(), 5);
+    assert_eq!(replay.get_transition_count(), 4);
+    assert_eq!(replay.transitions[3].from_agent, AgentType::Classification);
+    assert_eq!(replay.transitions[3].to_agent, AgentType::Reasoning);
+    assert_eq!(replay.current_agent, Some(AgentType::Reasoning));
+}
+
+#[test]
+fn test_network_centrality_counts_referencing_contributors() {
+    let session = |user: &str, input: &str| {
+        let mut meta = MetaAgent::new(user, "test_backend");
+        meta.log_event(AgentType::Reasoning, input, "output", "en", 0.9).unwrap();
+        meta.emit_provenance()
+    };
+
+    // C builds on B, which builds on A
+    let a = session("alice", "base question");
+    let b = session("bob", "follow-up").with_parent(&a);
+    let c = session("carol", "refinement").with_parent(&b);
+
+    let mut leaderboard = Leaderboard::new();
+    leaderboard.add_entry(a.clone(), vec!["en".to_string()]);
+    leaderboard.add_entry(b, vec!["en".to_string()]);
+    leaderboard.add_entry(c, vec!["en".to_string()]);
+
+    // In-degrees: alice 1 (from bob), bob 1 (from carol), carol 0; normalized by 3 - 1
+    let centrality = leaderboard.compute_network_centrality();
+    assert_eq!(centrality["alice"], 0.5);
+    assert_eq!(centrality["bob"], 0.5);
+    assert_eq!(centrality["carol"], 0.0);
+    assert_eq!(leaderboard.entries["bob"].network_centrality, 0.5);
+    assert_eq!(leaderboard.rank_by_network_centrality().last().unwrap().contributor_id, "carol");
+
+    // carol citing alice adds a second referrer; alice citing an own earlier session is ignored
+    leaderboard.add_entry(session("carol", "second pass").with_parent(&a), vec!["en".to_string()]);
+    leaderboard.add_entry(session("alice", "revisit").with_parent(&a), vec!["en".to_string()]);
+    assert_eq!(leaderboard.entries["alice"].network_centrality, 1.0);
+    assert_eq!(leaderboard.entries["carol"].network_centrality, 0.0);
+
+    // A reference submitted before the session it cites still counts once that session arrives
+    let d = session("dave", "late upload");
+    leaderboard.add_entry(session("erin", "early citation").with_parent(&d), vec!["en".to_string()]);
+    leaderboard.add_entry(d, vec!["en".to_string()]);
+    assert_eq!(leaderboard.entries["dave"].network_centrality, 0.25);
+    assert_eq!(leaderboard.entries["alice"].network_centrality, 0.5);
+    assert_eq!(format!("{}", RankingCriteria::NetworkCentrality), "Network Centrality");
+
+    // JSON Lines keeps no references, so loaded centrality stands until the next submission
+    let dump = leaderboard.export_jsonlines().unwrap();
+    let mut restored = Leaderboard::load_from_jsonlines(std::io::Cursor::new(dump.as_bytes())).unwrap();
+    for (id, stats) in &leaderboard.entries {
+        assert_eq!(restored.entries[id].network_centrality, stats.network_centrality);
+    }
+    restored.add_entry(session("frank", "new work").with_parent(&a), vec!["en".to_string()]);
+    assert_eq!(restored.entries["dave"].network_centrality, 0.0);
+}